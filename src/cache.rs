@@ -1,35 +1,327 @@
-use crate::utils::Facets;
-use clingo::Symbol;
+use crate::navigator::NavigatorError;
+use crate::translator::Atom;
+use crate::utils::{Facets, Repr};
+use clingo::{Literal, Symbol};
 use lru::{DefaultHasher, LruCache};
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-lazy_static::lazy_static! {
-    pub static ref CACHE: Arc<Mutex<Cache>> = Arc::new(Mutex::new(Cache::new(1_000)));
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+/// Stable 64 bit FNV-1a hash of `parts`, identifying a program together with its options.
+pub fn fingerprint<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+    parts.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, part| {
+        part.bytes()
+            .chain(std::iter::once(0))
+            .fold(hash, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+    })
+}
+
+/// File the cache of the program identified by `fingerprint` is persisted to within `dir`.
+pub fn file(dir: &Path, fingerprint: u64) -> PathBuf {
+    dir.join(format!("{:016x}.json", fingerprint))
 }
 
+/// Canonical cache key: the set of assumed literals, sorted and deduplicated, such that
+/// permutations of a route hit the same entry, together with the query memoized under them,
+/// e.g., the mode and weight of a filter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Key {
+    pub query: String,
+    pub literals: Vec<i32>,
+}
+impl Key {
+    pub fn new(query: impl Into<String>, literals: &[Literal]) -> Self {
+        let mut literals = literals
+            .iter()
+            .map(|l| l.get_integer())
+            .collect::<Vec<i32>>();
+        literals.sort_unstable();
+        literals.dedup();
+
+        Self {
+            query: query.into(),
+            literals,
+        }
+    }
+}
+
+/// LRU cache that tallies hits and misses of lookups.
 #[derive(Debug)]
+pub struct Table<K: Hash + Eq, V> {
+    entries: LruCache<K, V>,
+    hits: usize,
+    misses: usize,
+}
+impl<K: Hash + Eq, V> Table<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::with_hasher(capacity, DefaultHasher::default()),
+            hits: 0,
+            misses: 0,
+        }
+    }
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let value = self.entries.get(key);
+        match value.is_some() {
+            true => self.hits += 1,
+            _ => self.misses += 1,
+        }
+
+        value
+    }
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.entries.put(key, value)
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn capacity(&self) -> usize {
+        self.entries.cap()
+    }
+    /// Drops the least recently used entries beyond `capacity`.
+    fn resize(&mut self, capacity: usize) {
+        self.entries.resize(capacity)
+    }
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+    /// Entries in `f`'s representation, from least to most recently used.
+    fn dump<W>(&self, f: impl Fn(&V) -> W) -> Vec<(K, W)>
+    where
+        K: Clone,
+    {
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|(k, v)| (k.clone(), f(v)))
+            .collect::<Vec<_>>();
+        entries.reverse();
+
+        entries
+    }
+    /// Puts every entry `f` can restore and returns their number.
+    fn restore<W>(&mut self, entries: Vec<(K, W)>, f: impl Fn(W) -> Option<V>) -> usize {
+        let mut n = 0;
+        for (k, w) in entries {
+            if let Some(v) = f(w) {
+                self.put(k, v);
+                n += 1;
+            }
+        }
+
+        n
+    }
+}
+impl<K: Hash + Eq + Clone, V: Clone> Clone for Table<K, V> {
+    fn clone(&self) -> Self {
+        let mut table = Self::new(self.capacity());
+        table.restore(self.dump(|v| v.clone()), Some);
+        table.hits = self.hits;
+        table.misses = self.misses;
+
+        table
+    }
+}
+
+/// Number of cached entries and lookups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+impl CacheStats {
+    /// Fraction of lookups that were hits.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0f64,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+impl std::ops::Add for CacheStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            entries: self.entries + other.entries,
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+        }
+    }
+}
+
+/// Maximal number of entries per cache table, e.g., `500` for every table or
+/// `facets=500,counts=100`, which keeps the default for the remaining tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheCapacity {
+    pub facets: usize,
+    pub filtered: usize,
+    pub counts: usize,
+    pub consequences: usize,
+}
+impl Default for CacheCapacity {
+    fn default() -> Self {
+        Self {
+            facets: 1_000,
+            filtered: 1_000,
+            counts: 1_000,
+            consequences: 1_000,
+        }
+    }
+}
+impl FromStr for CacheCapacity {
+    type Err = NavigatorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |n: &str| match n.trim().parse::<usize>() {
+            Ok(0) => Err(NavigatorError::InvalidInput(
+                "cache capacities must be positive.".to_owned(),
+            )),
+            n => n.map_err(|e| NavigatorError::Parse(e.to_string())),
+        };
+
+        if !s.contains('=') {
+            let n = parse(s)?;
+            return Ok(Self {
+                facets: n,
+                filtered: n,
+                counts: n,
+                consequences: n,
+            });
+        }
+
+        let mut capacity = Self::default();
+        for table in s.split(',') {
+            match table.split_once('=') {
+                Some(("facets", n)) => capacity.facets = parse(n)?,
+                Some(("filtered", n)) => capacity.filtered = parse(n)?,
+                Some(("counts", n)) => capacity.counts = parse(n)?,
+                Some(("consequences", n)) => capacity.consequences = parse(n)?,
+                _ => {
+                    return Err(NavigatorError::InvalidInput(format!(
+                        "unknown cache table {:?}; use facets, filtered, counts or consequences.",
+                        table
+                    )))
+                }
+            }
+        }
+
+        Ok(capacity)
+    }
+}
+
+/// Persisted form of a [`Cache`]; symbols are stored by their string representation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    inclusive_facets: Vec<(Key, Vec<String>)>,
+    filtered: Vec<(Key, Vec<String>)>,
+    counts: Vec<(Key, usize)>,
+    consequences: Vec<(Key, Vec<String>)>,
+}
+
+fn strings(symbols: &[Symbol]) -> Vec<String> {
+    symbols.iter().map(|s| s.repr()).collect()
+}
+
+fn symbols(strings: Vec<String>) -> Option<Vec<Symbol>> {
+    strings.iter().map(|s| Atom(s).parse(&[])).collect()
+}
+
+/// Memoized query results of a single navigator.
+#[derive(Debug, Clone)]
 pub struct Cache {
-    pub inclusive_facets: LruCache<Vec<i32>, Facets>,
-    pub max_as_facets: LruCache<String, Vec<String>>,
-    pub min_as_facets: LruCache<String, Vec<String>>,
-    pub max_fc_facets: LruCache<String, Vec<String>>,
-    pub min_fc_facets: LruCache<String, Vec<String>>,
-    pub as_counts: LruCache<String, usize>,
-    pub cautious_consequences: LruCache<Vec<i32>, Vec<Symbol>>,
-    pub brave_consequences: LruCache<Vec<i32>, Vec<Symbol>>,
+    /// facets under a route
+    pub inclusive_facets: Table<Key, Facets>,
+    /// facets a mode filters under a route
+    pub filtered: Table<Key, Vec<String>>,
+    /// answer set counts under a route and counting mode
+    pub counts: Table<Key, usize>,
+    /// brave or cautious consequences under a route
+    pub consequences: Table<Key, Vec<Symbol>>,
 }
 impl Cache {
-    pub fn new(capacity: usize) -> Self {
+    pub fn new(capacity: CacheCapacity) -> Self {
         Self {
-            inclusive_facets: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            max_as_facets: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            min_as_facets: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            max_fc_facets: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            min_fc_facets: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            as_counts: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            cautious_consequences: LruCache::with_hasher(capacity, DefaultHasher::default()),
-            brave_consequences: LruCache::with_hasher(capacity, DefaultHasher::default()),
+            inclusive_facets: Table::new(capacity.facets),
+            filtered: Table::new(capacity.filtered),
+            counts: Table::new(capacity.counts),
+            consequences: Table::new(capacity.consequences),
+        }
+    }
+    pub fn capacity(&self) -> CacheCapacity {
+        CacheCapacity {
+            facets: self.inclusive_facets.capacity(),
+            filtered: self.filtered.capacity(),
+            counts: self.counts.capacity(),
+            consequences: self.consequences.capacity(),
+        }
+    }
+    /// Resizes the tables, keeping their most recently used entries.
+    pub fn set_capacity(&mut self, capacity: CacheCapacity) {
+        self.inclusive_facets.resize(capacity.facets);
+        self.filtered.resize(capacity.filtered);
+        self.counts.resize(capacity.counts);
+        self.consequences.resize(capacity.consequences);
+    }
+    pub fn clear(&mut self) {
+        self.inclusive_facets.clear();
+        self.filtered.clear();
+        self.counts.clear();
+        self.consequences.clear();
+    }
+    pub fn stats(&self) -> CacheStats {
+        self.inclusive_facets.stats()
+            + self.filtered.stats()
+            + self.counts.stats()
+            + self.consequences.stats()
+    }
+    /// Writes all entries to `path` as json.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let snapshot = Snapshot {
+            inclusive_facets: self.inclusive_facets.dump(|fs| strings(&fs.0)),
+            filtered: self.filtered.dump(|fs| fs.clone()),
+            counts: self.counts.dump(|c| *c),
+            consequences: self.consequences.dump(|cs| strings(cs)),
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
         }
+        let json = serde_json::to_string(&snapshot).map_err(|e| NavigatorError::IO(e.into()))?;
+        std::fs::write(path, json)?;
+
+        Ok(())
+    }
+    /// Restores the entries written to `path` by [`Cache::save`]; returns their number.
+    pub fn load(&mut self, path: &Path) -> Result<usize> {
+        let snapshot: Snapshot = serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| NavigatorError::IO(e.into()))?;
+
+        Ok(self
+            .inclusive_facets
+            .restore(snapshot.inclusive_facets, |fs| symbols(fs).map(Facets))
+            + self.filtered.restore(snapshot.filtered, Some)
+            + self.counts.restore(snapshot.counts, Some)
+            + self.consequences.restore(snapshot.consequences, symbols))
+    }
+}
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(CacheCapacity::default())
     }
 }
 
@@ -37,13 +329,126 @@ impl Cache {
 mod test {
     use super::*;
 
+    fn key(query: &str, literals: &[i32]) -> Key {
+        Key {
+            query: query.to_owned(),
+            literals: literals.to_vec(),
+        }
+    }
+
     #[test]
     fn new() {
-        Cache::new(1_000);
+        let cache = Cache::default();
+        assert_eq!(cache.capacity(), CacheCapacity::default());
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn stats() {
+        let mut cache = Cache::default();
+        cache.set_capacity(CacheCapacity {
+            counts: 2,
+            ..CacheCapacity::default()
+        });
+        assert!(cache.counts.get(&key("", &[])).is_none());
+        cache.counts.put(key("", &[]), 3);
+        cache.counts.put(key("", &[1]), 2);
+        cache.counts.put(key("", &[2]), 1);
+        assert_eq!(cache.counts.get(&key("", &[2])), Some(&1));
+        assert!(cache.counts.get(&key("", &[])).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(
+            stats,
+            CacheStats {
+                entries: 2,
+                hits: 1,
+                misses: 2
+            }
+        );
+        assert!((stats.hit_rate() - 1f64 / 3f64).abs() < 1e-6);
+
+        // clones keep entries and recency
+        let mut clone = cache.clone();
+        clone.set_capacity(CacheCapacity {
+            counts: 1,
+            ..CacheCapacity::default()
+        });
+        assert_eq!(clone.counts.get(&key("", &[2])), Some(&1));
+
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn capacity() -> Result<()> {
+        let all = "500".parse::<CacheCapacity>()?;
+        assert_eq!(
+            (all.facets, all.filtered, all.counts, all.consequences),
+            (500, 500, 500, 500)
+        );
+
+        let some = "counts=10,filtered=20".parse::<CacheCapacity>()?;
+        assert_eq!((some.counts, some.filtered), (10, 20));
+        assert_eq!(some.facets, CacheCapacity::default().facets);
+
+        assert!("0".parse::<CacheCapacity>().is_err());
+        assert!("counts=x".parse::<CacheCapacity>().is_err());
+        assert!("routes=3".parse::<CacheCapacity>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_load() -> Result<()> {
+        let a = Atom("a").parse(&[]).expect("parsing atom failed.");
+        let p = Atom("p(1,x)").parse(&[]).expect("parsing atom failed.");
+
+        let mut cache = Cache::default();
+        cache.inclusive_facets.put(key("", &[]), Facets(vec![a, p]));
+        cache
+            .inclusive_facets
+            .put(key("", &[-2, 1]), Facets(vec![p]));
+        cache.counts.put(key("Exact", &[1]), 42);
+        cache
+            .filtered
+            .put(key("absolute explore mode", &[]), vec!["~a".to_owned()]);
+        cache.consequences.put(key("Brave", &[3]), vec![a]);
+
+        let path = std::env::temp_dir()
+            .join(format!("fasb-cache-{}", std::process::id()))
+            .join(file(Path::new(""), fingerprint(["a;b.", "-c n=1"])));
+        cache.save(&path)?;
+
+        let mut restored = Cache::default();
+        assert_eq!(restored.load(&path)?, 5);
+        assert_eq!(
+            restored.inclusive_facets.get(&key("", &[])),
+            Some(&Facets(vec![a, p]))
+        );
+        assert_eq!(
+            restored.inclusive_facets.get(&key("", &[-2, 1])),
+            Some(&Facets(vec![p]))
+        );
+        assert_eq!(restored.counts.get(&key("Exact", &[1])), Some(&42));
+        assert_eq!(
+            restored.filtered.get(&key("absolute explore mode", &[])),
+            Some(&vec!["~a".to_owned()])
+        );
+        assert_eq!(
+            restored.consequences.get(&key("Brave", &[3])),
+            Some(&vec![a])
+        );
+
+        std::fs::remove_dir_all(path.parent().expect("no parent directory."))?;
+
+        Ok(())
     }
 
     #[test]
-    fn cache() {
-        assert!(CACHE.lock().is_ok());
+    fn fingerprint_t() {
+        assert_eq!(fingerprint(["a.", "-c n=1"]), fingerprint(["a.", "-c n=1"]));
+        assert_ne!(fingerprint(["a.", "-c n=1"]), fingerprint(["a.-c", " n=1"]));
+        assert_ne!(fingerprint(["a."]), fingerprint(["b."]));
     }
 }
@@ -1,17 +1,27 @@
 use std::fmt::Display;
 use std::time::Instant;
 
-use clingo::{Literal, Symbol};
+use clingo::Literal;
 use hashbrown::HashMap;
 use rand::seq::SliceRandom;
+use serde_json::json;
 
-use crate::asnc::AsnC;
+use crate::cache::CacheCapacity;
 use crate::config::CONFIG;
-use crate::navigator::{filter, GoalOrientedNavigation, Mode, Navigator, Weight};
-use crate::soe::{Cover, Sampler};
-use crate::utils::{Repr, Route, ToSymbol};
+use crate::cover::{Cover, Search};
+use crate::editor;
+use crate::incidences::{Incidences, Table};
+use crate::navigator::{
+    Counting, Frequencies, GoalOrientedNavigation, Mode, Navigator, NavigatorError, Optimization,
+    Projection, Weight,
+};
+use crate::output::{self, text, textln, Format};
+use crate::serve;
+use crate::session::Session;
+use crate::utils::{Repr, Route};
 
 pub type Input<'a> = std::str::SplitWhitespace<'a>;
+type Result<T> = std::result::Result<T, NavigatorError>;
 
 pub fn parse_mode(input: (Option<&str>, Option<&str>)) -> Option<Mode> {
     match input {
@@ -45,75 +55,527 @@ pub fn parse_mode(input: (Option<&str>, Option<&str>)) -> Option<Mode> {
         | (Some("--expl"), Some("--facet-counting"))
         | (Some("--expl"), Some("--fc"))
         | (Some("--expl"), None) => Some(Mode::Explore(Weight::FacetCounting)),
-        (Some("--!"), Some(s)) => Some(Mode::Io(
-            s[2..].parse::<u8>().expect("error: invalid command."),
-        )),
         (Some("--go"), Some("--U")) => Some(Mode::GoalOriented(Weight::Information)),
         (Some("--sgo"), Some("--U")) => Some(Mode::StrictlyGoalOriented(Weight::Information)),
         (Some("--expl"), Some("--U")) => Some(Mode::Explore(Weight::Information)),
+        (Some("--goal-oriented"), Some("--entropy"))
+        | (Some("--goal-oriented"), Some("--ent"))
+        | (Some("--go"), Some("--entropy"))
+        | (Some("--go"), Some("--ent"))
+        | (Some("--ent"), None) => Some(Mode::GoalOriented(Weight::Entropy)),
+        (Some("--strictly-goal-oriented"), Some("--entropy"))
+        | (Some("--strictly-goal-oriented"), Some("--ent"))
+        | (Some("--sgo"), Some("--entropy"))
+        | (Some("--sgo"), Some("--ent")) => Some(Mode::StrictlyGoalOriented(Weight::Entropy)),
+        (Some("--explore"), Some("--entropy"))
+        | (Some("--explore"), Some("--ent"))
+        | (Some("--expl"), Some("--entropy"))
+        | (Some("--expl"), Some("--ent")) => Some(Mode::Explore(Weight::Entropy)),
+        (Some("--goal-oriented"), Some("--information-gain"))
+        | (Some("--goal-oriented"), Some("--ig"))
+        | (Some("--go"), Some("--information-gain"))
+        | (Some("--go"), Some("--ig"))
+        | (Some("--ig"), None) => Some(Mode::GoalOriented(Weight::InformationGain)),
+        (Some("--strictly-goal-oriented"), Some("--information-gain"))
+        | (Some("--strictly-goal-oriented"), Some("--ig"))
+        | (Some("--sgo"), Some("--information-gain"))
+        | (Some("--sgo"), Some("--ig")) => {
+            Some(Mode::StrictlyGoalOriented(Weight::InformationGain))
+        }
+        (Some("--explore"), Some("--information-gain"))
+        | (Some("--explore"), Some("--ig"))
+        | (Some("--expl"), Some("--information-gain"))
+        | (Some("--expl"), Some("--ig")) => Some(Mode::Explore(Weight::InformationGain)),
+        (Some(mode), Some(weight)) if weight.len() > 9 && weight.starts_with("--weight=") => {
+            let weight = Weight::Declared(weight[9..].to_owned());
+            match mode {
+                "--goal-oriented" | "--go" => Some(Mode::GoalOriented(weight)),
+                "--strictly-goal-oriented" | "--sgo" => Some(Mode::StrictlyGoalOriented(weight)),
+                "--explore" | "--expl" => Some(Mode::Explore(weight)),
+                _ => None,
+            }
+        }
         _ => None,
     }
 }
 
-pub fn parse_args(args: impl Iterator<Item = String>) -> Option<(Mode, usize)> {
-    let (n_p, xs_p): (Vec<String>, Vec<String>) = args.partition(|s| s[2..].starts_with('n'));
-    let n = n_p
-        .get(0)
-        .and_then(|s| s[4..].parse::<usize>().ok())
-        .unwrap_or(3);
+const FASB_FLAGS: [&str; 16] = [
+    "--goal-oriented",
+    "--go",
+    "--strictly-goal-oriented",
+    "--sgo",
+    "--explore",
+    "--expl",
+    "--absolute",
+    "--abs",
+    "--facet-counting",
+    "--fc",
+    "--facet--counting",
+    "--U",
+    "--entropy",
+    "--ent",
+    "--information-gain",
+    "--ig",
+];
+
+/// Clingo options that may take their value as the next argument, e.g., `--opt-mode optN`;
+/// values of other options have to be attached, e.g., `--stats=2`.
+const CLINGO_VALUE_OPTIONS: [&str; 18] = [
+    "-c",
+    "--const",
+    "-t",
+    "--parallel-mode",
+    "-n",
+    "--models",
+    "--opt-mode",
+    "--opt-strategy",
+    "--opt-usc",
+    "--configuration",
+    "--enum-mode",
+    "--seed",
+    "--time-limit",
+    "--solve-limit",
+    "--sign-def",
+    "--restarts",
+    "--deletion",
+    "--outf",
+];
+
+/// Operation run once on the program and route given on the command line, e.g.,
+/// `fasb weights prog.lp --mode sgo --weight fc`, instead of navigating interactively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subcommand {
+    Facets,
+    Count,
+    Weights,
+    Zooms,
+    AnswerSets,
+    /// k-greedy sample, or the sample of the provided heuristic, i.e., `ediv` or `erep`
+    Sample(Option<String>),
+    Components,
+    /// HTTP server on the provided port, see `--port`
+    Serve(u16),
+}
+impl std::str::FromStr for Subcommand {
+    type Err = NavigatorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "facets" => Ok(Self::Facets),
+            "count" => Ok(Self::Count),
+            "weights" => Ok(Self::Weights),
+            "zooms" => Ok(Self::Zooms),
+            "answer-sets" => Ok(Self::AnswerSets),
+            "sample" => Ok(Self::Sample(None)),
+            "components" => Ok(Self::Components),
+            "serve" => Ok(Self::Serve(serve::DEFAULT_PORT)),
+            _ => Err(NavigatorError::Parse(s.to_owned())),
+        }
+    }
+}
+impl std::fmt::Display for Subcommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Facets => write!(f, "facets"),
+            Self::Count => write!(f, "count"),
+            Self::Weights => write!(f, "weights"),
+            Self::Zooms => write!(f, "zooms"),
+            Self::AnswerSets => write!(f, "answer-sets"),
+            Self::Sample(None) => write!(f, "sample"),
+            Self::Sample(Some(heuristic)) => write!(f, "sample --heuristic {}", heuristic),
+            Self::Components => write!(f, "components"),
+            Self::Serve(port) => write!(f, "serve --port {}", port),
+        }
+    }
+}
+
+/// Flag of the mode given by `--mode`, e.g., `sgo`.
+fn mode_flag(mode: &str) -> String {
+    format!("--{}", mode)
+}
+
+/// Flag of the weight given by `--weight`, e.g., `fc`; unknown names refer to declared weights.
+fn weight_flag(weight: &str) -> String {
+    match FASB_FLAGS.contains(&format!("--{}", weight).as_str()) {
+        true => format!("--{}", weight),
+        _ => format!("--weight={}", weight),
+    }
+}
 
-    let mut xs = xs_p.iter().take(2);
+#[derive(Debug, Clone)]
+pub struct Arguments {
+    pub mode: Mode,
+    pub n: usize,
+    /// program files; `-` reads from stdin
+    pub sources: Vec<String>,
+    /// options passed through to clingo, e.g., `-c n=5` or `--opt-mode=optN`
+    pub clingo_args: Vec<String>,
+    /// `--project` (by `#show`) or `--project=p/2,q/1` (by signatures)
+    pub projection: Option<Projection>,
+    /// `--optimal` or `--cost-bound=3,2`
+    pub optimization: Option<Optimization>,
+    /// `--counting=exact`, `--counting=approx` or `--counting=approx,0.8,0.9`
+    pub counting: Option<Counting>,
+    /// `--cache-dir=DIR` persists the cache into `DIR` across sessions
+    pub cache_dir: Option<String>,
+    /// `--cache-capacity=500` or `--cache-capacity=facets=500,counts=100`
+    pub cache_capacity: Option<CacheCapacity>,
+    /// `--workers N` evaluates facets on `N` program replicas concurrently
+    pub workers: Option<usize>,
+    /// `--session FILE` restores the session saved to `FILE` by `:save`
+    pub session: Option<String>,
+    /// `--script FILE` runs the commands in `FILE` instead of prompting for them
+    pub script: Option<String>,
+    /// `--output json` prints one JSON object per command instead of text
+    pub output: Option<Format>,
+    /// subcommand given as first argument, e.g., `fasb count prog.lp`
+    pub subcommand: Option<Subcommand>,
+    /// `--route "a ~b"` activates the whitespace separated facets on startup
+    pub route: Vec<String>,
+    /// `--tui` browses facets in the terminal UI instead of prompting for commands
+    pub tui: bool,
+    /// `--rpc` answers JSON-RPC requests on stdin instead of prompting for commands
+    pub rpc: bool,
+}
 
+pub fn parse_args(args: impl Iterator<Item = String>) -> Option<Arguments> {
+    let mut n = 3;
+    let (mut flags, mut sources, mut clingo_args) = (vec![], vec![], vec![]);
+    let (mut projection, mut optimization, mut counting) = (None, None, None);
+    let (mut cache_dir, mut cache_capacity, mut workers) = (None, None, None);
+    let (mut session, mut script, mut output) = (None, None, None);
+    let (mut mode, mut weight, mut route) = (None, None, vec![]);
+    let (mut tui, mut rpc) = (false, false);
+
+    let mut args = args.peekable();
+    let mut subcommand = match args.peek().map(|arg| arg.parse::<Subcommand>()) {
+        Some(Ok(subcommand)) => {
+            args.next();
+            Some(subcommand)
+        }
+        _ => None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-" => sources.push(arg),
+            s if s.starts_with("--n=") => n = s[4..].parse::<usize>().ok()?,
+            "--project" => projection = Some(Projection::Show),
+            s if s.starts_with("--project=") => projection = Some(s[10..].parse().ok()?),
+            "--optimal" => optimization = Some(Optimization::Optimal),
+            s if s.starts_with("--cost-bound=") => optimization = Some(s[13..].parse().ok()?),
+            s if s.starts_with("--counting=") => counting = Some(s[11..].parse().ok()?),
+            s if s.starts_with("--cache-dir=") => {
+                cache_dir = Some(s[12..].to_owned()).filter(|d| !d.is_empty())
+            }
+            s if s.starts_with("--cache-capacity=") => cache_capacity = Some(s[17..].parse().ok()?),
+            "--workers" => workers = Some(args.next()?.parse().ok().filter(|w| *w > 0)?),
+            s if s.starts_with("--workers=") => {
+                workers = Some(s[10..].parse().ok().filter(|w| *w > 0)?)
+            }
+            "--mode" => mode = Some(mode_flag(&args.next()?)),
+            "--weight" => weight = Some(weight_flag(&args.next()?)),
+            "--route" => route.extend(args.next()?.split_whitespace().map(|f| f.to_owned())),
+            s if s.starts_with("--route=") => {
+                route.extend(s[8..].split_whitespace().map(|f| f.to_owned()))
+            }
+            "--heuristic" => match &mut subcommand {
+                Some(Subcommand::Sample(heuristic)) => *heuristic = Some(args.next()?),
+                _ => return None,
+            },
+            "--port" => match &mut subcommand {
+                Some(Subcommand::Serve(port)) => *port = args.next()?.parse().ok()?,
+                _ => return None,
+            },
+            "--tui" => tui = true,
+            "--rpc" => rpc = true,
+            "--output" => output = Some(args.next()?.parse().ok()?),
+            s if s.starts_with("--output=") => output = Some(s[9..].parse().ok()?),
+            "--script" => script = Some(args.next()?),
+            s if s.starts_with("--script=") => {
+                script = Some(s[9..].to_owned()).filter(|f| !f.is_empty())
+            }
+            "--session" => session = Some(args.next()?),
+            s if s.starts_with("--session=") => {
+                session = Some(s[10..].to_owned()).filter(|f| !f.is_empty())
+            }
+            s if s.starts_with("--threads=") => {
+                let threads = s[10..].parse::<usize>().ok().filter(|t| *t > 0)?;
+                clingo_args.push(format!("--parallel-mode={}", threads));
+            }
+            s if FASB_FLAGS.contains(&s) || s.starts_with("--weight=") => flags.push(arg),
+            s if CLINGO_VALUE_OPTIONS.contains(&s) => {
+                clingo_args.push(arg);
+                clingo_args.push(args.next()?);
+            }
+            s if s.starts_with('-') => clingo_args.push(arg),
+            _ => sources.push(arg),
+        }
+    }
+
+    // `--mode` and `--weight` precede flags; `--weight` alone is goal-oriented
+    let mode = mode.or_else(|| weight.as_ref().map(|_| mode_flag("go")));
+    let flags = mode
+        .into_iter()
+        .chain(weight)
+        .chain(flags)
+        .collect::<Vec<_>>();
+    let mut xs = flags.iter().take(2);
     let t = (xs.next().map(|s| s.as_ref()), xs.next().map(|s| s.as_ref()));
 
     let mode = parse_mode(t)?;
 
-    Some((mode, n))
+    Some(Arguments {
+        mode,
+        n,
+        sources,
+        clingo_args,
+        projection,
+        optimization,
+        counting,
+        cache_dir,
+        cache_capacity,
+        workers,
+        session,
+        script,
+        output,
+        subcommand,
+        route,
+        tui,
+        rpc,
+    })
+}
+
+/// Runs `subcommand` on the current route of `session`.
+pub fn run_subcommand(session: &mut Session, subcommand: &Subcommand) -> Result<()> {
+    let none = "".split_whitespace();
+    match subcommand {
+        Subcommand::Facets => facets(session),
+        Subcommand::Count => q_count(session),
+        Subcommand::Weights => q_weight(session, none),
+        Subcommand::Zooms => q_zoom(session, none),
+        Subcommand::AnswerSets => navigate(session),
+        Subcommand::Sample(None) => k_greedy_search(session, none),
+        Subcommand::Sample(Some(heuristic)) => {
+            perfect_sample(session.navigator_mut(), heuristic.split_whitespace())
+        }
+        Subcommand::Components => components(session),
+        Subcommand::Serve(port) => serve::run(session, *port),
+    }
 }
 
-pub fn manual() {
-    println!();
-    CONFIG.manual.iter().for_each(|s| println!("{}", s));
-    println!();
+pub fn manual() -> Result<()> {
+    textln!();
+    CONFIG.manual.iter().for_each(|s| textln!("{}", s));
+    textln!();
+    output::field("manual", &CONFIG.manual[..]);
+
+    Ok(())
 }
 
-pub fn source(navigator: &Navigator) {
-    println!("\n{}\n", navigator.logic_program)
+pub fn source(session: &Session) -> Result<()> {
+    textln!("\n{}\n", session.navigator().logic_program);
+    output::field("source", &session.navigator().logic_program);
+
+    Ok(())
 }
 
-pub fn facets(navigator: &Navigator) {
-    println!("{}", navigator.current_facets)
+pub fn facets(session: &Session) -> Result<()> {
+    textln!("{}", session.navigator().current_facets);
+    output::field("facets", &session.navigator().current_facets);
+
+    Ok(())
 }
 
-pub fn facets_count(navigator: &Navigator) {
-    println!("\n{:?}\n", navigator.current_facets.len() * 2)
+pub fn facets_count(session: &Session) -> Result<()> {
+    textln!("\n{:?}\n", session.facets().len() * 2);
+    output::field("count", session.facets().len() * 2);
+
+    Ok(())
 }
 
-pub fn initial_facets(navigator: &Navigator) {
-    println!("{}", navigator.initial_facets)
+pub fn initial_facets(session: &Session) -> Result<()> {
+    textln!("{}", session.navigator().initial_facets);
+    output::field("facets", &session.navigator().initial_facets);
+
+    Ok(())
 }
 
-pub fn initial_facets_count(navigator: &Navigator) {
-    println!("\n{:?}\n", navigator.initial_facets.len() * 2)
+pub fn initial_facets_count(session: &Session) -> Result<()> {
+    textln!("\n{:?}\n", session.initial_facets().len() * 2);
+    output::field("count", session.initial_facets().len() * 2);
+
+    Ok(())
 }
 
-pub fn activate(mode: &Mode, navigator: &mut Navigator, input: Input) {
+pub fn q_count(session: &mut Session) -> Result<()> {
+    let estimate = session.estimate()?;
+    textln!("\n{}\n", estimate);
+    output::field("estimate", estimate);
+
+    Ok(())
+}
+
+pub fn switch_counting(session: &mut Session, mut input: Input) -> Result<()> {
+    match input.next() {
+        Some(counting) => session.set_counting(counting.parse()?),
+        _ => {
+            textln!("\n{:?}\n", session.counting());
+            output::field("counting", session.counting());
+            Ok(())
+        }
+    }
+}
+
+/// Switches between text and JSON output; shows the current format if none is provided.
+pub fn switch_format(mut input: Input) -> Result<()> {
+    match input.next() {
+        Some(format) => output::set_format(format.parse()?),
+        _ => {
+            textln!("\n{}\n", output::format());
+            output::field("format", output::format().to_string());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn q_cache(session: &Session) -> Result<()> {
+    let stats = session.cache_stats();
+    output::field("cache", stats);
+    output::field("hit_rate", stats.hit_rate());
+    output::field("file", session.cache_file());
+    textln!(
+        "\nentries: {}\nhits: {}\nmisses: {}\nhit rate: {:.2}%",
+        stats.entries,
+        stats.hits,
+        stats.misses,
+        stats.hit_rate() * 100f64
+    );
+    match session.cache_file() {
+        Some(path) => textln!("file: {}\n", path.display()),
+        _ => textln!("file: none\n"),
+    }
+
+    Ok(())
+}
+
+pub fn clear_cache(session: &mut Session) -> Result<()> {
+    session.clear_cache()
+}
+
+pub fn save_session(session: &Session, mut input: Input) -> Result<()> {
+    let path = input.next().ok_or_else(|| invalid("no file provided."))?;
+    session.save(path)?;
+    textln!("\n[INFO] saved session to {}\n", path);
+    output::field("file", path);
+
+    Ok(())
+}
+
+/// Opens the session saved to `path`, reporting changes of its program.
+pub fn restore_session(path: &str) -> Result<Session> {
+    let (session, opened) = Session::open(path)?;
+    output::field("program_changed", opened.program_changed);
+    output::field("dropped", &opened.dropped);
+    if opened.program_changed {
+        textln!("\n[INFO] the program changed since the session was saved");
+    }
+    if !opened.dropped.is_empty() {
+        textln!(
+            "\n[INFO] dropped unknown facets from the route: {}",
+            opened.dropped.join(" ")
+        );
+    }
+
+    Ok(session)
+}
+
+/// Replaces `session` by the one saved to the provided file, keeping workers and cache settings.
+pub fn open_session(session: &mut Session, mut input: Input) -> Result<()> {
+    let path = input.next().ok_or_else(|| invalid("no file provided."))?;
+    let mut opened = restore_session(path)?;
+
+    opened.set_workers(session.workers())?;
+    opened.set_cache_capacity(session.cache_capacity());
+    if let Some(dir) = session.cache_dir().map(|dir| dir.to_owned()) {
+        session.save_cache()?;
+        opened.set_cache_dir(dir)?;
+    }
+    *session = opened;
+    textln!("\n{:?}\n", session.route());
+    output::field("route", session.route());
+
+    Ok(())
+}
+
+fn invalid(message: &str) -> NavigatorError {
+    NavigatorError::InvalidInput(message.to_owned())
+}
+
+fn weight_parameter(mode: &impl Display) -> String {
+    format!("{}", mode)
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Facets along with their values as JSON objects, e.g., `{"facet": "a", "weight": 0.5}`.
+pub(crate) fn valued(facets: &[(String, f64)], value: &str) -> Vec<serde_json::Value> {
+    facets
+        .iter()
+        .map(|(facet, v)| json!({ "facet": facet, value: v }))
+        .collect()
+}
+
+fn show_weights(weights: &[(String, f64)]) {
+    weights
+        .iter()
+        .for_each(|(facet, w)| textln!("{:.2} {}", w, facet));
+}
+
+fn show_zooms(zooms: &[(String, f64)]) {
+    zooms
+        .iter()
+        .for_each(|(facet, z)| textln!("{} : {:.2}%", facet, z * 100f64));
+}
+
+fn show_dropped(dropped: &[String]) {
+    if !dropped.is_empty() {
+        textln!("\n[INFO] dropped from route: {:?}\n", dropped);
+    }
+    output::field("dropped", dropped);
+}
+
+pub fn activate(session: &mut Session, input: Input) -> Result<()> {
     let facets = input.map(|s| s.to_owned()).collect::<Vec<String>>();
 
-    navigator.activate(&facets, mode);
+    #[cfg(feature = "with_stats")]
+    let start = Instant::now();
+
+    session.activate(&facets)?;
+    output::field("route", session.route());
+
+    #[cfg(feature = "with_stats")]
+    {
+        textln!("call    : --activate");
+        textln!("elapsed : {:?}\n", start.elapsed());
+    }
+
+    Ok(())
 }
 
-pub fn activate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input) {
+pub fn activate_where(session: &mut Session, mut input: Input) -> Result<()> {
     let facets = match input.next() {
         Some("*~") => {
             let p = input
                 .next()
                 .map(|n| n.replace('(', "").replace(')', ""))
-                .expect("error: provide atom name.");
+                .ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
@@ -123,11 +585,11 @@ pub fn activate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input)
                 .collect::<Vec<_>>()
         }
         Some("r*~") => {
-            let p = input.next().expect("error: provide atom name.");
+            let p = input.next().ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
@@ -140,11 +602,11 @@ pub fn activate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input)
             let p = input
                 .next()
                 .map(|n| n.replace('(', "").replace(')', ""))
-                .expect("error: provide atom name.");
+                .ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
@@ -154,11 +616,11 @@ pub fn activate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input)
                 .collect::<Vec<_>>()
         }
         Some("r*") => {
-            let p = input.next().expect("error: provide atom name.");
+            let p = input.next().ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
@@ -167,316 +629,540 @@ pub fn activate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input)
                 .map(|i| p.replace('_', &i.to_string()))
                 .collect::<Vec<_>>()
         }
-        _ => todo!(),
+        pattern => {
+            return Err(NavigatorError::Unsupported(format!(
+                "pattern {:?}, expected one of `*`, `*~`, `r*`, `r*~`",
+                pattern.unwrap_or_default()
+            )))
+        }
     };
 
-    navigator.activate(&facets, mode);
+    session.activate(&facets)?;
+    output::field("route", session.route());
+
+    Ok(())
 }
-pub fn activate_all_of(mode: &Mode, navigator: &mut Navigator, mut input: Input) {
+pub fn activate_all_of(session: &mut Session, mut input: Input) -> Result<()> {
     let facets = {
-        let p = input.next().expect("error: provide atom name.");
-        navigator
-            .inclusive_facets(&[])
+        let p = input.next().ok_or_else(|| invalid("provide atom name."))?;
+        session
+            .navigator_mut()
+            .inclusive_facets(&[])?
             .iter()
             .map(|f| unsafe { f.to_string().unwrap_unchecked() })
             .filter(|s| s.starts_with(p))
             .collect::<Vec<_>>()
     };
     //dbg!(&facets);
-    navigator.activate(&facets, mode);
+    session.activate(&facets)?;
+    output::field("route", session.route());
+
+    Ok(())
 }
 
-pub fn deactivate(mode: &Mode, navigator: &mut Navigator, input: Input) {
-    navigator.deactivate_any(
-        &input
-            .map(|s| s.to_owned().symbol())
-            .collect::<Vec<Symbol>>(),
-        mode,
-    );
+pub fn deactivate(session: &mut Session, input: Input) -> Result<()> {
+    let facets = input.map(|s| s.to_owned()).collect::<Vec<String>>();
+
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    session.deactivate(&facets)?;
+
+    let elapsed = start.elapsed();
+
+    textln!("call    : --deactivate");
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn deactivate_where(mode: &Mode, navigator: &mut Navigator, mut input: Input) {
+pub fn deactivate_where(session: &mut Session, mut input: Input) -> Result<()> {
     let facets = match input.next() {
         Some("*~") => {
             let p = input
                 .next()
                 .map(|n| n.replace('(', "").replace(')', ""))
-                .expect("error: provide atom name.");
+                .ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
                 .unwrap_or(0);
             (l..u + 1)
-                .map(|i| format!("~{}({:?})", p, i).symbol())
+                .map(|i| format!("~{}({:?})", p, i))
                 .collect::<Vec<_>>()
         }
         Some("*") => {
             let p = input
                 .next()
                 .map(|n| n.replace('(', "").replace(')', ""))
-                .expect("error: provide atom name.");
+                .ok_or_else(|| invalid("provide atom name."))?;
             let u = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
-                .expect("error: provide upper bound.");
+                .ok_or_else(|| invalid("provide upper bound."))?;
             let l = input
                 .next()
                 .and_then(|s| s.parse::<usize>().ok())
                 .unwrap_or(0);
             (l..u + 1)
-                .map(|i| format!("{}({:?})", p, i).symbol())
+                .map(|i| format!("{}({:?})", p, i))
                 .collect::<Vec<_>>()
         }
-        _ => todo!(),
+        pattern => {
+            return Err(NavigatorError::Unsupported(format!(
+                "pattern {:?}, expected one of `*`, `*~`",
+                pattern.unwrap_or_default()
+            )))
+        }
     };
 
-    navigator.deactivate_any(&facets, mode);
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    session.deactivate(&facets)?;
+
+    let elapsed = start.elapsed();
+
+    textln!("call    : --deactivate");
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+pub fn clear_route(session: &mut Session) -> Result<()> {
+    match session.route().0.is_empty() {
+        true => textln!("\n[INFO] current route is already empty\n"),
+        _ => session.clear_route()?,
+    }
+    output::field("route", session.route());
+
+    Ok(())
 }
 
-pub fn clear_route(mode: &Mode, navigator: &mut Navigator) {
-    match navigator.route.0.is_empty() {
-        true => println!("\n[INFO] rcurrent route is already empty\n"),
+pub fn undo(session: &mut Session) -> Result<()> {
+    let undone = session.undo()?;
+    match undone {
+        true => textln!("\n{:?}\n", session.route()),
+        _ => textln!("\n[INFO] nothing to undo\n"),
+    }
+    output::field("changed", undone);
+    output::field("route", session.route());
+
+    Ok(())
+}
+
+pub fn redo(session: &mut Session) -> Result<()> {
+    let redone = session.redo()?;
+    match redone {
+        true => textln!("\n{:?}\n", session.route()),
+        _ => textln!("\n[INFO] nothing to redo\n"),
+    }
+    output::field("changed", redone);
+    output::field("route", session.route());
+
+    Ok(())
+}
+
+pub fn bookmark(session: &mut Session, mut input: Input) -> Result<()> {
+    match (input.next(), input.next()) {
+        (Some("save"), Some(name)) => session.bookmark(name),
+        (Some("goto"), Some(name)) => {
+            session.goto(name)?;
+            textln!("\n{:?}\n", session.route());
+            output::field("route", session.route());
+        }
+        (None, None) => {
+            textln!();
+            session
+                .history()
+                .bookmarks()
+                .for_each(|(name, route)| textln!("{} {:?}", name, route));
+            textln!();
+            output::field(
+                "bookmarks",
+                session
+                    .history()
+                    .bookmarks()
+                    .collect::<std::collections::BTreeMap<_, _>>(),
+            );
+        }
+        _ => return Err(invalid("use `:bm save name` or `:bm goto name`.")),
+    }
+
+    Ok(())
+}
+
+pub fn q_history(session: &Session) -> Result<()> {
+    textln!("\n{}", session.history());
+    output::field("history", session.history());
+
+    Ok(())
+}
+
+pub fn add_rule(session: &mut Session, input: Input) -> Result<()> {
+    let rule = input.collect::<Vec<_>>().join(" ");
+    if rule.is_empty() {
+        return Err(invalid("no rule provided."));
+    }
+
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    let dropped = session.extend(&rule)?;
+
+    let elapsed = start.elapsed();
+
+    show_dropped(&dropped);
+
+    textln!("call    : --add-rule {}", rule);
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+pub fn load(session: &mut Session, mut input: Input) -> Result<()> {
+    let path = input.next().ok_or_else(|| invalid("no file provided."))?;
+
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    let dropped = session.load_file(path)?;
+
+    let elapsed = start.elapsed();
+
+    show_dropped(&dropped);
+
+    textln!("call    : --load {}", path);
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+/// Edits the program as loaded and restarts on it once changes are written.
+pub fn edit(session: &mut Session) -> Result<()> {
+    let program = session.navigator().loaded_program()?;
+    let edited = match editor::edit(&program)? {
+        Some(edited) if !edited.lines().eq(program.lines()) => edited,
         _ => {
-            navigator.route = Route(vec![]);
-            navigator.active_facets = vec![];
-            navigator.update(mode);
+            textln!("\nprogram unchanged\n");
+            return Ok(());
+        }
+    };
+
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    let dropped = session.reload(&edited)?;
+
+    let elapsed = start.elapsed();
+
+    show_dropped(&dropped);
+
+    textln!("call    : --edit");
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+pub fn ground(session: &mut Session, mut input: Input) -> Result<()> {
+    let part = input
+        .next()
+        .ok_or_else(|| invalid("no program part provided."))?;
+
+    textln!("\nsolving...");
+    let start = Instant::now();
+
+    let dropped = session.ground(part)?;
+
+    let elapsed = start.elapsed();
+
+    show_dropped(&dropped);
+
+    textln!("call    : --ground {}", part);
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+/// Answer sets as objects with `atoms` and, if any, `cost`.
+pub(crate) fn answer_sets_json(answer_sets: &[(Vec<String>, Vec<i64>)]) -> Vec<serde_json::Value> {
+    answer_sets
+        .iter()
+        .map(|(atoms, cost)| match cost.is_empty() {
+            true => json!({ "atoms": atoms }),
+            _ => json!({ "atoms": atoms, "cost": cost }),
+        })
+        .collect()
+}
+
+/// Prints each of up to `n` answer sets as soon as it is found.
+fn show_answer_sets(session: &mut Session, n: usize) -> Result<()> {
+    textln!();
+
+    // answer sets are kept for the JSON object only, which is printed once the command is done
+    let json = !output::is_text();
+    let mut answer_sets = vec![];
+    let mut i = 0;
+    let count = session.for_each_answer_set(n, |answer_set, cost| {
+        i += 1;
+        textln!("Answer {:?}: ", i);
+        answer_set.iter().for_each(|atom| text!("{} ", atom));
+        textln!();
+        if !cost.is_empty() {
+            textln!(
+                "Optimization: {}",
+                cost.iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
         }
+        if json {
+            answer_sets.push((answer_set.to_vec(), cost.to_vec()));
+        }
+    })?;
+    output::field("satisfiable", count > 0);
+    output::field("answer_sets", answer_sets_json(&answer_sets));
+
+    match count {
+        0 => textln!("UNSATISFIABLE\n"),
+        _ => textln!("SATISFIABLE\n"),
     }
+
+    Ok(())
 }
 
-pub fn navigate(navigator: &mut Navigator) {
-    println!("\nsolving...");
+pub fn navigate(session: &mut Session) -> Result<()> {
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    navigator.navigate();
+    show_answer_sets(session, 0)?;
 
     let elapsed = start.elapsed();
 
-    println!("call    : ?-navigate");
-    println!("elapsed : {:?}\n", elapsed);
+    textln!("call    : ?-navigate");
+    textln!("elapsed : {:?}\n", elapsed);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn navigate_n(navigator: &mut Navigator, mut input: Input) {
-    let n = input.next().and_then(|n| n.parse::<usize>().ok());
+pub fn navigate_n(session: &mut Session, mut input: Input) -> Result<()> {
+    let n = input
+        .next()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(session.navigator().n);
 
-    println!("\nsolving...");
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    navigator.navigate_n(n);
+    show_answer_sets(session, n)?;
 
     let elapsed = start.elapsed();
 
-    println!("call    : ?-navigate-n {:?}", n.unwrap_or(navigator.n));
-    println!("elapsed : {:?}\n", elapsed);
+    textln!("call    : ?-navigate-n {:?}", n);
+    textln!("elapsed : {:?}\n", elapsed);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn q_zoom(
-    mode: &(impl GoalOrientedNavigation + Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
+pub fn q_zoom(session: &mut Session, mut input: Input) -> Result<()> {
     match input.next() {
         Some(f) => {
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
-            mode.show_z(navigator, f);
+            let zooms = session.zoom(f)?;
+            show_zooms(&zooms);
 
             let elapsed = start.elapsed();
 
-            println!("\ncall    : ?-zoom {}", f);
-            println!(
-                "zoom    : {}",
-                format!("{}", mode)
-                    .split_whitespace()
-                    .next()
-                    .expect("could not retrieve weight parameter.")
-            );
-            println!("elapsed : {:?}\n", elapsed);
+            textln!("\ncall    : ?-zoom {}", f);
+            textln!("zoom    : {}", weight_parameter(session.mode()));
+            textln!("elapsed : {:?}\n", elapsed);
+            output::field("zooms", valued(&zooms, "zoom"));
+            output::elapsed(elapsed);
+        }
+        _ if session.facets().is_empty() => {
+            textln!("\nno current facets.\n");
+            output::field("zooms", Vec::<()>::new());
         }
         _ => {
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
-            mode.show_a_z(navigator);
+            let zooms = session.zooms()?;
+            show_zooms(&zooms);
 
             let elapsed = start.elapsed();
 
-            println!("\ncall    : ?-zoom");
-            println!(
-                "zoom    : {}",
-                format!("{}", mode)
-                    .split_whitespace()
-                    .next()
-                    .expect("could not retrieve weight parameter.")
-            );
-            println!("elapsed : {:?}\n", elapsed);
+            textln!("\ncall    : ?-zoom");
+            textln!("zoom    : {}", weight_parameter(session.mode()));
+            textln!("elapsed : {:?}\n", elapsed);
+            output::field("zooms", valued(&zooms, "zoom"));
+            output::elapsed(elapsed);
         }
     };
+    output::field("mode", session.mode());
+
+    Ok(())
 }
 
-pub fn q_zoom_n(
-    mode: &(impl GoalOrientedNavigation + Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
+pub fn q_zoom_n(session: &mut Session, mut input: Input) -> Result<()> {
     match input.next() {
         Some(n) => {
-            println!("\nsolving...\n");
+            let k = n
+                .parse::<usize>()
+                .map_err(|_| NavigatorError::Parse(n.to_owned()))?;
+
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
-            navigator
-                .current_facets
-                .clone()
-                .iter()
-                .take(n.parse::<usize>().expect("parsing n failed."))
-                .for_each(|f| mode.show_z(navigator, &f.repr()));
+            let mut zooms = vec![];
+            for f in session.facets().iter().take(k) {
+                let zoom = session.zoom(f)?;
+                show_zooms(&zoom);
+                zooms.extend(zoom);
+            }
 
             let elapsed = start.elapsed();
 
-            println!("\ncall    : ?-zoom-n {}", n);
-            println!(
-                "zoom    : {}",
-                format!("{}", mode)
-                    .split_whitespace()
-                    .next()
-                    .expect("could not retrieve weight parameter.")
-            );
-            println!("elapsed : {:?}\n", elapsed);
+            textln!("\ncall    : ?-zoom-n {}", n);
+            textln!("zoom    : {}", weight_parameter(session.mode()));
+            textln!("elapsed : {:?}\n", elapsed);
+            output::field("zooms", valued(&zooms, "zoom"));
+            output::field("mode", session.mode());
+            output::elapsed(elapsed);
+
+            Ok(())
         }
-        _ => q_zoom(mode, navigator, input), // all
-    };
+        _ => q_zoom(session, input), // all
+    }
 }
 
-pub fn q_weight(
-    mode: &(impl GoalOrientedNavigation + std::fmt::Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
+pub fn q_weight(session: &mut Session, mut input: Input) -> Result<()> {
+    let start = Instant::now();
+
     match input.next() {
         Some(f) => {
             #[cfg(feature = "with_stats")]
-            {
-                println!("\nsolving...\n");
-                let start = Instant::now();
-            }
+            textln!("\nsolving...\n");
 
-            mode.show_w(navigator, f);
+            let weights = session.weight(f)?;
+            show_weights(&weights);
+            output::field("weights", valued(&weights, "weight"));
 
             #[cfg(feature = "with_stats")]
             {
-                let elapsed = start.elapsed();
-
-                println!("\ncall    : ?-weight {}", f);
-                println!(
-                    "weight  : {}",
-                    format!("{}", mode)
-                        .split_whitespace()
-                        .next()
-                        .expect("could not retrieve weight parameter.")
-                );
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-weight {}", f);
+                textln!("weight  : {}", weight_parameter(session.mode()));
+                textln!("elapsed : {:?}\n", start.elapsed());
             }
         }
+        _ if session.facets().is_empty() => {
+            textln!("\nno current facets.\n");
+            output::field("weights", Vec::<()>::new());
+        }
         _ => {
             #[cfg(feature = "with_stats")]
-            {
-                println!("\nsolving...\n");
-                let start = Instant::now();
-            }
+            textln!("\nsolving...\n");
 
-            mode.show_a_w(navigator);
+            let weights = session.weights()?;
+            show_weights(&weights);
+            output::field("weights", valued(&weights, "weight"));
 
             #[cfg(feature = "with_stats")]
             {
-                let elapsed = start.elapsed();
-
-                println!("\ncall    : ?-weight");
-                println!(
-                    "weight  : {}",
-                    format!("{}", mode)
-                        .split_whitespace()
-                        .next()
-                        .expect("could not retrieve weight parameter.")
-                );
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-weight");
+                textln!("weight  : {}", weight_parameter(session.mode()));
+                textln!("elapsed : {:?}\n", start.elapsed());
             }
         }
     };
+    output::field("mode", session.mode());
+    output::elapsed(start.elapsed());
+
+    Ok(())
 }
 
-pub fn q_weight_n(
-    mode: &(impl GoalOrientedNavigation + std::fmt::Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
+pub fn q_weight_n(session: &mut Session, mut input: Input) -> Result<()> {
+    let start = Instant::now();
+
     match input.next() {
         Some(n) => {
+            let k = n
+                .parse::<usize>()
+                .map_err(|_| NavigatorError::Parse(n.to_owned()))?;
+
             #[cfg(feature = "with_stats")]
-            {
-                println!("\nsolving...\n");
-                let start = Instant::now();
-            }
+            textln!("\nsolving...\n");
 
-            navigator
-                .current_facets
-                .clone()
-                .iter()
-                .take(n.parse::<usize>().expect("parsing n failed."))
-                .for_each(|f| mode.show_w(navigator, &f.repr()));
+            let mut weights = vec![];
+            for f in session.facets().iter().take(k) {
+                let weight = session.weight(f)?;
+                show_weights(&weight);
+                weights.extend(weight);
+            }
+            output::field("weights", valued(&weights, "weight"));
 
             #[cfg(feature = "with_stats")]
             {
-                let elapsed = start.elapsed();
-
-                println!("\ncall    : ?-weight-n {}", n);
-                println!(
-                    "weight  : {}",
-                    format!("{}", mode)
-                        .split_whitespace()
-                        .next()
-                        .expect("could not retrieve weight parameter.")
-                );
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-weight-n {}", n);
+                textln!("weight  : {}", weight_parameter(session.mode()));
+                textln!("elapsed : {:?}\n", start.elapsed());
             }
         }
+        _ if session.facets().is_empty() => {
+            textln!("\nno current facets.\n");
+            output::field("weights", Vec::<()>::new());
+        }
         _ => {
             #[cfg(feature = "with_stats")]
-            {
-                println!("\nsolving...\n");
-                let start = Instant::now();
-            }
+            textln!("\nsolving...\n");
 
-            mode.show_a_w(navigator);
+            let weights = session.weights()?;
+            show_weights(&weights);
+            output::field("weights", valued(&weights, "weight"));
 
             #[cfg(feature = "with_stats")]
             {
-                let elapsed = start.elapsed();
-
-                println!("\ncall    : ?-weight");
-                println!(
-                    "weight  : {}",
-                    format!("{}", mode)
-                        .split_whitespace()
-                        .next()
-                        .expect("could not retrieve weight parameter.")
-                );
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-weight");
+                textln!("weight  : {}", weight_parameter(session.mode()));
+                textln!("elapsed : {:?}\n", start.elapsed());
             }
         }
     };
+    output::field("mode", session.mode());
+    output::elapsed(start.elapsed());
+
+    Ok(())
 }
 
-pub fn q_route_safe(navigator: &mut Navigator, mut input: Input) {
+pub fn q_route_safe(session: &mut Session, mut input: Input) -> Result<()> {
+    let navigator = session.navigator_mut();
+
     match input.next() {
         Some(arg) => match arg.chars().next() {
             // some route
             Some('<') => {
-                println!("\nsolving...\n");
+                textln!("\nsolving...\n");
                 let start = Instant::now();
 
                 let facets = input
@@ -485,59 +1171,90 @@ pub fn q_route_safe(navigator: &mut Navigator, mut input: Input) {
                     .filter(|s| !s.is_empty())
                     .collect::<Vec<String>>();
 
-                let assumptions = navigator
-                    .parse_input_to_literals(&facets)
-                    .collect::<Vec<Literal>>();
+                let assumptions = navigator.literals_of(&facets)?;
 
-                println!("{:?}", navigator.satisfiable(&assumptions));
+                let safe = navigator.satisfiable(&assumptions)?;
+                textln!("{:?}", safe);
 
                 let elapsed = start.elapsed();
 
-                println!("\ncall    : ?-route-safe {}", Route(facets));
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-route-safe {}", Route(facets.clone()));
+                textln!("elapsed : {:?}\n", elapsed);
+                output::field("route", Route(facets));
+                output::field("safe", safe);
+                output::elapsed(elapsed);
             }
             // peeking on current route
             Some('+') => {
-                println!("\nsolving...\n");
+                textln!("\nsolving...\n");
                 let start = Instant::now();
 
                 let route = navigator.route.peek_steps(input);
-                let assumptions = navigator
-                    .parse_input_to_literals(&route.0)
-                    .collect::<Vec<Literal>>();
+                let assumptions = navigator.literals_of(&route.0)?;
 
-                println!("{:?}", navigator.satisfiable(&assumptions));
+                let safe = navigator.satisfiable(&assumptions)?;
+                textln!("{:?}", safe);
 
                 let elapsed = start.elapsed();
 
-                println!("\ncall    : ?-route-safe {}", route);
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-route-safe {}", route);
+                textln!("elapsed : {:?}\n", elapsed);
+                output::field("route", route);
+                output::field("safe", safe);
+                output::elapsed(elapsed);
             }
-            _ => println!("\ninvalid input: {:?}\n\nsee `?man ?rs` for syntax", arg),
+            _ => return Err(invalid(&format!("{:?}, see `?man ?rs` for syntax", arg))),
         },
         // current route
         _ => {
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
             let assumptions = navigator.active_facets.clone();
 
-            println!("{:?}", navigator.satisfiable(&assumptions));
+            let safe = navigator.satisfiable(&assumptions)?;
+            textln!("{:?}", safe);
 
             let elapsed = start.elapsed();
 
-            println!("\ncall    : ?-route-safe {}", navigator.route);
-            println!("elapsed : {:?}\n", elapsed);
+            textln!("\ncall    : ?-route-safe {}", navigator.route);
+            textln!("elapsed : {:?}\n", elapsed);
+            output::field("route", &navigator.route);
+            output::field("safe", safe);
+            output::elapsed(elapsed);
         }
     }
+
+    Ok(())
 }
 
-pub fn q_route_maximal_safe(navigator: &mut Navigator, mut input: Input) {
+pub(crate) fn route_is_maximal_safe(
+    navigator: &mut Navigator,
+    assumptions: &[Literal],
+) -> Result<bool> {
+    if !navigator.satisfiable(assumptions)? {
+        return Ok(false);
+    }
+
+    for s in navigator.inclusive_facets(assumptions)?.to_strings() {
+        let mut peek = assumptions.to_vec();
+        peek.push(navigator.literal(&s)?);
+        if navigator.satisfiable(&peek)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn q_route_maximal_safe(session: &mut Session, mut input: Input) -> Result<()> {
+    let navigator = session.navigator_mut();
+
     match input.next() {
         Some(s) => match s.chars().next() {
             // some route
             Some('<') => {
-                println!("\nsolving...\n");
+                textln!("\nsolving...\n");
                 let start = Instant::now();
 
                 let facets = input
@@ -546,439 +1263,472 @@ pub fn q_route_maximal_safe(navigator: &mut Navigator, mut input: Input) {
                     .filter(|s| !s.is_empty())
                     .collect::<Vec<String>>();
 
-                let assumptions = navigator
-                    .parse_input_to_literals(&facets)
-                    .collect::<Vec<Literal>>();
-
-                match navigator.satisfiable(&assumptions) {
-                    false => println!("{:?}", false),
-                    _ => {
-                        let facets = navigator.inclusive_facets(&assumptions);
-                        println!(
-                            "{:?}",
-                            facets.is_empty()
-                                || facets.to_strings().all(|s| {
-                                    !navigator.satisfiable(
-                                        &navigator
-                                            .parse_input_to_literals(
-                                                &navigator.route.peek_step(&s).0,
-                                            )
-                                            .collect::<Vec<Literal>>(),
-                                    )
-                                })
-                        )
-                    }
-                }
+                let assumptions = navigator.literals_of(&facets)?;
+
+                let maximal_safe = route_is_maximal_safe(navigator, &assumptions)?;
+                textln!("{:?}", maximal_safe);
 
                 let elapsed = start.elapsed();
 
-                println!("\ncall    : ?-route-maximal-safe {}", Route(facets));
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-route-maximal-safe {}", Route(facets.clone()));
+                textln!("elapsed : {:?}\n", elapsed);
+                output::field("route", Route(facets));
+                output::field("maximal_safe", maximal_safe);
+                output::elapsed(elapsed);
             }
             // peeking on current route
             Some('+') => {
-                println!("\nsolving...\n");
+                textln!("\nsolving...\n");
                 let start = Instant::now();
 
                 let route = navigator.route.peek_steps(input);
-                let assumptions = navigator
-                    .parse_input_to_literals(&route.0)
-                    .collect::<Vec<Literal>>();
-
-                match navigator.satisfiable(&assumptions) {
-                    false => println!("{:?}", false),
-                    _ => {
-                        let facets = navigator.inclusive_facets(&assumptions);
-                        println!(
-                            "{:?}",
-                            facets.is_empty()
-                                || facets.to_strings().all(|s| {
-                                    !navigator.satisfiable(
-                                        &navigator
-                                            .parse_input_to_literals(
-                                                &navigator.route.peek_step(&s).0,
-                                            )
-                                            .collect::<Vec<Literal>>(),
-                                    )
-                                })
-                        )
-                    }
-                }
+                let assumptions = navigator.literals_of(&route.0)?;
+
+                let maximal_safe = route_is_maximal_safe(navigator, &assumptions)?;
+                textln!("{:?}", maximal_safe);
 
                 let elapsed = start.elapsed();
 
-                println!("\ncall    : ?-route-maximal-safe {}", route);
-                println!("elapsed : {:?}\n", elapsed);
+                textln!("\ncall    : ?-route-maximal-safe {}", route);
+                textln!("elapsed : {:?}\n", elapsed);
+                output::field("route", route);
+                output::field("maximal_safe", maximal_safe);
+                output::elapsed(elapsed);
             }
-            _ => println!("\ninvalid input: {:?}\n\nsee `?man ?rms` for syntax", s),
+            _ => return Err(invalid(&format!("{:?}, see `?man ?rms` for syntax", s))),
         },
         // current route
         _ => {
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
-            println!("{:?}", navigator.current_route_is_maximal_safe());
+            let maximal_safe = navigator.current_route_is_maximal_safe()?;
+            textln!("{:?}", maximal_safe);
 
             let elapsed = start.elapsed();
 
-            println!("\ncall    : ?-route-maximal-safe {}", navigator.route);
-            println!("elapsed : {:?}\n", elapsed);
+            textln!("\ncall    : ?-route-maximal-safe {}", navigator.route);
+            textln!("elapsed : {:?}\n", elapsed);
+            output::field("route", &navigator.route);
+            output::field("maximal_safe", maximal_safe);
+            output::elapsed(elapsed);
         }
     }
+
+    Ok(())
 }
 
-pub fn step(
-    mode_: &Mode,
-    mode: &impl GoalOrientedNavigation,
-    navigator: &mut Navigator,
-    current_facets: &[Symbol],
-) {
-    if navigator.current_facets.0.is_empty() {
-        println!("[INFO] no current facets");
-        return;
+/// Shows which facets of the route entail the provided facet or its exclusion.
+pub fn q_why(session: &mut Session, mut input: Input) -> Result<()> {
+    let facet = input.next().ok_or_else(|| invalid("no facet provided."))?;
+
+    textln!("\nsolving...\n");
+    let start = Instant::now();
+
+    let explanation = session.explain(facet)?;
+    textln!("route   : {}", session.route().0.join(" "));
+    match &explanation {
+        Some((entailed, core)) if core.0.is_empty() => {
+            textln!(
+                "{} holds in every answer set, regardless of the route",
+                entailed
+            )
+        }
+        Some((entailed, core)) => textln!("{} is entailed by {}", entailed, core.0.join(" ")),
+        _ => textln!("{:?} is entailed by no facets of the route", facet),
+    }
+
+    let elapsed = start.elapsed();
+
+    textln!("\ncall    : ?-why {}", facet);
+    textln!("elapsed : {:?}\n", elapsed);
+    output::field("route", session.route());
+    output::field(
+        "entailed",
+        explanation.as_ref().map(|(entailed, _)| entailed),
+    );
+    output::field("core", explanation.as_ref().map(|(_, core)| core));
+    output::elapsed(elapsed);
+
+    Ok(())
+}
+
+fn show_filtered(session: &mut Session, mode: &Mode) -> Result<Vec<String>> {
+    let start = Instant::now();
+
+    let filtered = session.filter_with(mode)?;
+
+    let elapsed = start.elapsed();
+
+    textln!("\nnavigation mode : {}", mode);
+    textln!(
+        "filtered        : {:?}/{:?}",
+        filtered.len(),
+        session.facets().len() * 2
+    );
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("mode", mode);
+    output::field("filtered", &filtered);
+    output::elapsed(elapsed);
+
+    Ok(filtered)
+}
+
+pub fn step(session: &mut Session, mode: Option<Mode>) -> Result<()> {
+    if session.facets().is_empty() {
+        textln!("[INFO] no current facets");
+        return Ok(());
     }
 
     #[cfg(feature = "with_stats")]
     {
-        println!("\ncall            : --step");
+        textln!("\ncall            : --step");
     }
-    filter(mode, navigator, current_facets)
+    let mode = mode.unwrap_or_else(|| session.mode().clone());
+    show_filtered(session, &mode)?
         .iter()
-        .for_each(|s| print!("{} ", s));
-    print!("\ntype facet to activate: ");
+        .for_each(|s| text!("{} ", s));
+    text!("\ntype facet to activate: ");
 
-    activate(mode_, navigator, navigator.user_input().split_whitespace());
+    let answer = session.navigator().user_input()?;
+    activate(session, answer.split_whitespace())?;
 
-    navigate(navigator);
+    navigate(session)
 }
 
-pub fn step_n(
-    mode_: &Mode,
-    mode: &impl GoalOrientedNavigation,
-    navigator: &mut Navigator,
-    current_facets: &[Symbol],
-    input: Input,
-) {
-    if navigator.current_facets.0.is_empty() {
-        println!("\n[INFO] nno current facets\n");
-        return;
+pub fn step_n(session: &mut Session, input: Input) -> Result<()> {
+    if session.facets().is_empty() {
+        textln!("\n[INFO] no current facets\n");
+        return Ok(());
     }
 
-    println!("\ncall            : --step-n");
-    filter(mode, navigator, current_facets)
+    textln!("\ncall            : --step-n");
+    let mode = session.mode().clone();
+    show_filtered(session, &mode)?
         .iter()
-        .for_each(|s| print!("{} ", s));
-    print!("\n\ntype facet to activate: ");
+        .for_each(|s| text!("{} ", s));
+    text!("\n\ntype facet to activate: ");
+
+    let answer = session.navigator().user_input()?;
+    activate(session, answer.split_whitespace())?;
+
+    navigate_n(session, input)
+}
 
-    activate(mode_, navigator, navigator.user_input().split_whitespace());
+fn random_step(session: &mut Session, candidates: &[String]) -> Result<()> {
+    let mut rng = rand::thread_rng();
+    let facet = candidates
+        .choose(&mut rng)
+        .ok_or_else(|| NavigatorError::Solver("random step failed.".to_owned()))?;
 
-    navigate_n(navigator, input);
+    session.activate(&[facet.to_owned()])
 }
 
-pub fn random_safe_steps(mode_: &Mode, nav: &mut Navigator, mut input: Input) {
+pub fn random_safe_steps(session: &mut Session, mut input: Input) -> Result<()> {
     match input.next().map(|n| n.parse::<usize>()) {
         Some(Ok(n)) => {
             let t = (input.next(), input.next());
 
             let mut m = 0;
 
-            if nav.current_facets.0.is_empty() {
-                println!("[INFO] no current facets");
-                return;
+            if session.facets().is_empty() {
+                textln!("[INFO] no current facets");
+                return Ok(());
             }
 
-            print!("solving...");
+            text!("solving...");
             match parse_mode(t) {
                 Some(Mode::GoalOriented(_)) | None => {
                     #[cfg(feature = "with_stats")]
                     let start = Instant::now();
 
-                    while !nav.current_route_is_maximal_safe() && m != n {
-                        print!("{:?}.", m + 1);
-                        let mut rng = rand::thread_rng();
-                        nav.current_facets
-                            .clone()
-                            .0
-                            .choose(&mut rng)
-                            .map(|s| nav.activate(&[s.repr()], mode_))
-                            .expect("random step failed.");
+                    while !session.is_maximal_safe()? && m != n {
+                        text!("{:?}.", m + 1);
+                        let candidates = session.facets();
+                        random_step(session, &candidates)?;
                         m += 1;
                     }
-                    println!("done");
+                    textln!("done");
 
                     #[cfg(feature = "with_stats")]
                     {
                         let elapsed = start.elapsed();
-                        println!("\ncall            : --random-safe-steps {:?}", n);
-                        println!("navigation mode : goal-oriented");
-                        println!("elapsed         : {:?}\n", elapsed);
+                        textln!("\ncall            : --random-safe-steps {:?}", n);
+                        textln!("navigation mode : goal-oriented");
+                        textln!("elapsed         : {:?}\n", elapsed);
                     }
                 }
                 Some(mode) => {
                     #[cfg(feature = "with_stats")]
                     let start = Instant::now();
 
-                    while !nav.current_route_is_maximal_safe() && m != n {
-                        print!("{:?}.", m + 1);
-                        let mut rng = rand::thread_rng();
-                        filter(&mode, nav, nav.current_facets.clone().as_ref())
-                            .choose(&mut rng)
-                            .map(|s| nav.activate(&[s.to_string()], mode_))
-                            .expect("random step failed.");
+                    while !session.is_maximal_safe()? && m != n {
+                        text!("{:?}.", m + 1);
+                        let candidates = session.filter_with(&mode)?;
+                        random_step(session, &candidates)?;
                         m += 1;
                     }
-                    println!("done");
+                    textln!("done");
 
                     #[cfg(feature = "with_stats")]
                     {
                         let elapsed = start.elapsed();
-                        println!("call            : --random-safe-steps {:?}", n);
-                        println!("navigation mode : {}", mode);
-                        println!("elapsed         : {:?}\n", elapsed);
+                        textln!("call            : --random-safe-steps {:?}", n);
+                        textln!("navigation mode : {}", mode);
+                        textln!("elapsed         : {:?}\n", elapsed);
                     }
                 }
             }
+            output::field("steps", m);
+            output::field("route", session.route());
+
+            Ok(())
         }
-        _ => random_safe_walk(mode_, nav, input),
+        _ => random_safe_walk(session, input),
     }
 }
 
-pub fn random_safe_walk(mode_: &Mode, nav: &mut Navigator, mut input: Input) {
+pub fn random_safe_walk(session: &mut Session, mut input: Input) -> Result<()> {
     match parse_mode((input.next(), input.next())) {
         Some(Mode::GoalOriented(_)) | None => {
-            if nav.current_facets.0.is_empty() {
-                println!("\n[INFO] no current facets\n");
-                return;
+            if session.facets().is_empty() {
+                textln!("\n[INFO] no current facets\n");
+                return Ok(());
             }
 
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
             let mut i = 0;
-            while !nav.current_route_is_maximal_safe() {
-                println!("step {:?}", i);
-                let mut rng = rand::thread_rng();
-                nav.current_facets
-                    .clone()
-                    .0
-                    .choose(&mut rng)
-                    .map(|s| nav.activate(&[s.repr()], mode_))
-                    .expect("random step failed.");
+            while !session.is_maximal_safe()? {
+                textln!("step {:?}", i);
+                let candidates = session.facets();
+                random_step(session, &candidates)?;
                 i += 1;
             }
 
             let elapsed = start.elapsed();
 
-            println!("\ncall            : --random-safe-walk");
-            println!("navigation mode : goal-oriented");
-            println!("elapsed         : {:?}\n", elapsed);
+            textln!("\ncall            : --random-safe-walk");
+            textln!("navigation mode : goal-oriented");
+            textln!("elapsed         : {:?}\n", elapsed);
+            output::field("steps", i);
+            output::elapsed(elapsed);
         }
         Some(mode) => {
-            if nav.current_route_is_maximal_safe() {
-                println!("\n{} is maximal safe\n", nav.route);
-                return;
+            if session.is_maximal_safe()? {
+                textln!("\n{} is maximal safe\n", session.route());
+                return Ok(());
             }
 
-            println!("\nsolving...\n");
+            textln!("\nsolving...\n");
             let start = Instant::now();
 
             let mut i = 0;
-            while !nav.current_route_is_maximal_safe() {
-                println!("step {:?}", i);
-                let mut rng = rand::thread_rng();
-                filter(&mode, nav, nav.current_facets.clone().as_ref())
-                    .choose(&mut rng)
-                    .map(|s| nav.activate(&[s.to_string()], mode_))
-                    .expect("random step failed.");
+            while !session.is_maximal_safe()? {
+                textln!("step {:?}", i);
+                let candidates = session.filter_with(&mode)?;
+                random_step(session, &candidates)?;
                 i += 1;
             }
 
             let elapsed = start.elapsed();
 
-            println!("call            : --random-safe-walk");
-            println!("navigation mode : {}", mode);
-            println!("elapsed         : {:?}\n", elapsed);
+            textln!("call            : --random-safe-walk");
+            textln!("navigation mode : {}", mode);
+            textln!("elapsed         : {:?}\n", elapsed);
+            output::field("steps", i);
+            output::elapsed(elapsed);
         }
     }
+    output::field("route", session.route());
+
+    Ok(())
+}
+
+fn parse_bound(input: &mut Input) -> Result<f32> {
+    let f = input.next().ok_or_else(|| invalid("no bound provided."))?;
+
+    f.parse::<f32>()
+        .map_err(|_| NavigatorError::Parse(f.to_owned()))
 }
 
 // FIX
-pub fn q_zoom_higher_than(
-    mode: &(impl GoalOrientedNavigation + Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
-    let bound = if let Some(f) = input.next() {
-        f.parse::<f32>().expect("parsing bound failed.")
-    } else {
-        return println!("\n[ERROR] no bound provided\n");
-    };
+pub fn q_zoom_higher_than(session: &mut Session, mut input: Input) -> Result<()> {
+    let bound = parse_bound(&mut input)?;
+    let mode = session.mode().clone();
 
-    println!("\nsolving...");
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    mode.find_with_zh(navigator, bound)
-        .map(|f| println!("\n{}\n", f))
-        .unwrap_or_else(|| println!("\nno result\n"));
+    let found = mode.find_with_zh(session.navigator_mut(), bound)?;
+    match &found {
+        Some(f) => textln!("\n{}\n", f),
+        _ => textln!("\nno result\n"),
+    }
 
     let elapsed = start.elapsed();
 
-    println!("call            : ?-zoom-higher-than {:?}", bound);
-    println!("navigation mode : {}", mode);
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("call            : ?-zoom-higher-than {:?}", bound);
+    textln!("navigation mode : {}", mode);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("facet", found);
+    output::field("mode", mode);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
 // FIX
-pub fn q_zoom_lower_than(
-    mode: &(impl GoalOrientedNavigation + Display),
-    navigator: &mut Navigator,
-    mut input: Input,
-) {
-    let bound = if let Some(f) = input.next() {
-        f.parse::<f32>().expect("parsing bound failed.")
-    } else {
-        return println!("\n[ERROR] no bound provide\n");
-    };
+pub fn q_zoom_lower_than(session: &mut Session, mut input: Input) -> Result<()> {
+    let bound = parse_bound(&mut input)?;
+    let mode = session.mode().clone();
 
-    println!("\nsolving...");
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    mode.find_with_zl(navigator, bound)
-        .map(|f| println!("\n{}\n", f))
-        .unwrap_or_else(|| println!("\nno result\n"));
+    let found = mode.find_with_zl(session.navigator_mut(), bound)?;
+    match &found {
+        Some(f) => textln!("\n{}\n", f),
+        _ => textln!("\nno result\n"),
+    }
 
     let elapsed = start.elapsed();
 
-    println!("call            : ?-zoom-lower-than {:?}", bound);
-    println!("navigation mode : {}", mode);
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("call            : ?-zoom-lower-than {:?}", bound);
+    textln!("navigation mode : {}", mode);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("facet", found);
+    output::field("mode", mode);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
 pub fn find_facet_with_zoom_higher_than_and_activate(
-    mode: &(impl GoalOrientedNavigation + Display),
-    mode_: &Mode,
-    navigator: &mut Navigator,
+    session: &mut Session,
     mut input: Input,
-) {
-    let bound = if let Some(f) = input.next() {
-        f.parse::<f32>().expect("parsing bound failed.")
-    } else {
-        return println!("\n[ERROR] no bound provided\n");
-    };
+) -> Result<()> {
+    let bound = parse_bound(&mut input)?;
+    let mode = session.mode().clone();
 
-    println!("\nsolving...");
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    mode.find_with_zh(navigator, bound)
-        .map(|f| navigator.activate(&[f], mode_))
-        .unwrap_or_else(|| println!("\nno result"));
+    let found = mode.find_with_zh(session.navigator_mut(), bound)?;
+    match &found {
+        Some(f) => session.activate(&[f.to_owned()])?,
+        _ => textln!("\nno result"),
+    }
 
     let elapsed = start.elapsed();
 
-    println!(
+    textln!(
         "\ncall            : --find-facet-with-zoom-higher-than-and-activate {:?}",
         bound
     );
-    println!("navigation mode : {}", mode);
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("navigation mode : {}", mode);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("facet", found);
+    output::field("route", session.route());
+    output::field("mode", mode);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
 pub fn find_facet_with_zoom_lower_than_and_activate(
-    mode: &(impl GoalOrientedNavigation + Display),
-    mode_: &Mode,
-    navigator: &mut Navigator,
+    session: &mut Session,
     mut input: Input,
-) {
-    let bound = if let Some(f) = input.next() {
-        f.parse::<f32>().expect("parsing bound failed.")
-    } else {
-        return println!("\n[ERROR] no bound provided\n");
-    };
+) -> Result<()> {
+    let bound = parse_bound(&mut input)?;
+    let mode = session.mode().clone();
 
-    println!("\nsolving...");
+    textln!("\nsolving...");
     let start = Instant::now();
 
-    mode.find_with_zl(navigator, bound)
-        .map(|f| navigator.activate(&[f], mode_))
-        .unwrap_or_else(|| println!("\nno result"));
+    let found = mode.find_with_zl(session.navigator_mut(), bound)?;
+    match &found {
+        Some(f) => session.activate(&[f.to_owned()])?,
+        _ => textln!("\nno result"),
+    }
 
     let elapsed = start.elapsed();
 
-    println!(
+    textln!(
         "\ncall            : --find-facet-with-zoom-lower-than-and-activate {:?}",
         bound
     );
-    println!("navigation mode : {}", mode);
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("navigation mode : {}", mode);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("facet", found);
+    output::field("route", session.route());
+    output::field("mode", mode);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn k_greedy_search(navigator: &mut Navigator, mut input: Input) {
+pub fn k_greedy_search(session: &mut Session, mut input: Input) -> Result<()> {
     let fst = input.next();
     let sample_size = fst.and_then(|n| n.parse::<usize>().ok());
 
-    let mut ignored_atoms = input
-        .map(|s| crate::translator::Atom(s).parse(&[]))
-        .flatten() // NOTE: tricky
-        .collect::<Vec<_>>();
-    if !sample_size.is_some() && fst.is_some() {
-        let s = unsafe { fst.unwrap_unchecked() };
-        if let Some(sym) = crate::translator::Atom(&s).parse(&[]) {
-            ignored_atoms.push(sym)
+    let mut ignored_atoms = input.map(|s| s.to_owned()).collect::<Vec<_>>();
+    if sample_size.is_none() {
+        if let Some(s) = fst {
+            ignored_atoms.push(s.to_owned())
         }
     }
 
-    println!("\nsolving...\n");
+    textln!("\nsolving...\n");
     let start = Instant::now();
-    navigator.k_greedy_search_show(ignored_atoms.into_iter(), sample_size);
+    let sample = session.sample(sample_size, &ignored_atoms)?;
     let elapsed = start.elapsed();
 
-    println!("\ncall            : --k-greedy-search");
-    println!("elapsed         : {:?}\n", elapsed);
+    if sample.is_empty() {
+        textln!("UNSATISFIABLE");
+    }
+    for (i, answer_set) in sample.iter().enumerate() {
+        textln!("Answer {:?}: ", i + 1);
+        answer_set.iter().for_each(|atom| text!("{} ", atom));
+        textln!();
+    }
+
+    textln!("\ncall            : --k-greedy-search");
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("answer_sets", sample);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
 //pub fn k_greedy_search_io(navigator: &mut Navigator) {
 //    navigator.k_greedy_search_show(None);
 //}
 
-pub fn components(navigator: &mut Navigator) {
-    println!("\nsolving...\n");
+pub fn components(session: &mut Session) -> Result<()> {
+    textln!("\nsolving...\n");
     let start = Instant::now();
 
-    navigator.components().0.iter().for_each(|(k, v)| {
-        let (kl, vl0, vl1) = (k.len(), v.0.len(), v.1.len());
-        print!("({:?}) com: ", vl0);
-        v.0.iter().for_each(|s| print!("{} ", s));
-        print!("\n({:?}) cov: ", kl);
-        k.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        print!("\n({:?}) con: ", vl1);
-        v.1.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        println!("\n-");
+    let components = session.components()?;
+    components.iter().for_each(|c| {
+        text!("({:?}) com: ", c.facets.len());
+        c.facets.iter().for_each(|s| text!("{} ", s));
+        text!("\n({:?}) cov: ", c.cover.len());
+        c.cover.iter().for_each(|s| text!("{} ", s));
+        text!("\n({:?}) con: ", c.content.len());
+        c.content.iter().for_each(|s| text!("{} ", s));
+        textln!("\n-");
     });
 
     let elapsed = start.elapsed();
 
-    println!("\ncall            : --connected-components",);
-    println!("elapsed         : {:?}\n", elapsed);
-}
+    textln!("\ncall            : --connected-components",);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field("components", components);
+    output::elapsed(elapsed);
 
-pub fn components_io(navigator: &mut Navigator) {
-    let mut i = 0;
-    navigator.components().0.iter().for_each(|(k, v)| {
-        let (kl, vl0, vl1) = (k.len(), v.0.len(), v.1.len());
-        print!("({:?}) com: ", vl0);
-        v.0.iter().for_each(|s| print!("{} ", s));
-        print!("\n({:?}) cov: ", kl);
-        k.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        print!("\n({:?}) con: ", vl1);
-        v.1.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        println!("\n-");
-        i += 1;
-    });
-    println!("{:?}", i)
+    Ok(())
 }
 
 /*
@@ -987,7 +1737,7 @@ pub fn cores_in_io(navigator: &mut Navigator) {
 }
 
 pub fn find_perfect_core(navigator: &mut Navigator) {
-    println!("{:?}", navigator.find_perfect_core())
+    textln!("{:?}", navigator.find_perfect_core())
 }
 
 pub fn find_cores_encoding(navigator: &mut Navigator) {
@@ -995,95 +1745,83 @@ pub fn find_cores_encoding(navigator: &mut Navigator) {
 }
 */
 
-pub fn related_components(navigator: &mut Navigator) {
-    println!("\nsolving...\n");
-    let start = Instant::now();
-
-    navigator.related_components().0.iter().for_each(|(k, v)| {
-        let (kl, vl0, vl1) = (k.len(), v.0.len(), v.1.len());
-        print!("({:?}) com: ", vl0);
-        v.0.iter().for_each(|s| print!("{} ", s));
-        print!("\n({:?}) con: ", kl);
-        k.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        print!("\n({:?}) cov: ", vl1);
-        v.1.iter()
-            .for_each(|s| print!("{} ", unsafe { s.to_string().unwrap_unchecked() }));
-        println!("\n-");
-    });
-
-    let elapsed = start.elapsed();
+pub fn structure(navigator: &mut Navigator) -> Result<()> {
+    let mut covers = vec![];
+    for incidence in [Incidences::Brave, Incidences::Cautious, Incidences::Facet] {
+        let cover = Table::new(navigator, incidence)?.max_exact_cover();
+        textln!("{:?}", cover);
+        covers.push(cover);
+    }
+    output::field("covers", covers);
 
-    println!("\ncall            : --related-components",);
-    println!("elapsed         : {:?}\n", elapsed);
+    Ok(())
 }
 
-pub fn activate_from_file(mode: &Mode, navigator: &mut Navigator, file_path: &str) {
-    let facets = std::fs::read_to_string(file_path)
-        .unwrap() // TODO
+pub fn activate_from_file(session: &mut Session, file_path: &str) -> Result<()> {
+    let facets = std::fs::read_to_string(file_path)?
         .lines()
         .map(|s| s.to_owned())
         .collect::<Vec<_>>();
 
-    navigator.activate(&facets, mode);
+    session.activate(&facets)?;
+    output::field("route", session.route());
+
+    Ok(())
 }
 
-pub fn cc(navigator: &mut Navigator, input: Input) {
-    if let Some(cc) = navigator.consequences(
-        crate::navigator::EnumMode::Cautious,
-        &navigator
-            .parse_input_to_literals(&input.collect::<Vec<_>>())
-            .collect::<Vec<_>>(),
-    ) {
-        println!("{:?}", crate::soe::stringify(&cc));
-    }
+pub fn cc(navigator: &mut Navigator, input: Input) -> Result<()> {
+    let assumptions = navigator.literals_of(&input.collect::<Vec<_>>())?;
+    let cc = crate::soe::stringify(
+        &navigator.consequences(crate::navigator::EnumMode::Cautious, &assumptions)?,
+    );
+    textln!("{:?}", cc);
+    output::field("consequences", cc);
+
+    Ok(())
 }
 
-pub fn hole(navigator: &mut Navigator, input: Input) {
+pub fn hole(navigator: &mut Navigator, input: Input) -> Result<()> {
     let facets = input.map(|s| s.to_owned()).collect::<Vec<_>>();
-    if let Some(cc) = navigator.consequences(
-        crate::navigator::EnumMode::Cautious,
-        &navigator
-            .parse_input_to_literals(&facets)
-            .collect::<Vec<_>>(),
-    ) {
-        println!(
-            "{:?}",
-            crate::soe::stringify(&cc)
-                .iter()
-                .filter(|s| !facets.contains(&s))
-                .collect::<Vec<_>>()
-        );
-    }
+    let assumptions = navigator.literals_of(&facets)?;
+    let cc = navigator.consequences(crate::navigator::EnumMode::Cautious, &assumptions)?;
+    let hole = crate::soe::stringify(&cc)
+        .into_iter()
+        .filter(|s| !facets.contains(s))
+        .collect::<Vec<_>>();
+    textln!("{:?}", hole);
+    output::field("consequences", hole);
+
+    Ok(())
 }
 
-pub fn naive_approach_representative_sample(navigator: &mut Navigator, input: Input) {
+pub fn naive_approach_representative_sample(navigator: &mut Navigator, input: Input) -> Result<()> {
     let ignored_atoms = input
         .map(|s| crate::translator::Atom(s).parse(&[]))
         .flatten() // NOTE: tricky
         .collect::<Vec<_>>();
 
-    println!("\nsolving...\n");
+    textln!("\nsolving...\n");
     let start = Instant::now();
-    navigator.naive_approach_representative_search_show(ignored_atoms.into_iter());
+    navigator.naive_approach_representative_search_show(ignored_atoms.into_iter())?;
     let elapsed = start.elapsed();
 
-    println!("\ncall            : --naive-repr-search");
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("\ncall            : --naive-repr-search");
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn perfect_sample(navigator: &mut Navigator, mut input: Input) {
-    let mut h = "";
-    let mut heuristic = match input.next() {
-        Some("ediv") => {
-            h = "ediv";
-            crate::soe::Heuristic::Ediv
+pub fn perfect_sample(navigator: &mut Navigator, mut input: Input) -> Result<()> {
+    let (h, mut heuristic) = match input.next() {
+        Some("ediv") => ("ediv", crate::cover::Heuristic::Ediv),
+        Some("erep") => ("erep", crate::cover::Heuristic::Erep),
+        heuristic => {
+            return Err(NavigatorError::Unsupported(format!(
+                "heuristic {:?}, expected one of `ediv`, `erep`",
+                heuristic.unwrap_or_default()
+            )))
         }
-        Some("erep") => {
-            h = "erep";
-            crate::soe::Heuristic::Erep
-        }
-        _ => unimplemented!(),
     };
 
     let ignored_atoms = input
@@ -1091,22 +1829,58 @@ pub fn perfect_sample(navigator: &mut Navigator, mut input: Input) {
         .flatten() // NOTE: tricky
         .collect::<Vec<_>>();
 
-    println!("\nsolving...\n");
+    textln!("\nsolving...\n");
     let start = Instant::now();
-    heuristic.collect_show(
+    let sample = heuristic.collect_show(
         navigator,
         &[],
         &ignored_atoms,
         std::collections::HashSet::new(),
         &navigator.current_facets.0.clone(),
-    );
+    )?;
     let elapsed = start.elapsed();
 
-    println!("\ncall            : --{}", h);
-    println!("elapsed         : {:?}\n", elapsed);
+    textln!("\ncall            : --{}", h);
+    textln!("elapsed         : {:?}\n", elapsed);
+    output::field(
+        "answer_sets",
+        sample
+            .iter()
+            .map(|answer_set| crate::soe::stringify(answer_set))
+            .collect::<Vec<_>>(),
+    );
+    output::elapsed(elapsed);
+
+    Ok(())
 }
 
-pub fn uncertainty_true(navigator: &mut Navigator, mut input: Input) {
+fn show_frequencies(frequencies: &Frequencies) {
+    textln!("{:.2} {:.2}", frequencies.entropy, frequencies.perplexity);
+    for (atom, freq) in &frequencies.frequencies {
+        textln!("{} {}", atom, freq);
+    }
+    textln!("{}", frequencies.n);
+    output::field("entropy", frequencies.entropy);
+    output::field("perplexity", frequencies.perplexity);
+    output::field("frequencies", &frequencies.frequencies);
+    output::field("n", frequencies.n);
+}
+
+pub fn atom_entropy(navigator: &mut Navigator) -> Result<()> {
+    textln!("solving...");
+    show_frequencies(&navigator.atom_entropy()?);
+
+    Ok(())
+}
+
+pub fn abbundance(navigator: &mut Navigator) -> Result<()> {
+    textln!("solving...");
+    show_frequencies(&navigator.abbundance()?);
+
+    Ok(())
+}
+
+pub fn uncertainty_true(navigator: &mut Navigator, mut input: Input) -> Result<()> {
     let (of, target) = (
         &input
             .next()
@@ -1114,10 +1888,14 @@ pub fn uncertainty_true(navigator: &mut Navigator, mut input: Input) {
             .unwrap_or_else(|| vec![]),
         &input.map(|s| s.to_owned()).collect::<Vec<_>>(),
     );
-    println!("{:.2}", navigator.uncertainty_true(of, target))
+    let uncertainty = navigator.uncertainty_true(of, target)?;
+    textln!("{:.2}", uncertainty);
+    output::field("uncertainty", uncertainty);
+
+    Ok(())
 }
 
-pub fn uncertainty_false(navigator: &mut Navigator, mut input: Input) {
+pub fn uncertainty_false(navigator: &mut Navigator, mut input: Input) -> Result<()> {
     let (of, target) = (
         &input
             .next()
@@ -1125,10 +1903,14 @@ pub fn uncertainty_false(navigator: &mut Navigator, mut input: Input) {
             .unwrap_or_else(|| vec![]),
         &input.map(|s| s.to_owned()).collect::<Vec<_>>(),
     );
-    println!("{:.2}", navigator.uncertainty_false(of, target))
+    let uncertainty = navigator.uncertainty_false(of, target)?;
+    textln!("{:.2}", uncertainty);
+    output::field("uncertainty", uncertainty);
+
+    Ok(())
 }
 
-pub fn gini(navigator: &mut Navigator, mut input: Input) {
+pub fn gini(navigator: &mut Navigator, mut input: Input) -> Result<()> {
     let (of, target) = (
         &input
             .next()
@@ -1138,55 +1920,373 @@ pub fn gini(navigator: &mut Navigator, mut input: Input) {
     );
     match target.is_empty() {
         true => {
+            let mut ginis = vec![];
             for a in navigator.current_facets.clone().iter() {
-                let s = &a.repr();
-                println!("{:.2} {}", navigator.gini(of, Some(&[s.to_owned()])), s)
+                let s = a.repr();
+                let gini = navigator.gini(of, Some(&[s.to_owned()]))?;
+                textln!("{:.2} {}", gini, s);
+                ginis.push((s, gini));
             }
+            output::field("ginis", valued(&ginis, "gini"));
+        }
+        _ => {
+            let gini = navigator.gini(of, Some(target))?;
+            textln!("{:.2}", gini);
+            output::field("gini", gini);
         }
-        _ => println!("{:.2}", navigator.gini(of, Some(target))),
     }
+
+    Ok(())
 }
 
-pub fn seperates_best(navigator: &mut Navigator, mut input: Input) {
+pub fn seperates_best(navigator: &mut Navigator, mut input: Input) -> Result<()> {
     let of = &input
         .next()
         .map(|s| vec![s.to_owned()])
-        .unwrap_or_else(|| vec![]);
+        .ok_or_else(|| invalid("provide facet."))?;
 
     let mut hm = HashMap::new();
-    navigator.current_facets.clone().iter().for_each(|a| {
+    for a in navigator.current_facets.clone().iter() {
         if a.repr() != of[0] {
             hm.insert(
                 a.repr(),
-                (100f64 * navigator.gini(of, Some(&[a.repr()]))) as usize,
+                (100f64 * navigator.gini(of, Some(&[a.repr()]))?) as usize,
             );
         }
-    });
-    let max = unsafe { hm.values().min().unwrap_unchecked() };
-    hm.iter().for_each(|(k, v)| {
-        if v == max {
-            println!("{:.2} {}", *v as f64 / 100f64, k);
-        }
-    })
+    }
+    if let Some(max) = hm.values().min() {
+        let ginis = hm
+            .iter()
+            .filter(|(_, v)| *v == max)
+            .map(|(k, v)| (k.to_owned(), *v as f64 / 100f64))
+            .collect::<Vec<_>>();
+        ginis.iter().for_each(|(k, v)| textln!("{:.2} {}", v, k));
+        output::field("ginis", valued(&ginis, "gini"));
+    }
+
+    Ok(())
 }
 
-pub fn seperates_worst(navigator: &mut Navigator, mut input: Input) {
+pub fn seperates_worst(navigator: &mut Navigator, mut input: Input) -> Result<()> {
     let of = &input
         .next()
         .map(|s| vec![s.to_owned()])
-        .unwrap_or_else(|| vec![]);
+        .ok_or_else(|| invalid("provide facet."))?;
 
     let mut hm = HashMap::new();
-    navigator.current_facets.clone().iter().for_each(|a| {
+    for a in navigator.current_facets.clone().iter() {
         hm.insert(
             a.repr(),
-            (100f64 * navigator.gini(of, Some(&[a.repr()]))) as usize,
+            (100f64 * navigator.gini(of, Some(&[a.repr()]))?) as usize,
         );
-    });
-    let min = unsafe { hm.values().max().unwrap_unchecked() };
-    hm.iter().for_each(|(k, v)| {
-        if v == min {
-            println!("{:.2} {}", *v as f64 / 100f64, k);
+    }
+    if let Some(min) = hm.values().max() {
+        let ginis = hm
+            .iter()
+            .filter(|(_, v)| *v == min)
+            .map(|(k, v)| (k.to_owned(), *v as f64 / 100f64))
+            .collect::<Vec<_>>();
+        ginis.iter().for_each(|(k, v)| textln!("{:.2} {}", v, k));
+        output::field("ginis", valued(&ginis, "gini"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(|s| s.to_owned())
+    }
+
+    #[test]
+    fn parse_args_sources_and_clingo_options() {
+        let parsed = parse_args(args(
+            "enc.lp inst.lp - -c n=5 --const m=2 --opt-mode=optN --sgo --abs --n=7",
+        ))
+        .expect("parsing arguments failed.");
+
+        assert_eq!(parsed.sources, vec!["enc.lp", "inst.lp", "-"]);
+        assert_eq!(
+            parsed.clingo_args,
+            vec!["-c", "n=5", "--const", "m=2", "--opt-mode=optN"]
+        );
+        assert_eq!(parsed.n, 7);
+        assert!(matches!(
+            parsed.mode,
+            Mode::StrictlyGoalOriented(Weight::Absolute)
+        ));
+
+        // values of clingo options are no program files
+        let parsed = parse_args(args(
+            "--opt-mode optN prog.lp --configuration crafty --stats",
+        ))
+        .expect("parsing arguments failed.");
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+        assert_eq!(
+            parsed.clingo_args,
+            vec!["--opt-mode", "optN", "--configuration", "crafty", "--stats"]
+        );
+        assert!(parse_args(args("prog.lp --opt-mode")).is_none());
+    }
+
+    #[test]
+    fn parse_mode_entropy() {
+        assert!(matches!(
+            parse_mode((Some("--sgo"), Some("--ent"))),
+            Some(Mode::StrictlyGoalOriented(Weight::Entropy))
+        ));
+        assert!(matches!(
+            parse_mode((Some("--explore"), Some("--information-gain"))),
+            Some(Mode::Explore(Weight::InformationGain))
+        ));
+        assert!(matches!(
+            parse_mode((Some("--ig"), None)),
+            Some(Mode::GoalOriented(Weight::InformationGain))
+        ));
+
+        let parsed = parse_args(args("prog.lp --expl --ent")).expect("parsing arguments failed.");
+        assert!(parsed.clingo_args.is_empty());
+        assert!(matches!(parsed.mode, Mode::Explore(Weight::Entropy)));
+    }
+
+    #[test]
+    fn parse_mode_declared() {
+        assert!(matches!(
+            parse_mode((Some("--sgo"), Some("--weight=cost"))),
+            Some(Mode::StrictlyGoalOriented(Weight::Declared(name))) if name == "cost"
+        ));
+        assert!(parse_mode((Some("--go"), Some("--weight="))).is_none());
+        assert!(parse_mode((Some("--weight=cost"), None)).is_none());
+
+        let parsed =
+            parse_args(args("prog.lp --expl --weight=cost/2")).expect("parsing arguments failed.");
+        assert!(parsed.clingo_args.is_empty());
+        assert!(matches!(
+            parsed.mode,
+            Mode::Explore(Weight::Declared(name)) if name == "cost/2"
+        ));
+    }
+
+    #[test]
+    fn parse_args_session() {
+        let parsed = parse_args(args("--session saved.json")).expect("parsing arguments failed.");
+        assert_eq!(parsed.session.as_deref(), Some("saved.json"));
+        assert!(parsed.sources.is_empty());
+
+        let parsed = parse_args(args("--session=saved.json --workers 2"))
+            .expect("parsing arguments failed.");
+        assert_eq!(parsed.session.as_deref(), Some("saved.json"));
+        assert!(parse_args(args("--session")).is_none());
+    }
+
+    #[test]
+    fn parse_args_script() {
+        let parsed =
+            parse_args(args("prog.lp --script nightly.fasb")).expect("parsing arguments failed.");
+        assert_eq!(parsed.script.as_deref(), Some("nightly.fasb"));
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+
+        let parsed =
+            parse_args(args("prog.lp --script=nightly.fasb")).expect("parsing arguments failed.");
+        assert_eq!(parsed.script.as_deref(), Some("nightly.fasb"));
+        assert!(parsed.clingo_args.is_empty());
+    }
+
+    #[test]
+    fn parse_args_output() {
+        let parsed = parse_args(args("prog.lp --output json")).expect("parsing arguments failed.");
+        assert_eq!(parsed.output, Some(Format::Json));
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+
+        let parsed = parse_args(args("prog.lp --output=text")).expect("parsing arguments failed.");
+        assert_eq!(parsed.output, Some(Format::Text));
+
+        assert!(parse_args(args("prog.lp --output=yaml")).is_none());
+        assert!(!parsed.tui && !parsed.rpc);
+        assert!(
+            parse_args(args("prog.lp --rpc"))
+                .expect("parsing arguments failed.")
+                .rpc
+        );
+        assert!(
+            parse_args(args("prog.lp --tui"))
+                .expect("parsing arguments failed.")
+                .tui
+        );
+    }
+
+    #[test]
+    fn parse_args_subcommand() {
+        let parsed = parse_args(
+            args("weights prog.lp --mode sgo --weight fc --route")
+                .chain(["a ~b(1,2)".to_owned(), "--route=c".to_owned()]),
+        )
+        .expect("parsing arguments failed.");
+        assert_eq!(parsed.subcommand, Some(Subcommand::Weights));
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+        assert!(matches!(
+            parsed.mode,
+            Mode::StrictlyGoalOriented(Weight::FacetCounting)
+        ));
+        assert_eq!(parsed.route, vec!["a", "~b(1,2)", "c"]);
+
+        let parsed = parse_args(args("sample prog.lp --heuristic erep --weight cost"))
+            .expect("parsing arguments failed.");
+        assert_eq!(
+            parsed.subcommand,
+            Some(Subcommand::Sample(Some("erep".to_owned())))
+        );
+        assert!(matches!(
+            parsed.mode,
+            Mode::GoalOriented(Weight::Declared(ref name)) if name == "cost"
+        ));
+
+        let parsed = parse_args(args("prog.lp count")).expect("parsing arguments failed.");
+        assert!(parsed.subcommand.is_none());
+        assert_eq!(parsed.sources, vec!["prog.lp", "count"]);
+
+        let parsed =
+            parse_args(args("serve prog.lp --port 8000")).expect("parsing arguments failed.");
+        assert_eq!(parsed.subcommand, Some(Subcommand::Serve(8000)));
+        let parsed = parse_args(args("serve prog.lp")).expect("parsing arguments failed.");
+        assert_eq!(
+            parsed.subcommand,
+            Some(Subcommand::Serve(serve::DEFAULT_PORT))
+        );
+
+        assert!(parse_args(args("count prog.lp --heuristic erep")).is_none());
+        assert!(parse_args(args("count prog.lp --port 8000")).is_none());
+        assert!(parse_args(args("count prog.lp --mode up")).is_none());
+    }
+
+    #[test]
+    fn parse_args_defaults() {
+        let parsed = parse_args(args("prog.lp")).expect("parsing arguments failed.");
+
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+        assert!(parsed.clingo_args.is_empty());
+        assert_eq!(parsed.n, 3);
+        assert!(matches!(
+            parsed.mode,
+            Mode::GoalOriented(Weight::FacetCounting)
+        ));
+
+        assert!(parse_args(args("prog.lp -c")).is_none());
+        assert!(parsed.projection.is_none());
+    }
+
+    #[test]
+    fn parse_args_projection() {
+        let parsed = parse_args(args("prog.lp --project")).expect("parsing arguments failed.");
+        assert_eq!(parsed.projection, Some(Projection::Show));
+        assert!(parsed.clingo_args.is_empty());
+
+        let parsed =
+            parse_args(args("prog.lp --project=p/2,q/1")).expect("parsing arguments failed.");
+        assert_eq!(
+            parsed.projection,
+            Some(Projection::Signatures(vec![
+                ("p".to_owned(), 2),
+                ("q".to_owned(), 1)
+            ]))
+        );
+
+        assert!(parse_args(args("prog.lp --project=p")).is_none());
+    }
+
+    #[test]
+    fn parse_args_optimization() {
+        let parsed = parse_args(args("prog.lp --optimal")).expect("parsing arguments failed.");
+        assert_eq!(parsed.optimization, Some(Optimization::Optimal));
+
+        let parsed =
+            parse_args(args("prog.lp --cost-bound=3,2")).expect("parsing arguments failed.");
+        assert_eq!(parsed.optimization, Some(Optimization::Bounded(vec![3, 2])));
+
+        assert!(parse_args(args("prog.lp --cost-bound=x")).is_none());
+    }
+
+    #[test]
+    fn parse_args_threads() {
+        let parsed = parse_args(args("prog.lp --threads=4")).expect("parsing arguments failed.");
+        assert_eq!(parsed.clingo_args, vec!["--parallel-mode=4"]);
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+
+        let parsed = parse_args(args("prog.lp -t 2")).expect("parsing arguments failed.");
+        assert_eq!(parsed.clingo_args, vec!["-t", "2"]);
+        assert_eq!(parsed.sources, vec!["prog.lp"]);
+
+        assert!(parse_args(args("prog.lp --threads=0")).is_none());
+    }
+
+    #[test]
+    fn parse_args_workers() {
+        let parsed =
+            parse_args(args("prog.lp --workers 4 --sgo")).expect("parsing arguments failed.");
+        assert_eq!(parsed.workers, Some(4));
+        assert!(parsed.sources == vec!["prog.lp".to_owned()]);
+        assert_eq!(
+            parse_args(args("prog.lp --workers=2")).and_then(|p| p.workers),
+            Some(2)
+        );
+        assert!(parse_args(args("prog.lp --workers 0")).is_none());
+        assert!(parse_args(args("prog.lp --workers")).is_none());
+    }
+
+    #[test]
+    fn parse_args_counting() {
+        let parsed = parse_args(args("prog.lp --counting=approx,0.5,0.9"))
+            .expect("parsing arguments failed.");
+        assert_eq!(
+            parsed.counting,
+            Some(Counting::Approximate {
+                tolerance: 0.5,
+                confidence: 0.9
+            })
+        );
+        assert_eq!(parse_args(args("prog.lp")).map(|p| p.counting), Some(None));
+        assert!(parse_args(args("prog.lp --counting=approx,2")).is_none());
+    }
+
+    #[test]
+    fn parse_args_cache_dir() {
+        let parsed =
+            parse_args(args("prog.lp --cache-dir=/tmp/fasb")).expect("parsing arguments failed.");
+        assert_eq!(parsed.cache_dir.as_deref(), Some("/tmp/fasb"));
+        assert!(parsed.clingo_args.is_empty());
+        assert_eq!(parse_args(args("prog.lp")).map(|p| p.cache_dir), Some(None));
+
+        let parsed = parse_args(args("prog.lp --cache-capacity=facets=10"))
+            .expect("parsing arguments failed.");
+        assert_eq!(
+            parsed.cache_capacity,
+            Some(CacheCapacity {
+                facets: 10,
+                ..CacheCapacity::default()
+            })
+        );
+        assert!(parse_args(args("prog.lp --cache-capacity=0")).is_none());
+    }
+
+    #[test]
+    fn route_is_maximal_safe_explicit() -> Result<()> {
+        let mut navigator = Navigator::new("a;b. c;d :- b.", 0)?;
+        navigator.activate(&["a".to_owned()], &Mode::GoalOriented(Weight::Absolute))?;
+
+        // routes other than the current one are checked on their own
+        for (route, maximal_safe) in [(vec!["b"], false), (vec!["b", "c"], true), (vec![], false)] {
+            let assumptions = navigator.literals_of(&route)?;
+            assert_eq!(
+                route_is_maximal_safe(&mut navigator, &assumptions)?,
+                maximal_safe
+            );
         }
-    })
+        let assumptions = navigator.active_facets.clone();
+        assert!(route_is_maximal_safe(&mut navigator, &assumptions)?);
+
+        Ok(())
+    }
 }
@@ -2,35 +2,62 @@ pub struct Config<'a> {
     pub name: &'a str,
     pub authors: &'a str,
     pub version: &'a str,
-    pub help: [&'a str; 7],
-    pub manual: [&'a str; 35],
+    pub help: [&'a str; 22],
+    pub manual: [&'a str; 61],
 }
 
 const NAME: &str = env!("CARGO_PKG_NAME");
 const AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const HELP: [&str; 7] = [
-    "usage             : fasb path [mode] [weight] [n]",
+const HELP: [&str; 22] = [
+    "usage             : fasb [command] path... [mode] [weight] [n] [clingo options]",
     "default           : fasb path --goal-oriented --facet-counting --n=3\n",
-    "[REQUIRED] path   : path to the .lp file to read",
+    "[OPTIONAL] command: facets | count | weights | zooms | answer-sets | sample [--heuristic ediv | --heuristic erep] | components; prints the result for the route given by --route and exits instead of navigating; serve [--port 8080] serves a browser page for navigating on localhost",
+    "[REQUIRED] path   : paths to the .lp files to read; use - to read from stdin",
     "[OPTIONAL] mode   : [--goal-oriented | --go] | [--strictly-goal-oriented | --sgo] | [--explore | --expl]",
-    "[OPTIONAL] weight : [--absolute | --abs] | [--facet-counting | --fc]",
+    "[OPTIONAL] weight : [--absolute | --abs] | [--facet-counting | --fc] | [--entropy | --ent] | [--information-gain | --ig] | --weight=p, declared by `%@fasb weight p/n` in the program; or as --mode sgo --weight fc",
     "[OPTIONAL] n      : u64",
+    "[OPTIONAL] route  : --route \"f0 f1 ... fn\" | --route=f0; activates the whitespace separated facets on startup",
+    "[OPTIONAL] project: --project (by #show) | --project=p/2,q/1 (by signatures); counts distinct projected answer sets",
+    "[OPTIONAL] optimum: --optimal | --cost-bound=3,2; navigates optimal or cost-bounded answer sets only",
+    "[OPTIONAL] count  : --counting=exact | --counting=approx | --counting=approx,<tolerance>,<confidence>; counting under --abs",
+    "[OPTIONAL] threads: --threads=4 | -t 4; number of solver threads, defaults to the number of cpus",
+    "[OPTIONAL] workers: --workers 4; evaluates weights, zooms, filters and components on 4 program replicas concurrently",
+    "[OPTIONAL] cache  : --cache-dir=DIR persists cached results in DIR and restores them on startup; --cache-capacity=500 | --cache-capacity=facets=500,counts=100 bounds the cache tables",
+    "[OPTIONAL] session: --session FILE | --session=FILE; restores program, options, mode and route saved by `:save FILE`, no path needed",
+    "[OPTIONAL] script : --script FILE | --script=FILE; runs the commands in FILE (one per line, % starts a comment) instead of prompting, as do commands piped into stdin; exits with code 1 on the first failing command, e.g., on interactive ones like :s",
+    "[OPTIONAL] output : --output json | --output=json; prints one JSON object per command, with its results, timing and error",
+    "[OPTIONAL] tui    : --tui; browses facets in a full-screen terminal UI instead of prompting for commands",
+    "[OPTIONAL] rpc    : --rpc; answers JSON-RPC 2.0 requests, one per line on stdin, by methods activate, deactivate, facets, weights, zoom, filter, navigate_n, count, route_safe and route_maximal_safe, and sends progress notifications",
+    "[OPTIONAL] clingo : any other option is passed to clingo, e.g., -c n=5, --const n=5, --opt-mode=optN, --configuration=crafty",
+    "\nexample           : fasb encoding.lp instance.lp - -c n=5 --sgo --fc",
+    "                    fasb weights encoding.lp --mode sgo --weight fc --route \"a ~b\"",
     "\nuse `?man` to inspect manual during navigation",
 ];
 
-const MANUAL: [&str; 35] = [
+const MANUAL: [&str; 61] = [
     "fasb supports the following combinations of weights and modes:\n",
     "\t* absolute goal-oriented (--go --abs)",
     "\t* absolute strictly-goal-oriented (--sgo --abs)",
     "\t* absolute explore (--expl --abs)",
     "\t* facet-counting goal-oriented (--go --fc)",
     "\t* facet-counting strictly-goal-oriented (--sgo --fc)",
-    "\t* facet-counting explore (--expl --fc)\n\n",
+    "\t* facet-counting explore (--expl --fc)",
+    "\t* entropy goal-oriented (--go --ent)",
+    "\t* entropy strictly-goal-oriented (--sgo --ent)",
+    "\t* entropy explore (--expl --ent)",
+    "\t* information-gain goal-oriented (--go --ig)",
+    "\t* information-gain strictly-goal-oriented (--sgo --ig)",
+    "\t* information-gain explore (--expl --ig)\n",
+    "entropy weights are the share of the entropy over the remaining answer sets removed by a facet; information-gain weights are the expected entropy reduction in bits of asking whether a facet holds or not\n",
+    "weights declared in the program or in a file it includes by a line `%@fasb weight p/n` are selected by --weight=p (or --weight=p/n), e.g., `:sm --sgo --weight=cost`; the value of such a weight is the sum of the last arguments of the shown brave consequences over p/n, which has to be a non-negative integer, and the weight of a facet is the decrease of the value it causes\n\n",
     "commands:\n:a        activates n provided whitespace separated facets",
     ":d        deactivates n provided whitespace separated facets; if a facet is activated multiple times, any occurence will be deactivated",
     ":cr       clears the current route, i.e., sets empty route as current route",
+    ":u        undoes the last change of the route",
+    ":r        redoes the change of the route undone last",
+    ":bm       bookmarks the current route by `:bm save name`, returns to it by `:bm goto name` and lists bookmarks otherwise",
     ":zha      activates first facet found with zoom in effect higher than or equal to the provided bound",
     ":zla      activates first facet found with zoom in effect lower than or equal to the provided bound",
     ":rss      activates n random facets w.r.t. the specified combination of mode and weight",
@@ -38,9 +65,22 @@ const MANUAL: [&str; 35] = [
     ":s        filter facets w.r.t. to currently used combination of mode and weight, prompts user to activate a filtered facet and calls `?n`",
     ":sn       filter facets w.r.t. to currently used combination of mode and weight, prompts user to activate a filtered facet and calls `?nn`",
     ":sm       switches current combination of mode and weight to specified combination of mode and weight",
+    ":ar       adds the provided rules to the program and grounds them without restarting",
+    ":l        adds the program in the provided file and grounds it without restarting",
+    ":gr       grounds the provided program part, e.g., `step(3)`, declared via #program",
+    ":e        edits the program in a built-in vim-like editor and restarts on it once written, keeping the route",
+    ":tui      browses the current facets, their weights and zooms and some answer sets in a full-screen terminal UI",
+    ":cnt      switches between exact and approximate counting of answer sets",
+    ":cca      clears the cache, including the cache persisted via --cache-dir",
+    ":format   switches between text and JSON output, e.g., `:format json`",
+    ":save     saves program, options, mode and route of the session to the provided file",
+    ":open     restores the session saved to the provided file",
     ":q        exits",
-    "\nqueries:\n?fc       returns the number of current facets",
+    "\nqueries:\n?c        returns the (estimated) number of answer sets under the current route",
+    "?ca       returns the number of cache entries, cache hits and misses and the cache file",
+    "?fc       returns the number of current facets",
     "?fs       returns the current facets",
+    "?h        returns the tree of routes navigated so far, marking the current route by *",
     "?ifs      returns the initial facets",
     "?ifc      returns the number of initial facets",
     "?m        returns the currently used combination of mode and weight",
@@ -48,6 +88,7 @@ const MANUAL: [&str; 35] = [
     "?nn       solves program on current route and by default outputs --n solutions",
     "?rs       returns true, if provided route is safe, false otherwise",
     "?rms      returns true, if provided route is maximal safe, false otherwise",
+    "?why      shows a minimal subset of the current route entailing the provided facet or its exclusion, e.g., why it vanished from the current facets",
     "?src      returns the logic program source code, fasb is reading from",
     "?w        returns the currently used weight value of the provided facet; returns weight of all current facets, if no facet is provided",
     "?wn       returns the currently used weight value of the first n current facets; returns weight of all current facets, if no n is provided",
@@ -55,9 +96,19 @@ const MANUAL: [&str; 35] = [
     "?zn       returns the zoom in effect percentage of the first n current facets; returns zoom in effects of all current facets, if no n is provided",
     "?zh       returns true if zoom in effect of provided facet is higher or equal to provided bound, otherwise false",
     "?zl       returns true if zoom in effect of provided facet is lower or equal to provided bound, otherwise false",
+    "\nat the prompt, the arrow keys browse the lines entered before, kept per program in ~/.fasb/history; Tab completes commands, queries and current facets, including their exclusions `~f`",
     "\nfor a more detailed manual w.r.t. a certain command or query use `?man` and provide the functionality in question",
 ];
 
+/// Short names of the commands and queries listed in the manual, e.g., `:a` or `?fs`.
+pub fn commands() -> impl Iterator<Item = &'static str> {
+    MANUAL
+        .iter()
+        .filter_map(|s| s.rsplit('\n').next()?.split_whitespace().next())
+        .filter(|c| c.starts_with(':') || c.starts_with('?'))
+        .chain(["?man"])
+}
+
 #[cfg(not(tarpaulin_include))]
 pub fn manual_command_or_query(input: &str) {
     match input {
@@ -87,6 +138,58 @@ pub fn manual_command_or_query(input: &str) {
             errors:  no op for route = < > 
             syntax: `:cr`
         "),
+        ":save" | "--save" => println!("
+        `--save`
+            short: `:save`
+            description: saves the paths of the program files (stdin and added rules by content), clingo options, projection, optimization, counting, mode and weight, n and the route to the provided json file
+            parameters: 
+                [REQUIRED] file
+            errors: no op, if the file cannot be written, with error message
+            syntax: `:save session.json`
+        "),
+        ":open" | "--open" => println!("
+        `--open`
+            short: `:open`
+            description: restores the session saved to the provided file, rereading its program files; reports, if the program changed since saving, and drops facets of the route that are no longer known; workers and cache settings are kept
+            parameters: 
+                [REQUIRED] file
+            errors: no op for missing or invalid file with error message
+            syntax: `:open session.json`
+        "),
+        ":u" | "--undo" => println!("
+        `--undo`
+            short: `:u`
+            description: returns to the route before the last change of the route, along with its facets and pace
+            parameters: 
+            errors: no op, if the route was not changed yet or a facet of the previous route is no longer known
+            syntax: `:u`
+        "),
+        ":r" | "--redo" => println!("
+        `--redo`
+            short: `:r`
+            description: returns to the route undone last; after returning to a route any other way, redo follows the routes visited last
+            parameters: 
+            errors: no op, if there is nothing to redo
+            syntax: `:r`
+        "),
+        ":bm" | "--bookmark" => println!("
+        `--bookmark`
+            short: `:bm`
+            description: bookmarks the current route by name or returns to a bookmarked route; lists all bookmarks, if no parameters are provided
+            parameters: 
+                save name
+                goto name
+            errors: no op for unknown bookmark with error message
+            syntax: `:bm save name`, `:bm goto name`, `:bm`
+        "),
+        "?h" | "?-history" => println!("
+        `?-history`
+            short: `?h`
+            description: returns the tree of routes navigated so far, one change per line; undoing changes and taking other steps branches the tree, * marks the current route and bookmarks are given in brackets
+            parameters: 
+            errors: 
+            syntax: `?h`
+        "),
         ":zha" | "--zoom-higher-than-and-activate" => println!("
         `--zoom-higher-than-and-activate`
             short: `:zha`
@@ -145,19 +248,104 @@ pub fn manual_command_or_query(input: &str) {
             short: `:sm`
             description: switches current combination of mode and weight to specified combination of mode and weight
             parameters: 
-                [REQUIRED] mode
-                [REQUIRED] weight 
+                [REQUIRED] mode; --go, --sgo or --expl
+                [REQUIRED] weight; --abs, --fc, --ent, --ig or --weight=p for a weight declared by `%@fasb weight p/n`
             errors: no op for invalid combination of mode and weight with error message
-            syntax: `:sm`
+            syntax: `:sm mode weight`, e.g., `:sm --sgo --ig`
+        "),
+        ":ar" | ":add-rule" | "--add-rule" => println!("
+        `--add-rule`
+            short: `:ar`
+            description: adds the provided rules as a new program part and grounds it (multi-shot); facets are recomputed and the current route is kept as long as it stays satisfiable
+            parameters: 
+                [REQUIRED] rules `r0 r1 ... rn`
+            errors: no op for invalid rules with error message
+            syntax: `:ar a :- b.`
+        "),
+        ":l" | ":load" | "--load" => println!("
+        `--load`
+            short: `:l`
+            description: adds the program in the provided file as a new program part and grounds it (multi-shot); facets are recomputed and the current route is kept as long as it stays satisfiable
+            parameters: 
+                [REQUIRED] path
+            errors: no op for invalid path or program with error message
+            syntax: `:l file.lp`
+        "),
+        ":gr" | ":ground" | "--ground" => println!("
+        `--ground`
+            short: `:gr`
+            description: grounds a program part declared via `#program` in the loaded program; facets are recomputed and the current route is kept as long as it stays satisfiable
+            parameters: 
+                [REQUIRED] part `name(arg0,arg1,...,argn)`
+            errors: no op for unknown or unparsable program parts with error message
+            syntax: `:gr step(3)`
+        "),
+        ":e" | ":edit" | "--edit" => println!("
+        `--edit`
+            short: `:e`
+            description: opens the program as loaded in a built-in editor supporting basic vim keys (h, j, k, l, 0, $, x, dd, i, a, A, o, O and ESC) and commands (:w, :q, :q!, :wq); once changes are written and the editor is quit, the program is grounded anew, programs added by `:ar` or `:l` and parts grounded by `:gr` are applied again, facets are recomputed and the route is replayed, reporting the facets dropped from it
+            parameters: 
+            errors: no op for unsatisfiable or invalid programs with error message; requires a terminal
+            syntax: `:e`
+        "),
+        ":tui" | "--tui" => println!("
+        `--tui`
+            short: `:tui`
+            description: opens a full-screen terminal UI with panes for the current route, the current facets grouped by predicate along with their weights (w) and zooms (z) under the current combination of mode and weight, and the first --n answer sets; j and k (or the arrow keys) select a facet, enter or + activates it, ~ or - activates its exclusion, u and r undo and redo, c clears the route and q returns to the prompt; every pane is updated after the route changed
+            parameters: 
+            errors: errors of single actions are shown in the status line; requires a terminal
+            syntax: `:tui`
+        "),
+        ":cnt" | "--counting" => println!("
+        `--counting`
+            short: `:cnt`
+            description: switches between exact and approximate counting of answer sets, which is used by absolute weights; approximate counts are within a factor of 1 + tolerance of the exact count with probability of at least confidence; prints the current counting mode, if none is provided
+            parameters: 
+                [OPTIONAL] counting `exact` | `approx` | `approx,<tolerance>,<confidence>`
+            errors: no op for invalid input with error message
+            syntax: `:cnt approx,0.8,0.9`
+        "),
+        ":cca" | "--clear-cache" => println!("
+        `--clear-cache`
+            short: `:cca`
+            description: clears the cache of weights, counts and consequences; with --cache-dir the persisted cache file of the current program is removed as well
+            parameters: 
+            errors: no op for unremovable cache files with error message
+            syntax: `:cca`
+        "),
+        ":format" | "--format" => println!("
+        `--format`
+            short: `:format`
+            description: switches between text output and JSON output, which prints one JSON object per command with fields `command`, `ok`, the results of the command, e.g., `facets`, `weights` or `answer_sets`, `elapsed` in seconds and `error`, if the command failed; prints the current format, if none is provided
+            parameters: 
+                [OPTIONAL] format `text` | `json`
+            errors: no op for unknown formats with error message
+            syntax: `:format json`
         "),
         ":q" | "--quit" => println!("
         `--quit`
             short: `:q`
-            description: exits
+            description: exits; with --cache-dir the cache is persisted before
             parameters: 
             errors: 
             syntax: `:q`
         "),
+        "?ca" | "?-cache" => println!("
+        `?-cache`
+            short: `?ca`
+            description: returns the number of cache entries, the number of cache hits and misses as well as the hit rate since startup, and the file the cache is persisted to on `:q`, if --cache-dir is provided
+            parameters: 
+            errors: 
+            syntax: `?ca`
+        "),
+        "?c" | "?-count" => println!("
+        `?-count`
+            short: `?c`
+            description: returns the number of answer sets under the current route; under approximate counting together with its confidence interval
+            parameters: 
+            errors: 
+            syntax: `?c`
+        "),
         "?fc" | "?-facets-count" => println!("
         `?-facets-count`
             short: `?fc`
@@ -239,6 +427,15 @@ pub fn manual_command_or_query(input: &str) {
             errors: no op for invalid syntax or invalid facets
             syntax: `?rms < f0 f2 ... fn >`, `?rms + f0 f1 ... fn`, `?rms `
         "),
+        "?why" | "?-why" => println!("
+        `?-why`
+            short: `?why`
+            description: explains why a facet is no current facet; shows the current route along with a minimal subset of it, which entails the provided facet `f` (`f` is cautious) or its exclusion `~f` (`f` is impossible)
+            parameters: 
+                facet
+            errors: unknown atoms
+            syntax: `?why f`
+        "),
         "?src" | "?-source" => println!("
         `?-source`
             short: `?src`
@@ -1,37 +1,452 @@
+//! Built-in editor for the program, see `:e`.
+//!
+//! Supports a basic subset of vim: in normal mode `h`, `j`, `k`, `l` (or the arrow keys), `0`,
+//! `$`, `x`, `dd`, `i`, `a`, `A`, `o` and `O`, and the commands `:w`, `:q`, `:q!` and `:wq`;
+//! `ESC` returns from insert mode to normal mode.
+
+use std::io::{stdin, stdout, IsTerminal};
+
 use ncurses::*;
 
-const START: [&'static str; 3] = [
-    "basic vim support (:w, :q, normal mode, insert mode)",
-    "type i\t to edit text",
-    "type ESC+:wq\t to write and exit",
-];
+use crate::navigator::NavigatorError;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+const START: &str = "type i to edit text, ESC+:wq to write and exit, ESC+:q! to discard changes";
+const TAB: &str = "    ";
+
+const ESC: char = '\u{1b}';
+const DELETE: char = '\u{7f}';
+const BACKSPACE: char = '\u{8}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Char(char),
+    Code(i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    Insert,
+    Command(String),
+}
+
+/// Lines of the edited text along with the cursor position in chars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Buffer {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+}
+impl Buffer {
+    fn new(text: &str) -> Self {
+        let mut lines = text
+            .lines()
+            .map(|line| line.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        if lines.is_empty() {
+            lines.push(vec![]);
+        }
+
+        Self {
+            lines,
+            row: 0,
+            col: 0,
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.iter().chain(['\n'].iter()).collect::<String>())
+            .collect()
+    }
+
+    fn line(&self) -> &Vec<char> {
+        &self.lines[self.row]
+    }
+
+    fn left(&mut self) {
+        self.col = self.col.saturating_sub(1);
+    }
+
+    fn right(&mut self) {
+        self.col = (self.col + 1).min(self.line().len());
+    }
+
+    fn up(&mut self) {
+        self.row = self.row.saturating_sub(1);
+        self.col = self.col.min(self.line().len());
+    }
+
+    fn down(&mut self) {
+        self.row = (self.row + 1).min(self.lines.len() - 1);
+        self.col = self.col.min(self.line().len());
+    }
+
+    fn start(&mut self) {
+        self.col = 0;
+    }
+
+    fn end(&mut self) {
+        self.col = self.line().len();
+    }
+
+    fn insert(&mut self, c: char) {
+        let col = self.col;
+        self.lines[self.row].insert(col, c);
+        self.col += 1;
+    }
+
+    /// Splits the line at the cursor.
+    fn newline(&mut self) {
+        let rest = self.lines[self.row].split_off(self.col);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    /// Removes the char before the cursor, joining lines at the start of a line.
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let col = self.col;
+            self.lines[self.row].remove(col);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line().len();
+            self.lines[self.row].extend(line);
+        }
+    }
+
+    /// Removes the char under the cursor.
+    fn delete(&mut self) {
+        if self.col < self.line().len() {
+            let col = self.col;
+            self.lines[self.row].remove(col);
+        }
+    }
+
+    fn delete_line(&mut self) {
+        match self.lines.len() > 1 {
+            true => {
+                self.lines.remove(self.row);
+                self.row = self.row.min(self.lines.len() - 1);
+            }
+            _ => self.lines[0].clear(),
+        }
+        self.col = 0;
+    }
+
+    fn open_below(&mut self) {
+        self.lines.insert(self.row + 1, vec![]);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    fn open_above(&mut self) {
+        self.lines.insert(self.row, vec![]);
+        self.col = 0;
+    }
+}
+
+/// State of an editing session; keys are applied by [`Editor::press`].
+#[derive(Debug, Clone)]
+pub(crate) struct Editor {
+    buffer: Buffer,
+    mode: Mode,
+    /// whether the buffer changed since it was written last
+    modified: bool,
+    /// text written last, if changes were written
+    written: Option<String>,
+    /// first `d` of `dd`
+    pending: bool,
+    message: String,
+}
+impl Editor {
+    pub(crate) fn new(text: &str) -> Self {
+        Self {
+            buffer: Buffer::new(text),
+            mode: Mode::Normal,
+            modified: false,
+            written: None,
+            pending: false,
+            message: START.to_owned(),
+        }
+    }
+
+    /// Text written last, if any changes were written.
+    pub(crate) fn written(self) -> Option<String> {
+        self.written
+    }
+
+    /// Applies `key`; returns `false` once the editor is quit.
+    pub(crate) fn press(&mut self, key: Key) -> bool {
+        let pending = std::mem::take(&mut self.pending);
+
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.normal(key, pending),
+            Mode::Insert => self.insert(key),
+            Mode::Command(command) => return self.command(key, command),
+        }
+
+        true
+    }
+
+    fn normal(&mut self, key: Key, pending: bool) {
+        match key {
+            Key::Char('h') | Key::Code(KEY_LEFT) => self.buffer.left(),
+            Key::Char('l') | Key::Code(KEY_RIGHT) => self.buffer.right(),
+            Key::Char('k') | Key::Code(KEY_UP) => self.buffer.up(),
+            Key::Char('j') | Key::Code(KEY_DOWN) => self.buffer.down(),
+            Key::Char('0') | Key::Code(KEY_HOME) => self.buffer.start(),
+            Key::Char('$') | Key::Code(KEY_END) => self.buffer.end(),
+            Key::Char('x') | Key::Code(KEY_DC) => {
+                self.buffer.delete();
+                self.modified = true;
+            }
+            Key::Char('d') if pending => {
+                self.buffer.delete_line();
+                self.modified = true;
+            }
+            Key::Char('d') => self.pending = true,
+            Key::Char('i') => self.mode = Mode::Insert,
+            Key::Char('a') => {
+                self.buffer.right();
+                self.mode = Mode::Insert;
+            }
+            Key::Char('A') => {
+                self.buffer.end();
+                self.mode = Mode::Insert;
+            }
+            Key::Char('o') => {
+                self.buffer.open_below();
+                self.modified = true;
+                self.mode = Mode::Insert;
+            }
+            Key::Char('O') => {
+                self.buffer.open_above();
+                self.modified = true;
+                self.mode = Mode::Insert;
+            }
+            Key::Char(':') => self.mode = Mode::Command(String::new()),
+            _ => (),
+        }
+    }
+
+    fn insert(&mut self, key: Key) {
+        self.mode = Mode::Insert;
+        match key {
+            Key::Char(ESC) => self.mode = Mode::Normal,
+            Key::Code(KEY_LEFT) => self.buffer.left(),
+            Key::Code(KEY_RIGHT) => self.buffer.right(),
+            Key::Code(KEY_UP) => self.buffer.up(),
+            Key::Code(KEY_DOWN) => self.buffer.down(),
+            Key::Code(KEY_HOME) => self.buffer.start(),
+            Key::Code(KEY_END) => self.buffer.end(),
+            Key::Char(DELETE) | Key::Char(BACKSPACE) | Key::Code(KEY_BACKSPACE) => {
+                self.buffer.backspace();
+                self.modified = true;
+            }
+            Key::Code(KEY_DC) => {
+                self.buffer.delete();
+                self.modified = true;
+            }
+            Key::Char('\n') | Key::Char('\r') | Key::Code(KEY_ENTER) => {
+                self.buffer.newline();
+                self.modified = true;
+            }
+            Key::Char(c) if c == '\t' || !c.is_control() => {
+                self.buffer.insert(c);
+                self.modified = true;
+            }
+            _ => (),
+        }
+    }
+
+    fn command(&mut self, key: Key, mut command: String) -> bool {
+        match key {
+            Key::Char(ESC) => (),
+            Key::Char(DELETE) | Key::Char(BACKSPACE) | Key::Code(KEY_BACKSPACE) => {
+                if command.pop().is_some() {
+                    self.mode = Mode::Command(command);
+                }
+            }
+            Key::Char('\n') | Key::Char('\r') | Key::Code(KEY_ENTER) => {
+                return self.execute(command.trim())
+            }
+            Key::Char(c) if !c.is_control() => {
+                command.push(c);
+                self.mode = Mode::Command(command);
+            }
+            _ => self.mode = Mode::Command(command),
+        }
+
+        true
+    }
+
+    fn execute(&mut self, command: &str) -> bool {
+        match command {
+            "w" => self.write(),
+            "wq" | "x" => {
+                self.write();
+                return false;
+            }
+            "q" if self.modified => {
+                self.message = "unsaved changes, use :wq to write or :q! to discard them".to_owned()
+            }
+            "q" | "q!" => return false,
+            _ => self.message = format!("unknown command :{}", command),
+        }
+
+        true
+    }
+
+    fn write(&mut self) {
+        if self.modified {
+            self.written = Some(self.buffer.text());
+            self.modified = false;
+        }
+        self.message = format!("{} lines written", self.buffer.lines.len());
+    }
+
+    fn status(&self) -> String {
+        let (row, col) = (self.buffer.row + 1, self.buffer.col + 1);
+        match &self.mode {
+            Mode::Command(command) => format!(":{}", command),
+            Mode::Insert => format!("-- INSERT -- {},{}", row, col),
+            Mode::Normal => format!("{} {},{}", self.message, row, col),
+        }
+    }
+}
+
+/// Columns taken by `line` on screen, expanding tabs.
+fn expand(line: &[char]) -> String {
+    line.iter()
+        .map(|c| match c {
+            '\t' => TAB.to_owned(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Draws the lines visible from `top` and `left` on, followed by the status line.
+fn draw(editor: &Editor, top: &mut usize, left: &mut usize) {
+    let (mut height, mut width) = (0, 0);
+    getmaxyx(stdscr(), &mut height, &mut width);
+    let (height, width) = ((height - 1).max(1) as usize, width.max(1) as usize);
+
+    let buffer = &editor.buffer;
+    let x = expand(&buffer.line()[..buffer.col]).chars().count();
+    *top = (*top)
+        .min(buffer.row)
+        .max((buffer.row + 1).saturating_sub(height));
+    *left = (*left).min(x).max((x + 1).saturating_sub(width));
+
+    erase();
+    for (y, line) in buffer.lines.iter().skip(*top).take(height).enumerate() {
+        let line = expand(line)
+            .chars()
+            .skip(*left)
+            .take(width)
+            .collect::<String>();
+        mvaddstr(y as i32, 0, &line);
+    }
+    let status = editor.status().chars().take(width - 1).collect::<String>();
+    mvaddstr(height as i32, 0, &status);
+    mv((buffer.row - *top) as i32, (x - *left) as i32);
+    refresh();
+}
 
-pub(crate) const ESC: i32 = 27;
-pub(crate) const DELETE_ITEM: i32 = 100;
-pub(crate) const EXIT: i32 = 101;
-pub(crate) const VIM_DOWN: i32 = 106;
-pub(crate) const VIM_UP: i32 = 107;
-pub(crate) const QUIT: i32 = 113;
+/// Edits `text` on the terminal until the editor is quit; returns the text written last, if
+/// any changes were written.
+pub(crate) fn edit(text: &str) -> Result<Option<String>> {
+    if !stdin().is_terminal() || !stdout().is_terminal() {
+        return Err(NavigatorError::Unsupported(
+            "editing requires a terminal.".to_owned(),
+        ));
+    }
 
-pub(crate) fn startup() {
+    // return from insert mode without waiting for further escape sequences
+    std::env::set_var("ESCDELAY", "25");
+    setlocale(LcCategory::all, "");
     initscr();
+    raw();
     noecho();
-    curs_set(CURSOR_VISIBILITY::CURSOR_INVISIBLE);
-    start_color();
-}
+    keypad(stdscr(), true);
+
+    let mut editor = Editor::new(text);
+    let (mut top, mut left) = (0, 0);
+    loop {
+        draw(&editor, &mut top, &mut left);
 
-pub(crate) fn start_screen() {
-    let (mut x, mut y) = (0, 0);
-    let w = stdscr();
-    getmaxyx(w, &mut y, &mut x);
-    START.iter().enumerate().for_each(|(i, s)| {
-        let j = s.len() as i32;
-        mvprintw((y / 2) + i as i32, (x / 2) - (j / 2), s);
-    })
+        let key = match get_wch() {
+            Some(WchResult::Char(c)) => match char::from_u32(c) {
+                Some(c) => Key::Char(c),
+                _ => continue,
+            },
+            Some(WchResult::KeyCode(code)) => Key::Code(code),
+            _ => continue,
+        };
+        if !editor.press(key) {
+            break;
+        }
+    }
+    endwin();
+
+    Ok(editor.written())
 }
 
-pub(crate) fn display_command(c: i32, i: i32) {
-    let w = stdscr();
-    let y = getmaxy(w);
-    mvprintw(y - 1 as i32, 0 + i, &format!("{}", c as u8 as char));
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn type_in(editor: &mut Editor, keys: &str) -> bool {
+        keys.chars().all(|c| editor.press(Key::Char(c)))
+    }
+
+    #[test]
+    fn edit_buffer() {
+        let mut buffer = Buffer::new("a;b.\nc :- a.");
+        buffer.down();
+        buffer.end();
+        buffer.backspace();
+        buffer.insert('!');
+        assert_eq!(buffer.text(), "a;b.\nc :- a!\n");
+
+        buffer.start();
+        buffer.backspace();
+        assert_eq!(buffer.text(), "a;b.c :- a!\n");
+        assert_eq!((buffer.row, buffer.col), (0, 4));
+
+        buffer.newline();
+        buffer.delete();
+        assert_eq!(buffer.text(), "a;b.\n :- a!\n");
+
+        buffer.delete_line();
+        buffer.delete_line();
+        assert_eq!(buffer.text(), "\n");
+        assert_eq!(Buffer::new("").lines.len(), 1);
+    }
+
+    #[test]
+    fn edit_keys() {
+        let mut editor = Editor::new("a;b.\n");
+        assert!(type_in(&mut editor, "A c.\u{1b}"));
+        assert!(type_in(&mut editor, "o\u{7f}\u{7f}!\u{1b}:q\n"));
+        assert!(editor.modified);
+
+        assert!(type_in(&mut editor, ":w\n"));
+        assert_eq!(editor.written.as_deref(), Some("a;b. c!\n"));
+        assert!(type_in(&mut editor, "0ddihello\u{1b}:x"));
+        assert!(!editor.press(Key::Char('\n')));
+        assert_eq!(editor.written(), Some("hello\n".to_owned()));
+
+        // unchanged text is not written
+        let mut editor = Editor::new("a.");
+        assert!(!type_in(&mut editor, "jl:wq\n"));
+        assert_eq!(editor.written(), None);
+    }
 }
@@ -0,0 +1,218 @@
+//! History of the routes navigated.
+//!
+//! Routes are kept as a tree rooted at the empty route: taking a step after undoing others
+//! starts a new branch instead of discarding the undone steps, so that any route navigated
+//! before can be returned to by undo, redo or a bookmark.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::utils::Route;
+
+#[derive(Debug, Clone, Serialize)]
+struct Node {
+    route: Route,
+    /// change that led from the parent to this route, e.g., `+a ~b`
+    change: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// child redo returns to, i.e., the one visited last
+    redo: Option<usize>,
+}
+
+/// Serializes as its nodes, the index of the current node and the bookmarked nodes by name.
+#[derive(Debug, Clone, Serialize)]
+pub struct History {
+    nodes: Vec<Node>,
+    current: usize,
+    bookmarks: BTreeMap<String, usize>,
+}
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl History {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                route: Route(vec![]),
+                change: "< >".to_owned(),
+                parent: None,
+                children: vec![],
+                redo: None,
+            }],
+            current: 0,
+            bookmarks: BTreeMap::new(),
+        }
+    }
+
+    /// Records that the route changed to `route` by `change`; a route equal to the current one
+    /// or to one of its children is not recorded twice.
+    pub fn record(&mut self, route: &Route, change: impl Into<String>) {
+        if self.nodes[self.current].route == *route {
+            return;
+        }
+
+        let node = match self.nodes[self.current]
+            .children
+            .iter()
+            .find(|c| self.nodes[**c].route == *route)
+        {
+            Some(c) => *c,
+            _ => {
+                self.nodes.push(Node {
+                    route: route.clone(),
+                    change: change.into(),
+                    parent: Some(self.current),
+                    children: vec![],
+                    redo: None,
+                });
+                let node = self.nodes.len() - 1;
+                self.nodes[self.current].children.push(node);
+
+                node
+            }
+        };
+
+        self.visit(node);
+    }
+
+    pub fn route(&self, node: usize) -> Option<&Route> {
+        self.nodes.get(node).map(|n| &n.route)
+    }
+
+    /// Node undo returns to, i.e., the parent of the current node.
+    pub fn undo_target(&self) -> Option<usize> {
+        self.nodes[self.current].parent
+    }
+
+    /// Node redo returns to, i.e., the child of the current node visited last.
+    pub fn redo_target(&self) -> Option<usize> {
+        self.nodes[self.current].redo
+    }
+
+    /// Makes `node` the current node; redo leads back along the routes visited last.
+    pub fn visit(&mut self, node: usize) {
+        if node >= self.nodes.len() {
+            return;
+        }
+
+        self.mark_path(self.current);
+        self.mark_path(node);
+        self.current = node;
+    }
+
+    fn mark_path(&mut self, mut node: usize) {
+        while let Some(parent) = self.nodes[node].parent {
+            self.nodes[parent].redo = Some(node);
+            node = parent;
+        }
+    }
+
+    /// Bookmarks the current node by `name`, replacing any bookmark of the same name.
+    pub fn bookmark(&mut self, name: impl Into<String>) {
+        self.bookmarks.insert(name.into(), self.current);
+    }
+
+    pub fn bookmarked(&self, name: &str) -> Option<usize> {
+        self.bookmarks.get(name).copied()
+    }
+
+    /// Bookmarks by name along with their routes.
+    pub fn bookmarks(&self) -> impl Iterator<Item = (&str, &Route)> {
+        self.bookmarks
+            .iter()
+            .map(|(name, node)| (name.as_str(), &self.nodes[*node].route))
+    }
+
+    fn fmt_node(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: usize,
+        depth: usize,
+    ) -> std::fmt::Result {
+        let n = &self.nodes[node];
+        let marker = match node == self.current {
+            true => "*",
+            _ => " ",
+        };
+        let names = self
+            .bookmarks
+            .iter()
+            .filter(|(_, b)| **b == node)
+            .map(|(name, _)| format!(" [{}]", name))
+            .collect::<String>();
+
+        writeln!(f, "{} {}{}{}", marker, "  ".repeat(depth), n.change, names)?;
+        n.children
+            .iter()
+            .try_for_each(|c| self.fmt_node(f, *c, depth + 1))
+    }
+}
+/// Tree of routes, one change per line; `*` marks the current route.
+impl std::fmt::Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_node(f, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn route(facets: &[&str]) -> Route {
+        Route(facets.iter().map(|f| f.to_string()).collect())
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::new();
+        history.record(&route(&["a"]), "+a");
+        history.record(&route(&["a", "b"]), "+b");
+        assert_eq!(history.nodes.len(), 3);
+        assert_eq!(history.route(history.current), Some(&route(&["a", "b"])));
+
+        let undone = history.undo_target().expect("no undo target.");
+        history.visit(undone);
+        assert_eq!(history.route(history.current), Some(&route(&["a"])));
+
+        // a step after undo branches off
+        history.record(&route(&["a", "c"]), "+c");
+        assert_eq!(history.nodes.len(), 4);
+        history.visit(history.undo_target().expect("no undo target."));
+        let redone = history.redo_target().expect("no redo target.");
+        assert_eq!(history.route(redone), Some(&route(&["a", "c"])));
+
+        // stepping into a known route does not record it twice
+        history.record(&route(&["a", "b"]), "+b");
+        assert_eq!(history.nodes.len(), 4);
+        assert!(history.redo_target().is_none());
+
+        history.visit(0);
+        assert!(history.undo_target().is_none());
+        assert_eq!(history.redo_target(), Some(1));
+    }
+
+    #[test]
+    fn bookmarks() {
+        let mut history = History::new();
+        history.record(&route(&["a"]), "+a");
+        history.bookmark("left");
+        history.visit(0);
+        history.record(&route(&["~a"]), "+~a");
+        history.bookmark("right");
+
+        let left = history.bookmarked("left").expect("no bookmark.");
+        assert_eq!(history.route(left), Some(&route(&["a"])));
+        assert!(history.bookmarked("up").is_none());
+        assert_eq!(history.bookmarks().count(), 2);
+
+        history.visit(left);
+        let tree = history.to_string();
+        assert_eq!(tree.lines().count(), 3);
+        assert!(tree.contains("*   +a [left]"));
+        assert!(tree.contains("    +~a [right]"));
+    }
+}
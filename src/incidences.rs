@@ -1,6 +1,9 @@
-use crate::navigator::Navigator;
+use crate::navigator::{EnumMode, Navigator, NavigatorError};
+use crate::utils::Repr;
+use indicatif::ProgressBar;
 
 pub(crate) type Matrix<T> = Vec<Vec<T>>;
+type Result<T> = std::result::Result<T, NavigatorError>;
 
 #[allow(dead_code)]
 /// Incidence structure
@@ -22,84 +25,44 @@ pub struct Table {
     initial_facets: Vec<clingo::Symbol>,
 }
 impl Table {
-    pub fn new(nav: &mut Navigator, incidence: Incidences) -> Self {
+    pub fn new(nav: &mut Navigator, incidence: Incidences) -> Result<Self> {
         let initial_facets = nav.current_facets.0.clone();
         let mut incidences = crate::dlx::Matrix::new(initial_facets.len());
-
-        match incidence {
-            Incidences::Brave => {
-                for f in &initial_facets {
-                    let l = *unsafe { nav.literals.get(f).unwrap_unchecked() };
-
-                    let is = unsafe {
-                        nav.consequences(crate::navigator::EnumMode::Brave, &[l])
-                            .unwrap_unchecked()
-                    };
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
-
-
-                    let is = unsafe {
-                        nav.consequences(crate::navigator::EnumMode::Brave, &[l.negate()])
-                            .unwrap_unchecked()
-                    };
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
-                }
-            }
-            Incidences::Cautious => {
-                for f in &initial_facets {
-                    let l = *unsafe { nav.literals.get(f).unwrap_unchecked() };
-
-                    let is = unsafe {
-                        nav.consequences(crate::navigator::EnumMode::Cautious, &[l])
-                            .unwrap_unchecked()
-                    };
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
-
-
-                    let is = unsafe {
-                        nav.consequences(crate::navigator::EnumMode::Cautious, &[l.negate()])
-                            .unwrap_unchecked()
-                    };
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
+        let show_rows = matches!(incidence, Incidences::Facet);
+
+        // rows of a facet and of its inverse, possibly computed concurrently
+        let facets = initial_facets.iter().map(|f| f.repr()).collect::<Vec<_>>();
+        let rows = nav.par_map(
+            facets,
+            move |nav, facet| {
+                let l = nav.literal(facet)?;
+                match incidence {
+                    Incidences::Brave => Ok((
+                        nav.consequences(EnumMode::Brave, &[l])?,
+                        nav.consequences(EnumMode::Brave, &[l.negate()])?,
+                    )),
+                    Incidences::Cautious => Ok((
+                        nav.consequences(EnumMode::Cautious, &[l])?,
+                        nav.consequences(EnumMode::Cautious, &[l.negate()])?,
+                    )),
+                    _ => Ok((
+                        nav.inclusive_facets(&[l])?.0,
+                        nav.inclusive_facets(&[l.negate()])?.0,
+                    )),
                 }
-            }
-            _ => {
-                for f in &initial_facets {
-                    let l = *unsafe { nav.literals.get(f).unwrap_unchecked() };
-
-                    let is = nav.inclusive_facets(&[l]).0;
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
-                    for b in v {
-                        if *b {print!("1 ")} else {print!("0 ")}
-                    }
-                    println!();
-
-                    let is = nav.inclusive_facets(&[l.negate()]).0;
-                    let v = &initial_facets
-                        .iter()
-                        .map(|x| is.contains(x))
-                        .collect::<Vec<_>>();
-                    incidences.add_row(v);
+            },
+            &ProgressBar::hidden(),
+        )?;
+
+        for row in rows {
+            let (inclusive, exclusive) = row?;
+            for is in [inclusive, exclusive] {
+                let v = &initial_facets
+                    .iter()
+                    .map(|x| is.contains(x))
+                    .collect::<Vec<_>>();
+                incidences.add_row(v);
+                if show_rows {
                     for b in v {
                         if *b {print!("1 ")} else {print!("0 ")}
                     }
@@ -108,10 +71,10 @@ impl Table {
             }
         }
 
-        Self {
+        Ok(Self {
             incidences,
             initial_facets,
-        }
+        })
     }
     pub fn max_exact_cover(&self) -> Vec<Vec<usize>> {
         let ecs = crate::dlx::solve_all(self.incidences.clone());
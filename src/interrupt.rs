@@ -0,0 +1,53 @@
+//! Interruption of running queries by SIGINT.
+//!
+//! The handler only raises a flag. Solve calls poll it and cancel the search, while loops over
+//! facets stop early and keep what they computed so far.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::navigator::NavigatorError;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs the SIGINT handler; fails if some handler was installed already.
+pub fn install() -> Result<()> {
+    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+        .map_err(|e| NavigatorError::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))
+}
+
+/// Whether SIGINT was received since the last [`reset`].
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+pub fn reset() {
+    INTERRUPTED.store(false, Ordering::SeqCst)
+}
+
+/// Maps an interrupt to `Ok(None)`, such that loops can stop and keep their partial results.
+pub(crate) fn or_interrupted<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Err(NavigatorError::Interrupted) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn or_interrupted_t() {
+        assert!(matches!(or_interrupted(Ok(1)), Ok(Some(1))));
+        assert!(matches!(
+            or_interrupted::<usize>(Err(NavigatorError::Interrupted)),
+            Ok(None)
+        ));
+        assert!(matches!(
+            or_interrupted::<usize>(Err(NavigatorError::Unsatisfiable)),
+            Err(NavigatorError::Unsatisfiable)
+        ));
+    }
+}
@@ -2,10 +2,19 @@ extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
+mod asnc;
 mod cache;
+mod history;
+pub mod interrupt;
 mod navigator;
+mod pool;
+mod session;
+mod soe;
 mod translator;
 mod utils;
 
 pub use crate::navigator::*;
+pub use crate::cache::{CacheCapacity, CacheStats};
+pub use crate::history::History;
+pub use crate::session::*;
 pub use crate::utils::*;
@@ -4,25 +4,36 @@ mod asnc;
 mod cache;
 mod commands;
 mod config;
+mod cover;
 mod dlx;
-//mod editor;
+mod editor;
+mod history;
 mod incidences;
+mod interrupt;
 mod navigator;
+mod output;
+mod pool;
+mod prompt;
+mod rpc;
+mod serve;
+mod session;
 mod soe;
 mod translator;
+mod tui;
 mod utils;
 
 extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
-use std::fs::read_to_string;
-use std::path::Path;
+use std::io::{stdin, IsTerminal, Read};
 use std::time::Instant;
 
 use commands::*;
 use config::{manual_command_or_query, CONFIG};
 use navigator::*;
+use output::textln;
+use session::Session;
 
 type Result<T> = std::result::Result<T, NavigatorError>;
 
@@ -34,9 +45,9 @@ fn clingo_version_str() -> String {
 
 #[cfg(not(tarpaulin_include))]
 fn main() -> Result<()> {
-    let mut args = std::env::args();
-    let arg = match args.nth(1) {
-        Some(s) => s,
+    let mut args = std::env::args().skip(1).peekable();
+    let arg = match args.peek() {
+        Some(s) => s.clone(),
         _ => {
             println!("\nNo program path provided. Use --help or -h for help.\n");
             return Ok(());
@@ -58,197 +69,335 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    let (mut mode, n) = parse_args(args).ok_or(NavigatorError::None)?;
+    let Arguments {
+        mode,
+        n,
+        sources,
+        clingo_args,
+        projection,
+        optimization,
+        counting,
+        cache_dir,
+        cache_capacity,
+        workers,
+        session: saved,
+        script,
+        output: format,
+        subcommand,
+        route,
+        tui,
+        rpc,
+    } = parse_args(args).ok_or(NavigatorError::None)?;
+    if let Some(format) = format {
+        output::set_format(format);
+    }
+    // stdout carries responses only
+    if rpc {
+        output::set_format(output::Format::Json);
+    }
 
-    let path = Path::new(&arg).to_str().ok_or(NavigatorError::None)?;
+    if sources.is_empty() && saved.is_none() {
+        println!("\nNo program path provided. Use --help or -h for help.\n");
+        return Ok(());
+    }
 
     let start = Instant::now();
-    let mut navigator = read_to_string(path).map(|s| Navigator::new(s, n))??;
+    let mut session = match &saved {
+        Some(path) => restore_session(path)?,
+        _ => Session::load(&sources, clingo_args, projection, mode, n)?,
+    };
+    if let Some(optimization) = optimization {
+        session.optimize(optimization)?;
+    }
+    if let Some(counting) = counting {
+        session.set_counting(counting)?;
+    }
+    if let Some(workers) = workers {
+        session.set_workers(workers)?;
+    }
+    if let Some(capacity) = cache_capacity {
+        session.set_cache_capacity(capacity);
+    }
+    if let Some(dir) = cache_dir {
+        let restored = session.set_cache_dir(dir)?;
+        if restored > 0 {
+            textln!("\n[INFO] restored {} cache entries", restored);
+        }
+    }
     let end = start.elapsed();
 
-    let io = match mode {
-        Mode::Io(1) => {
-            //find_perfect_core(&mut navigator);
-            Some(())
-        }
-        Mode::Io(2) => {
-            //k_greedy_search_io(&mut navigator);
-            Some(())
-        }
-        Mode::Io(3) => {
-            //cores_in_io(&mut navigator);
-            Some(())
-        }
-        Mode::Io(4) => {
-            //components_io(&mut navigator);
-            Some(())
-        }
-        Mode::Io(7) => {
-            //find_cores_encoding(&mut navigator);
-            Some(())
+    interrupt::install()?;
+
+    if !route.is_empty() {
+        session.activate(&route)?;
+    }
+
+    if let Some(subcommand) = subcommand {
+        let result = run_subcommand(&mut session, &subcommand).map(|_| false);
+        output::emit(&subcommand.to_string(), &result, interrupt::interrupted());
+        if let Err(e) = result {
+            eprintln!("[ERROR] {}: {}", subcommand, e);
+            std::process::exit(1);
         }
-        Mode::Io(8) => {
-            //naive_approach_representative_sample(&mut navigator);
-            Some(())
+
+        return session.save_cache().map(|_| ());
+    }
+
+    if rpc {
+        rpc::serve(&mut session)?;
+        return session.save_cache().map(|_| ());
+    }
+
+    let script = match script {
+        Some(path) => Some(std::fs::read_to_string(path)?),
+        // commands piped into stdin, unless the program is read from there
+        _ if !stdin().is_terminal() && !sources.iter().any(|s| s == "-") => {
+            let mut commands = String::new();
+            stdin().read_to_string(&mut commands)?;
+            Some(commands)
         }
         _ => None,
     };
-    if io.is_some() {
-        return Ok(());
+    if let Some(script) = script {
+        return run_script(&mut session, &script);
+    }
+
+    if tui {
+        tui::run(&mut session)?;
+        return session.save_cache().map(|_| ());
     }
 
-    println!(
+    textln!(
         "\n{} version {} [clingo version {}]",
         CONFIG.name,
         CONFIG.version,
         clingo_version_str()
     );
 
-    println!("\nelapsed : {:?}", end);
+    textln!("\nelapsed : {:?}", end);
 
-    println!("reading from {}\n", arg);
-    println!("type `?man` for help\n\n");
+    match &saved {
+        Some(path) => textln!("restored session {}\n", path),
+        _ => textln!("reading from {}\n", sources.join(" ")),
+    }
+    textln!("type `?man` for help\n\n");
 
     let mut quit = false;
+    let mut prompt = prompt::Prompt::new(session.navigator().program_id())?;
 
     while !quit {
-        navigator.info();
+        let line = match output::is_text() {
+            true => session.navigator_mut().prompt()?,
+            _ => String::new(),
+        };
+        prompt.set_facets(session.facets());
 
-        let input = navigator.user_input();
+        let input = prompt.read(&line)?;
 
-        if input.is_empty() {
+        interrupt::reset();
+        let result = execute(&mut session, &input);
+
+        if !output::is_text() {
+            if !input.trim().is_empty() {
+                output::emit(&input, &result, interrupt::interrupted());
+            }
+            quit = matches!(result, Ok(true));
             continue;
         }
 
-        let mut input_iter = input.split_whitespace();
-        let command = input_iter.next().expect("unknown error.");
-
-        match command {
-            "?-manual" | "?man" => match input_iter.next() {
-                Some(s) => manual_command_or_query(s),
-                _ => manual(),
-            },
-            "?-source" | "?src" => source(&navigator),
-            "?-facets" | "?fs" => facets(&navigator),
-            "?-facets-count" | "?fc" => facets_count(&navigator),
-            "?-initial-facets" | "?ifs" => initial_facets(&navigator),
-            "?-initial-facets-count" | "?ifc" => initial_facets_count(&navigator),
-            "--activate" | ":a" => activate(&mode, &mut navigator, input_iter),
-            "--deactivate" | ":d" => deactivate(&mode, &mut navigator, input_iter),
-            "--clear-route" | ":cr" => clear_route(&mode, &mut navigator),
-            "--random-safe-steps" | ":rss" => random_safe_steps(&mode, &mut navigator, input_iter),
-            "--random-safe-walk" | ":rsw" => random_safe_walk(&mode, &mut navigator, input_iter),
-            "--step" | ":s" => {
-                let fs = navigator.clone().current_facets;
-
-                match (input_iter.next(), input_iter.next()) {
-                    (None, None) => step(&mode, &mode, &mut navigator, fs.as_ref()),
-                    t => match parse_mode(t) {
-                        Some(m) => step(&mode, &m, &mut navigator, fs.as_ref()),
-                        _ => step(&mode, &mode, &mut navigator, fs.as_ref()),
-                    },
-                }
+        // report and keep the session alive
+        match result {
+            Err(NavigatorError::Interrupted) => println!("\n[INFO] interrupted\n"),
+            Err(e) => println!("\n[ERROR] {}\n", e),
+            Ok(q) if interrupt::interrupted() => {
+                println!("\n[INFO] interrupted, results are partial\n");
+                quit = q;
             }
-            "--step-n" | ":sn" => {
-                let fs = navigator.current_facets.clone();
-                step_n(&mode, &mode, &mut navigator, fs.as_ref(), input_iter);
-            }
-            "?-navigate" | "?n" => navigate(&mut navigator),
-            "?-navigate-n" | "?nn" => navigate_n(&mut navigator, input_iter),
-            "--find-facet-with-zoom-higher-than-and-activate" | ":zha" => {
-                find_facet_with_zoom_higher_than_and_activate(
-                    &mode,
-                    &mode,
-                    &mut navigator,
-                    input_iter,
-                )
-            }
-            "--find-facet-with-zoom-lower-than-and-activate" | ":zla" => {
-                find_facet_with_zoom_lower_than_and_activate(
-                    &mode,
-                    &mode,
-                    &mut navigator,
-                    input_iter,
-                )
+            Ok(q) => quit = q,
+        }
+    }
+
+    Ok(())
+}
+
+/// Commands prompting for input beyond their arguments, which scripts cannot provide.
+const INTERACTIVE: [&str; 9] = [
+    "--step", ":s", "--step-n", ":sn", "--edit", ":edit", ":e", "--tui", ":tui",
+];
+
+/// Executes the commands of `script` line by line, skipping blank lines and comments starting
+/// with `%`; exits with a non-zero code on the first failing or interrupted command, including
+/// interactive ones.
+#[cfg(not(tarpaulin_include))]
+fn run_script(session: &mut Session, script: &str) -> Result<()> {
+    for (i, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+
+        interrupt::reset();
+        let result = execute_script_line(session, line);
+        let interrupted = interrupt::interrupted();
+        output::emit(line, &result, interrupted);
+        match result {
+            Ok(true) => return Ok(()),
+            Ok(_) if !interrupted => (),
+            result => {
+                let e = result.err().unwrap_or(NavigatorError::Interrupted);
+                eprintln!("[ERROR] line {}: {}: {}", i + 1, line, e);
+                std::process::exit(1);
             }
-            "--switch-mode" | ":sm" => match parse_mode((input_iter.next(), input_iter.next())) {
-                Some(m) => {
-                    mode = m;
-                    navigator.update(&mode)
-                }
-                _ => println!("\n[ERROR] unknown mode.\n"),
-            },
-            "?-weight" | "?w" => q_weight(&mode, &mut navigator, input_iter),
-            "?-weight-n" | "?wn" => q_weight_n(&mode, &mut navigator, input_iter),
-            "?-zoom" | "?z" => q_zoom(&mode, &mut navigator, input_iter),
-            "?-zoom-n" | "?zn" => q_zoom_n(&mode, &mut navigator, input_iter),
-            "?-route-safe" | "?rs" => q_route_safe(&mut navigator, input_iter),
-            "?-route-maximal-safe" | "?rms" => q_route_maximal_safe(&mut navigator, input_iter),
-            "?-zoom-higher-than" | "?zh" => q_zoom_higher_than(&mode, &mut navigator, input_iter),
-            "?-zoom-lower-than" | "?zl" => q_zoom_lower_than(&mode, &mut navigator, input_iter),
-            "?-mode" | "?m" => println!("\n{}\n", mode),
-            "?com" => components(&mut navigator),
-            //"?cor" => cores_in_io(&mut navigator), // TODO
-            //"?fpc" => find_perfect_core(&mut navigator), // TODO
-            //":h0" => h0_perfect_sample_search_show(&mut navigator),
-            //"?rcom" => related_components(&mut navigator),
-            //"?str" => ctx.structure(&mut navigator),
-            //"?strn" => ctx.structure(&mut navigator),
-            ":str" => {
-                let table = incidences::Table::new(&mut navigator, incidences::Incidences::Brave);
-                println!("{:?}", table.max_exact_cover());
-                let table =
-                    incidences::Table::new(&mut navigator, incidences::Incidences::Cautious);
-                println!("{:?}", table.max_exact_cover());
-                let table = incidences::Table::new(&mut navigator, incidences::Incidences::Facet);
-                println!("{:?}", table.max_exact_cover());
+        }
+    }
+
+    session.save_cache().map(|_| ())
+}
+
+/// Executes a line of a script like [`execute`], failing on commands prompting for input.
+fn execute_script_line(session: &mut Session, line: &str) -> Result<bool> {
+    match line.split_whitespace().next() {
+        Some(command) if INTERACTIVE.contains(&command) => Err(NavigatorError::Unsupported(
+            format!("{} prompts for input, which scripts cannot provide.", command),
+        )),
+        _ => execute(session, line),
+    }
+}
+
+/// Executes a single command or query; returns whether to quit.
+#[cfg(not(tarpaulin_include))]
+fn execute(session: &mut Session, input: &str) -> Result<bool> {
+    let mut input_iter = input.split_whitespace();
+    let command = match input_iter.next() {
+        Some(command) => command,
+        _ => return Ok(false),
+    };
+
+    let mut quit = false;
+    match command {
+        "?-manual" | "?man" => match input_iter.next() {
+            Some(s) => {
+                manual_command_or_query(s);
+                Ok(())
             }
-            ":aw" => activate_where(&mode, &mut navigator, input_iter),
-            ":aa" => activate_all_of(&mode, &mut navigator, input_iter),
-            ":aff" => activate_from_file(&mode, &mut navigator, input_iter.next().unwrap()), // TODO: unwrap
-            ":dw" => deactivate_where(&mode, &mut navigator, input_iter),
-            // soe
-            ":kg" => k_greedy_search(&mut navigator, input_iter), // Algorithm 2
-            ":nar" => naive_approach_representative_sample(&mut navigator, input_iter), // Algorithm 3
-            ":ps" => perfect_sample(&mut navigator, input_iter), // Algorithm 3
-            ":cc" => cc(&mut navigator, input_iter),
-            ":hole" => hole(&mut navigator, input_iter),
-            ":ep" => navigator.atom_entropy(),
-            ":abb" => navigator.abbundance(),
-            ":ut" => uncertainty_true(&mut navigator, input_iter),
-            ":uf" => uncertainty_false(&mut navigator, input_iter),
-            ":g" => gini(&mut navigator, input_iter),
-            ":gmax" => seperates_best(&mut navigator, input_iter),
-            ":gmin" => seperates_worst(&mut navigator, input_iter),
-            /*
-            ":e" | "--edit" => {
-                editor::startup();
-                editor::start_screen();
-
-                loop {
-                    let c = ncurses::getch();
-                    editor::display_command(c, 1);
-
-                    match c {
-                        editor::VIM_DOWN => {}
-                        editor::QUIT => {
-                            ncurses::flash();
-                            ncurses::clear();
-                            ncurses::endwin();
-                            break;
-                        }
-                        _ => (),
-                    }
-                }
+            _ => manual(),
+        },
+        "?-source" | "?src" => source(session),
+        "?-facets" | "?fs" => facets(session),
+        "?-count" | "?c" => q_count(session),
+        "?-cache" | "?ca" => q_cache(session),
+        "--clear-cache" | ":cca" => clear_cache(session),
+        "?-facets-count" | "?fc" => facets_count(session),
+        "?-initial-facets" | "?ifs" => initial_facets(session),
+        "?-initial-facets-count" | "?ifc" => initial_facets_count(session),
+        "--activate" | ":a" => activate(session, input_iter),
+        "--deactivate" | ":d" => deactivate(session, input_iter),
+        "--clear-route" | ":cr" => clear_route(session),
+        "--undo" | ":u" => undo(session),
+        "--redo" | ":r" => redo(session),
+        "--bookmark" | ":bm" => bookmark(session, input_iter),
+        "?-history" | "?h" => q_history(session),
+        "--save" | ":save" => save_session(session, input_iter),
+        "--open" | ":open" => open_session(session, input_iter),
+        "--add-rule" | ":add-rule" | ":ar" => add_rule(session, input_iter),
+        "--load" | ":load" | ":l" => load(session, input_iter),
+        "--ground" | ":ground" | ":gr" => ground(session, input_iter),
+        "--random-safe-steps" | ":rss" => random_safe_steps(session, input_iter),
+        "--random-safe-walk" | ":rsw" => random_safe_walk(session, input_iter),
+        "--step" | ":s" => {
+            let mode = parse_mode((input_iter.next(), input_iter.next()));
+            step(session, mode)
+        }
+        "--step-n" | ":sn" => step_n(session, input_iter),
+        "?-navigate" | "?n" => navigate(session),
+        "?-navigate-n" | "?nn" => navigate_n(session, input_iter),
+        "--find-facet-with-zoom-higher-than-and-activate" | ":zha" => {
+            find_facet_with_zoom_higher_than_and_activate(session, input_iter)
+        }
+        "--find-facet-with-zoom-lower-than-and-activate" | ":zla" => {
+            find_facet_with_zoom_lower_than_and_activate(session, input_iter)
+        }
+        "--counting" | ":cnt" => switch_counting(session, input_iter),
+        "--switch-mode" | ":sm" => match parse_mode((input_iter.next(), input_iter.next())) {
+            Some(m) => {
+                output::field("mode", &m);
+                session.set_mode(m)
             }
-            */
-            "--quit" | ":q" => quit = true,
-            _ => println!(
-                "\nunknown command or query: {:?}\nuse `?man` to inspect manual\n",
-                input
-            ),
+            _ => Err(NavigatorError::InvalidInput("unknown mode.".to_owned())),
+        },
+        "--format" | ":format" => switch_format(input_iter),
+        "?-weight" | "?w" => q_weight(session, input_iter),
+        "?-weight-n" | "?wn" => q_weight_n(session, input_iter),
+        "?-zoom" | "?z" => q_zoom(session, input_iter),
+        "?-zoom-n" | "?zn" => q_zoom_n(session, input_iter),
+        "?-route-safe" | "?rs" => q_route_safe(session, input_iter),
+        "?-route-maximal-safe" | "?rms" => q_route_maximal_safe(session, input_iter),
+        "?-why" | "?why" => q_why(session, input_iter),
+        "?-zoom-higher-than" | "?zh" => q_zoom_higher_than(session, input_iter),
+        "?-zoom-lower-than" | "?zl" => q_zoom_lower_than(session, input_iter),
+        "?-mode" | "?m" => {
+            textln!("\n{}\n", session.mode());
+            output::field("mode", session.mode());
+            Ok(())
         }
-    }
+        "?com" => components(session),
+        //"?cor" => cores_in_io(&mut navigator), // TODO
+        //"?fpc" => find_perfect_core(&mut navigator), // TODO
+        //":h0" => h0_perfect_sample_search_show(&mut navigator),
+        //"?str" => ctx.structure(&mut navigator),
+        //"?strn" => ctx.structure(&mut navigator),
+        ":str" => structure(session.navigator_mut()),
+        ":aw" => activate_where(session, input_iter),
+        ":aa" => activate_all_of(session, input_iter),
+        ":aff" => match input_iter.next() {
+            Some(path) => activate_from_file(session, path),
+            _ => Err(NavigatorError::InvalidInput("no file provided.".to_owned())),
+        },
+        ":dw" => deactivate_where(session, input_iter),
+        // soe
+        ":kg" => k_greedy_search(session, input_iter), // Algorithm 2
+        ":nar" => naive_approach_representative_sample(session.navigator_mut(), input_iter), // Algorithm 3
+        ":ps" => perfect_sample(session.navigator_mut(), input_iter), // Algorithm 3
+        ":cc" => cc(session.navigator_mut(), input_iter),
+        ":hole" => hole(session.navigator_mut(), input_iter),
+        ":ep" => atom_entropy(session.navigator_mut()),
+        ":abb" => abbundance(session.navigator_mut()),
+        ":ut" => uncertainty_true(session.navigator_mut(), input_iter),
+        ":uf" => uncertainty_false(session.navigator_mut(), input_iter),
+        ":g" => gini(session.navigator_mut(), input_iter),
+        ":gmax" => seperates_best(session.navigator_mut(), input_iter),
+        ":gmin" => seperates_worst(session.navigator_mut(), input_iter),
+        "--edit" | ":edit" | ":e" => edit(session),
+        "--tui" | ":tui" => tui::run(session),
+        "--quit" | ":q" => {
+            quit = true;
+            session.save_cache().map(|_| ())
+        }
+        _ => Err(NavigatorError::InvalidInput(format!(
+            "unknown command or query {:?}, use `?man` to inspect manual",
+            input
+        ))),
+    }?;
 
-    Ok(())
+    Ok(quit)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn script_rejects_interactive_commands() -> Result<()> {
+        let mut session = Session::new("a;b.", Mode::GoalOriented(Weight::Absolute), 0)?;
+        for line in [":s", ":sn 2", ":e", ":tui"] {
+            assert!(matches!(
+                execute_script_line(&mut session, line),
+                Err(NavigatorError::Unsupported(_))
+            ));
+        }
+        assert!(!execute_script_line(&mut session, ":a a")?);
+        assert_eq!(session.route().0, vec!["a".to_owned()]);
+
+        Ok(())
+    }
 }
@@ -1,26 +1,70 @@
 use hashbrown::HashMap as HM;
-use std::collections::HashMap;
+use std::cmp::Eq;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
+use std::fs::read_to_string;
+use std::hash::{Hash, Hasher};
 use std::io::Error as IOError;
-use std::io::{stdin, stdout, Write};
-use std::sync::Arc;
-use std::time::Instant;
-use std::{cmp::Eq, hash::Hash};
-
-use clingo::{ClingoError, Control, Literal, Part, ShowType, SolveMode, SolveResult, Symbol};
-use indicatif::{ProgressBar, ProgressIterator, ProgressStyle};
+use std::io::{stderr, stdin, stdout, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clingo::{
+    ClingoError, Control, Literal, Model, Part, ShowType, SolveMode, SolveResult, Symbol,
+};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use itertools::Itertools;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::cache::CACHE;
+use crate::cache::{self, Cache, CacheCapacity, Key};
+use crate::interrupt;
+use crate::pool::Pool;
 use crate::translator::Atom;
 use crate::utils::{Facets, Repr, Route, ToHashSet};
 
+static REPORT_PROGRESS: AtomicBool = AtomicBool::new(false);
+/// bar of the running query, if progress is reported, see [`report_progress`]
+static PROGRESS: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Progress bar of `len` steps; hidden unless stderr is a terminal, e.g., in scripts.
+pub(crate) fn progress_bar(len: u64) -> ProgressBar {
+    if REPORT_PROGRESS.load(Ordering::SeqCst) {
+        let pb = ProgressBar::with_draw_target(len, ProgressDrawTarget::hidden());
+        *PROGRESS.lock().unwrap_or_else(|e| e.into_inner()) = Some(pb.clone());
+        return pb;
+    }
+
+    match stderr().is_terminal() {
+        true => ProgressBar::new(len),
+        _ => ProgressBar::hidden(),
+    }
+}
+
+/// Hides progress bars and exposes the progress of the running query by [`progress`] instead,
+/// e.g., to send it to clients; resets the progress.
+pub fn report_progress(report: bool) {
+    REPORT_PROGRESS.store(report, Ordering::SeqCst);
+    *PROGRESS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Position and length of the progress bar created last since [`report_progress`], if any.
+pub fn progress() -> Option<(u64, u64)> {
+    PROGRESS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|pb| (pb.position(), pb.length()))
+}
+
 pub fn filter(
     mode: &impl GoalOrientedNavigation,
     navigator: &mut Navigator,
     current_facets: &[Symbol],
-) -> Vec<String> {
+) -> Result<Vec<String>> {
     mode.filter(navigator, current_facets)
 }
 
@@ -28,35 +72,23 @@ fn eval_weight(
     weight: &impl Eval,
     navigator: &mut Navigator,
     facet: &str,
-) -> (usize, Option<usize>) {
+) -> Result<(usize, Option<usize>)> {
     weight.eval_weight(navigator, facet)
 }
 
-fn show_weight(weight: &impl Eval, navigator: &mut Navigator, facet: &str) {
-    weight.show_weight(navigator, facet)
-}
-
-fn show_all_weights(weight: &impl Eval, navigator: &mut Navigator) {
-    weight.show_all_weights(navigator)
-}
-
-fn eval_zoom(weight: &impl Eval, navigator: &mut Navigator, facet: &str) -> (f32, Option<f32>) {
+fn eval_zoom(
+    weight: &impl Eval,
+    navigator: &mut Navigator,
+    facet: &str,
+) -> Result<(f32, Option<f32>)> {
     weight.eval_zoom(navigator, facet)
 }
 
-fn show_all_zooms(weight: &impl Eval, navigator: &mut Navigator) {
-    weight.show_all_zooms(navigator)
-}
-
-fn show_zoom(weight: &impl Eval, navigator: &mut Navigator, facet: &str) {
-    weight.show_zoom(navigator, facet)
-}
-
 fn find_facet_with_zoom_higher_than(
     weight: &impl Eval,
     navigator: &mut Navigator,
     bound: f32,
-) -> Option<String> {
+) -> Result<Option<String>> {
     weight.find_with_zoom_higher_than(navigator, bound)
 }
 
@@ -64,154 +96,326 @@ fn find_facet_with_zoom_lower_than(
     weight: &impl Eval,
     navigator: &mut Navigator,
     bound: f32,
-) -> Option<String> {
+) -> Result<Option<String>> {
     weight.find_with_zoom_lower_than(navigator, bound)
 }
 
 #[derive(Error, Debug)]
 pub enum NavigatorError {
-    #[error("ClingoError: ")]
+    #[error("ClingoError: {0}")]
     Clingo(#[from] ClingoError),
     #[error("Unwrapped None.")]
     None,
-    #[error("IOError: ")]
+    #[error("IOError: {0}")]
     IO(#[from] IOError),
-    #[error("Invalid input.")]
+    #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("cannot parse input: {0:?}")]
+    Parse(String),
+    #[error("solving failed: {0}")]
+    Solver(String),
+    #[error("unknown atom: {0:?}")]
+    UnknownAtom(String),
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+    #[error("program is unsatisfiable.")]
+    Unsatisfiable,
+    #[error("interrupted.")]
+    Interrupted,
 }
 
 type Result<T> = std::result::Result<T, NavigatorError>;
 type Literals = HashMap<Symbol, Literal>;
 
+/// Keeps the literals of projected atoms only, i.e., those possibly contained in some answer set.
+fn project(literals: &mut Literals, projected: &[Symbol]) {
+    let projected = projected.iter().collect::<std::collections::HashSet<_>>();
+    literals.retain(|s, _| projected.contains(s));
+}
+
+pub(crate) fn control_mut(control: &mut Arc<Control>) -> Result<&mut Control> {
+    Arc::get_mut(control).ok_or_else(|| NavigatorError::Solver("control is shared.".to_owned()))
+}
+
+/// Name of the externals guarding the parity constraints of approximate counting.
+const PARITY_GUARD: &str = "__fasb_parity";
+
+/// Number of solver threads requested via `-t` or `--parallel-mode`, e.g., `-t 4` or
+/// `--parallel-mode=4,compete`.
+fn requested_threads(arguments: &[String]) -> Option<usize> {
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let value = match argument.as_str() {
+            "-t" | "--parallel-mode" => arguments.next().map(|s| s.as_str()),
+            s => s
+                .strip_prefix("--parallel-mode=")
+                .or_else(|| s.strip_prefix("-t")),
+        };
+        if let Some(threads) = value.and_then(|v| v.split(',').next()?.parse().ok()) {
+            return Some(threads);
+        }
+    }
+
+    None
+}
+
+/// Interval in which running searches check for interrupts.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Calls `on_model` for each model under `assumptions` as long as it returns `true`. The search
+/// runs asynchronously and is cancelled on interrupt, leaving `control` ready to solve again.
+pub(crate) fn for_each_model(
+    control: &mut Control,
+    assumptions: &[Literal],
+    mut on_model: impl FnMut(&Model) -> Result<bool>,
+) -> Result<()> {
+    let mut handle = control.solve(SolveMode::ASYNC | SolveMode::YIELD, assumptions)?;
+
+    let mut solve = || loop {
+        loop {
+            if interrupt::interrupted() {
+                handle.cancel()?;
+                return Err(NavigatorError::Interrupted);
+            }
+            if handle.wait(POLL) {
+                break;
+            }
+        }
+
+        match handle.model()? {
+            Some(model) if on_model(model)? => handle.resume()?,
+            _ => return Ok(()),
+        }
+    };
+    let result = solve();
+
+    handle.close()?;
+
+    result
+}
+
+fn current_facet_or_err(navigator: &Navigator, facet: &str) -> Result<()> {
+    let f = match facet.starts_with('~') {
+        true => &facet[1..],
+        _ => facet,
+    };
+
+    match Atom(f).parse(&[]) {
+        Some(s) if navigator.current_facets.0.contains(&s) => Ok(()),
+        Some(_) => Err(NavigatorError::InvalidInput(format!(
+            "{:?} is not a current facet.",
+            facet
+        ))),
+        _ => Err(NavigatorError::Parse(facet.to_owned())),
+    }
+}
+
 pub trait Eval {
-    fn eval_weight(&self, navigator: &mut Navigator, facet: &str) -> (usize, Option<usize>);
-    fn show_weight(&self, navigator: &mut Navigator, facet: &str);
-    fn show_all_weights(&self, navigator: &mut Navigator);
-    fn eval_zoom(&self, navigator: &mut Navigator, facet: &str) -> (f32, Option<f32>);
-    fn show_zoom(&self, navigator: &mut Navigator, facet: &str);
-    fn show_all_zooms(&self, navigator: &mut Navigator);
-    fn find_with_zoom_higher_than(&self, navigator: &mut Navigator, bound: f32) -> Option<String>;
-    fn find_with_zoom_lower_than(&self, navigator: &mut Navigator, bound: f32) -> Option<String>;
+    fn eval_weight(&self, navigator: &mut Navigator, facet: &str)
+        -> Result<(usize, Option<usize>)>;
+    /// Normalized weight of `facet` (and its inverse under [`Weight::Absolute`]).
+    fn weight(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>>;
+    /// Normalized weights of all current facets.
+    fn all_weights(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>>;
+    fn eval_zoom(&self, navigator: &mut Navigator, facet: &str) -> Result<(f32, Option<f32>)>;
+    /// Zoom of `facet` (and its inverse under [`Weight::Absolute`]) as a fraction.
+    fn zoom(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>>;
+    /// Zooms of all current facets as fractions.
+    fn all_zooms(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>>;
+    fn find_with_zoom_higher_than(
+        &self,
+        navigator: &mut Navigator,
+        bound: f32,
+    ) -> Result<Option<String>>;
+    fn find_with_zoom_lower_than(
+        &self,
+        navigator: &mut Navigator,
+        bound: f32,
+    ) -> Result<Option<String>>;
 }
 
-#[derive(Debug, Clone)]
+/// Facets among `facets` and their inverses whose weights or zooms are evaluated; weights and
+/// zooms on counts of answer sets come along with those of the inverse facet.
+fn evaluated_facets(weight: &Weight, facets: &[Symbol]) -> Vec<String> {
+    match weight {
+        Weight::Absolute | Weight::Entropy | Weight::InformationGain => {
+            facets.iter().map(|f| f.repr()).collect()
+        }
+        _ => facets
+            .iter()
+            .flat_map(|f| [f.repr(), f.exclusive_repr()])
+            .collect(),
+    }
+}
+
+/// Entropy in bits of the uniform distribution over `count` answer sets.
+fn entropy(count: usize) -> f64 {
+    (count.max(1) as f64).log2()
+}
+
+/// Share of the entropy over `count` answer sets that is removed when `remaining` answer sets
+/// are left.
+fn entropy_reduction(count: usize, remaining: usize) -> f64 {
+    match count > 1 {
+        true => (entropy(count) - entropy(remaining.min(count))) / entropy(count),
+        _ => 0f64,
+    }
+}
+
+/// Expected entropy reduction in bits of learning whether a facet, under which `remaining` out
+/// of `count` answer sets are left, holds; i.e., the entropy over the answer sets minus the
+/// entropy expected after activating either the facet or its inverse.
+fn information_gain(count: usize, remaining: usize) -> f64 {
+    let remaining = remaining.min(count);
+    let p = match count {
+        0 => return 0f64,
+        _ => remaining as f64 / count as f64,
+    };
+
+    entropy(count) - p * entropy(remaining) - (1f64 - p) * entropy(count - remaining)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Weight {
+    /// answer sets removed by a facet
     Absolute,
+    /// facets removed by a facet
     FacetCounting,
+    /// facets remaining under a facet
     Information, // U(.)
+    /// share of the entropy over answer sets removed by a facet
+    Entropy,
+    /// expected entropy reduction of asking whether a facet holds or not
+    InformationGain,
+    /// decrease of the value of a weight declared in the program by `%@fasb weight p/n`, i.e.,
+    /// the sum of the last arguments of the brave consequences over `p/n`
+    Declared(String),
 }
 // TODO: route as int vec
 impl Eval for Weight {
-    fn eval_weight(&self, navigator: &mut Navigator, facet: &str) -> (usize, Option<usize>) {
+    fn eval_weight(
+        &self,
+        navigator: &mut Navigator,
+        facet: &str,
+    ) -> Result<(usize, Option<usize>)> {
+        navigator.literal(facet)?;
+
         let new_route = navigator.route.peek_step(&facet.to_owned()).0;
         let new_assumptions = navigator
             .parse_input_to_literals(&new_route)
             .collect::<Vec<Literal>>();
 
         match self {
-            Self::Absolute => {
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                let cr_s = navigator.route.iter().cloned().collect::<String>();
-
-                let count = if let Some(c) = cache.as_counts.get(&cr_s) {
-                    *c
-                } else {
-                    let c = navigator.count(&navigator.active_facets.clone());
-
-                    assert!(cache.as_counts.put(cr_s, c).is_none());
-
-                    c
-                };
+            Self::Absolute | Self::Entropy | Self::InformationGain => {
+                let count = navigator.cached_count(&navigator.active_facets.clone())?;
 
-                let weight = count - navigator.count(&new_assumptions);
+                // saturating, as approximate counts need not be monotone
+                let weight = count.saturating_sub(navigator.estimated_count(&new_assumptions)?);
                 let inverse_weight = count - weight; // w_#AS is splitting
 
-                (weight, Some(inverse_weight))
+                Ok((weight, Some(inverse_weight)))
             }
             Self::FacetCounting => {
                 let count = navigator.current_facets.len();
 
-                let facets = navigator.inclusive_facets(&new_assumptions);
+                let facets = navigator.inclusive_facets(&new_assumptions)?;
                 let weight = count - facets.len();
 
-                (weight, None)
+                Ok((weight, None))
             }
             Self::Information => {
-                let facets = navigator.inclusive_facets(&new_assumptions);
+                let facets = navigator.inclusive_facets(&new_assumptions)?;
                 let weight = facets.len();
 
-                (weight, None)
+                Ok((weight, None))
             }
-        }
-    }
-    fn show_weight(&self, navigator: &mut Navigator, facet: &str) {
-        let f = match facet.starts_with('~') {
-            true => &facet[1..],
-            _ => facet,
-        };
+            Self::Declared(name) => {
+                let value = navigator.declared_value(name, &navigator.active_facets.clone())?;
+                let new_value = navigator.declared_value(name, &new_assumptions)?;
 
-        if Atom(f)
-            .parse(&[])
-            .map(|s| navigator.current_facets.0.contains(&s))
-            == Some(false)
-        {
-            println!("\ncurrently fasb only supports ?w for current facets: {:?} is not a current facet.\n", facet);
-            return;
+                // brave consequences only shrink along the route
+                Ok((value.saturating_sub(new_value), None))
+            }
         }
+    }
+    fn weight(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>> {
+        current_facet_or_err(navigator, facet)?;
 
         match self {
-            Self::Absolute => {
-                let (weight, inverse_weight) = self.eval_weight(navigator, facet);
+            Self::Absolute | Self::Entropy | Self::InformationGain => {
+                let (weight, inverse_weight) = self.eval_weight(navigator, facet)?;
+                let inverse_weight = inverse_weight.ok_or(NavigatorError::None)?;
 
                 let inverse_facet = match facet.starts_with('~') {
                     true => facet[1..].to_owned(),
                     _ => format!("~{}", facet),
                 };
 
-                println!(
-                    "{:.2} {}",
-                    weight as f64 / navigator.count(&[]) as f64,
-                    facet
-                );
-                println!(
-                    "{:.2} {}",
-                    inverse_weight.expect("computing absolute inverse weight failed.") as f64
-                        / navigator.count(&[]) as f64,
-                    inverse_facet,
-                );
+                // the answer sets under the route split among the facet and its inverse
+                let count = weight + inverse_weight;
+                let (w0, w1) = match self {
+                    Self::Absolute => {
+                        let initial_count = navigator.cached_count(&[])? as f64;
+                        (
+                            weight as f64 / initial_count,
+                            inverse_weight as f64 / initial_count,
+                        )
+                    }
+                    Self::Entropy => (
+                        entropy_reduction(count, count - weight),
+                        entropy_reduction(count, count - inverse_weight),
+                    ),
+                    _ => {
+                        let gain = information_gain(count, count - weight);
+                        (gain, gain)
+                    }
+                };
+
+                Ok(vec![(facet.to_owned(), w0), (inverse_facet, w1)])
             }
             Self::FacetCounting | Self::Information => {
-                let (weight, _) = self.eval_weight(navigator, facet);
+                let (weight, _) = self.eval_weight(navigator, facet)?;
 
-                println!(
-                    "{:.2} {}",
+                Ok(vec![(
+                    facet.to_owned(),
                     weight as f64 / navigator.current_facets.len() as f64,
-                    facet
-                );
+                )])
+            }
+            Self::Declared(name) => {
+                let (weight, _) = self.eval_weight(navigator, facet)?;
+                let value = navigator.declared_value(name, &navigator.active_facets.clone())?;
+
+                Ok(vec![(
+                    facet.to_owned(),
+                    match value {
+                        0 => 0f64,
+                        _ => weight as f64 / value as f64,
+                    },
+                )])
             }
         }
     }
-    fn show_all_weights(&self, navigator: &mut Navigator) {
-        if navigator.current_facets.0.is_empty() {
-            println!("\nno current facets.\n");
-            return;
-        }
-
-        match self {
-            Self::Absolute => navigator
-                .current_facets
-                .clone()
-                .iter()
-                .for_each(|f| self.show_weight(navigator, &f.repr())),
-            Self::FacetCounting | Self::Information => {
-                navigator.current_facets.clone().iter().for_each(|f| {
-                    self.show_weight(navigator, &f.repr());
-                    self.show_weight(navigator, &f.exclusive_repr());
-                })
+    fn all_weights(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>> {
+        let mut weights = vec![];
+
+        let weight = self.clone();
+        let results = navigator.par_map(
+            evaluated_facets(self, &navigator.current_facets.0),
+            move |navigator, facet| weight.weight(navigator, facet),
+            &ProgressBar::hidden(),
+        )?;
+        for result in results {
+            match interrupt::or_interrupted(result)? {
+                Some(w) => weights.extend(w),
+                _ => break,
             }
         }
+
+        Ok(weights)
     }
-    fn eval_zoom(&self, navigator: &mut Navigator, facet: &str) -> (f32, Option<f32>) {
+    fn eval_zoom(&self, navigator: &mut Navigator, facet: &str) -> Result<(f32, Option<f32>)> {
+        navigator.literal(facet)?;
+
         let new_route = navigator.route.peek_step(&facet.to_owned()).0;
         let new_assumptions = navigator
             .parse_input_to_literals(&new_route)
@@ -219,208 +423,214 @@ impl Eval for Weight {
 
         match self {
             Self::Absolute => {
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                let cr_s = navigator.route.iter().cloned().collect::<String>();
-
-                let count = if let Some(c) = cache.as_counts.get(&cr_s) {
-                    *c
-                } else {
-                    let c = navigator.count(&navigator.active_facets.clone());
-
-                    assert!(cache.as_counts.put(cr_s, c).is_none());
-
-                    c
-                };
-
-                let initial_count = if let Some(c) = cache.as_counts.get(&"".to_owned()) {
-                    *c
-                } else {
-                    let c = navigator.count(&navigator.active_facets.clone());
+                let count = navigator.cached_count(&navigator.active_facets.clone())?;
+                let initial_count = navigator.cached_count(&[])?;
+                let pace = initial_count.saturating_sub(count) as f32 / initial_count as f32;
+                let new_count = navigator.estimated_count(&new_assumptions)?;
 
-                    assert!(cache.as_counts.put("".to_owned(), c).is_none());
-
-                    c
-                };
-                let pace = (initial_count - count) as f32 / initial_count as f32;
-
-                (
-                    (initial_count - navigator.count(&new_assumptions)) as f32
-                        / initial_count as f32
-                        - pace,
+                Ok((
+                    initial_count.saturating_sub(new_count) as f32 / initial_count as f32 - pace,
                     Some(
-                        (initial_count - (count - navigator.count(&new_assumptions))) as f32
+                        initial_count.saturating_sub(count.saturating_sub(new_count)) as f32
                             / initial_count as f32
                             - pace,
                     ),
-                )
+                ))
             }
             Self::FacetCounting => {
                 let initial_count = navigator.initial_facets.len() * 2;
-                let new_count = navigator.inclusive_facets(&new_assumptions).len() * 2;
+                let new_count = navigator.inclusive_facets(&new_assumptions)?.len() * 2;
 
-                (
+                Ok((
                     (initial_count - new_count) as f32 / initial_count as f32 - navigator.pace,
                     None,
-                )
+                ))
             }
             Self::Information => {
                 let initial_count = navigator.initial_facets.len();
-                let new_count = navigator.inclusive_facets(&new_assumptions).len();
+                let new_count = navigator.inclusive_facets(&new_assumptions)?.len();
 
-                (new_count as f32 / initial_count as f32, None)
+                Ok((new_count as f32 / initial_count as f32, None))
             }
-        }
-    }
-    fn show_zoom(&self, navigator: &mut Navigator, facet: &str) {
-        let f = match facet.starts_with('~') {
-            true => &facet[1..],
-            _ => facet,
-        };
+            Self::Declared(name) => {
+                let initial_value = navigator.declared_value(name, &[])?;
+                let value = navigator.declared_value(name, &navigator.active_facets.clone())?;
+                let new_value = navigator.declared_value(name, &new_assumptions)?;
+
+                Ok((
+                    match initial_value {
+                        0 => 0f32,
+                        _ => value.saturating_sub(new_value) as f32 / initial_value as f32,
+                    },
+                    None,
+                ))
+            }
+            Self::InformationGain => {
+                let count = navigator.cached_count(&navigator.active_facets.clone())?;
+                let initial_count = navigator.cached_count(&[])?;
+                let new_count = navigator.estimated_count(&new_assumptions)?;
+
+                // share of the initial entropy expected to be removed, alike for the inverse
+                let zoom = match initial_count > 1 {
+                    true => (information_gain(count, new_count) / entropy(initial_count)) as f32,
+                    _ => 0f32,
+                };
 
-        if Atom(f)
-            .parse(&[])
-            .map(|s| navigator.current_facets.0.contains(&s))
-            == Some(false)
-        {
-            println!("\ncurrently fasb only supports ?z for current facets: {:?} is not a current facet.\n", facet);
-            return;
+                Ok((zoom, Some(zoom)))
+            }
+            Self::Entropy => {
+                let count = navigator.cached_count(&navigator.active_facets.clone())?;
+                let initial_count = navigator.cached_count(&[])?;
+                let pace = entropy_reduction(initial_count, count);
+                let new_count = navigator.estimated_count(&new_assumptions)?;
+
+                Ok((
+                    (entropy_reduction(initial_count, new_count) - pace) as f32,
+                    Some(
+                        (entropy_reduction(initial_count, count.saturating_sub(new_count)) - pace)
+                            as f32,
+                    ),
+                ))
+            }
         }
+    }
+    fn zoom(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>> {
+        current_facet_or_err(navigator, facet)?;
 
         match self {
-            Self::Absolute => {
-                let (z0, z1) = self.eval_zoom(navigator, facet);
+            Self::Absolute | Self::Entropy | Self::InformationGain => {
+                let (z0, z1) = self.eval_zoom(navigator, facet)?;
 
                 let inverse_facet = match facet.starts_with('~') {
                     true => facet[1..].to_owned(),
                     _ => format!("~{}", facet),
                 };
 
-                println!("{} : {:.2}%", facet, z0 * 100f32);
-                println!(
-                    "{} : {:.2}%",
-                    inverse_facet,
-                    z1.expect("unknown error.") * 100f32
-                );
+                Ok(vec![
+                    (facet.to_owned(), z0 as f64),
+                    (inverse_facet, z1.ok_or(NavigatorError::None)? as f64),
+                ])
             }
-            Self::FacetCounting | Self::Information => {
-                let (z, _) = self.eval_zoom(navigator, facet);
+            Self::FacetCounting | Self::Information | Self::Declared(_) => {
+                let (z, _) = self.eval_zoom(navigator, facet)?;
 
-                println!("{} : {:.2}%", facet, z * 100f32);
+                Ok(vec![(facet.to_owned(), z as f64)])
             }
         }
     }
-    fn show_all_zooms(&self, navigator: &mut Navigator) {
-        if navigator.current_facets.0.is_empty() {
-            println!("\nno current facets.\n");
-            return;
+    fn all_zooms(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>> {
+        let mut zooms = vec![];
+
+        let weight = self.clone();
+        let results = navigator.par_map(
+            evaluated_facets(self, &navigator.current_facets.0),
+            move |navigator, facet| weight.zoom(navigator, facet),
+            &ProgressBar::hidden(),
+        )?;
+        for result in results {
+            match interrupt::or_interrupted(result)? {
+                Some(z) => zooms.extend(z),
+                _ => break,
+            }
         }
 
-        match self {
-            Self::Absolute => navigator
-                .current_facets
-                .clone()
-                .iter()
-                .for_each(|f| self.show_zoom(navigator, &f.repr())),
-            _ => navigator.current_facets.clone().iter().for_each(|f| {
-                self.show_zoom(navigator, &f.repr());
-                self.show_zoom(navigator, &f.exclusive_repr());
-            }),
-        }
+        Ok(zooms)
     }
-    fn find_with_zoom_higher_than(&self, navigator: &mut Navigator, bound: f32) -> Option<String> {
+    fn find_with_zoom_higher_than(
+        &self,
+        navigator: &mut Navigator,
+        bound: f32,
+    ) -> Result<Option<String>> {
         match self {
-            Self::Absolute => {
-                let mut data = vec![];
-
-                navigator.current_facets.clone().iter().for_each(|f| {
+            Self::Absolute | Self::Entropy | Self::InformationGain => {
+                for f in navigator.current_facets.clone().iter() {
                     let (fr, fer) = (f.repr(), f.exclusive_repr());
-                    let (z0, z1) = self.eval_zoom(navigator, &fr);
+                    let (z0, z1) = self.eval_zoom(navigator, &fr)?;
 
-                    data.push((fr, z0));
-                    data.push((fer, z1.expect("unknown error.")));
-                });
+                    if z0 >= bound {
+                        return Ok(Some(fr));
+                    }
+                    if z1.ok_or(NavigatorError::None)? >= bound {
+                        return Ok(Some(fer));
+                    }
+                }
 
-                data.iter()
-                    .find(|(_, z)| *z >= bound)
-                    .map(|(f, _)| f)
-                    .cloned()
+                Ok(None)
             }
             _ => {
-                match navigator
-                    .current_facets
-                    .clone()
-                    .iter()
-                    .map(|f| f.repr())
-                    .find(|f| self.eval_zoom(navigator, f).0 >= bound)
-                {
-                    Some(f) => Some(f),
-                    _ => navigator
-                        .current_facets
-                        .clone()
-                        .iter()
-                        .map(|f| format!("~{}", f.repr()))
-                        .find(|f| self.eval_zoom(navigator, f).0 >= bound),
+                let facets = navigator.current_facets.clone();
+                for f in facets.iter().map(|f| f.repr()) {
+                    if self.eval_zoom(navigator, &f)?.0 >= bound {
+                        return Ok(Some(f));
+                    }
                 }
+                for f in facets.iter().map(|f| f.exclusive_repr()) {
+                    if self.eval_zoom(navigator, &f)?.0 >= bound {
+                        return Ok(Some(f));
+                    }
+                }
+
+                Ok(None)
             }
         }
     }
-    fn find_with_zoom_lower_than(&self, navigator: &mut Navigator, bound: f32) -> Option<String> {
+    fn find_with_zoom_lower_than(
+        &self,
+        navigator: &mut Navigator,
+        bound: f32,
+    ) -> Result<Option<String>> {
         match self {
-            Self::Absolute => {
-                let mut data = vec![];
-
-                navigator.current_facets.clone().iter().for_each(|f| {
+            Self::Absolute | Self::Entropy | Self::InformationGain => {
+                for f in navigator.current_facets.clone().iter() {
                     let (fr, fer) = (f.repr(), f.exclusive_repr());
-                    let (z0, z1) = self.eval_zoom(navigator, &fr);
+                    let (z0, z1) = self.eval_zoom(navigator, &fr)?;
 
-                    data.push((fr, z0));
-                    data.push((fer, z1.expect("unknown error.")));
-                });
+                    if z0 <= bound {
+                        return Ok(Some(fr));
+                    }
+                    if z1.ok_or(NavigatorError::None)? <= bound {
+                        return Ok(Some(fer));
+                    }
+                }
 
-                data.iter()
-                    .find(|(_, z)| *z <= bound)
-                    .map(|(f, _)| f)
-                    .cloned()
+                Ok(None)
             }
             _ => {
-                match navigator
-                    .current_facets
-                    .clone()
-                    .iter()
-                    .map(|f| format!("~{}", f.repr()))
-                    .find(|f| self.eval_zoom(navigator, f).0 <= bound)
-                {
-                    Some(f) => Some(f),
-                    _ => navigator
-                        .current_facets
-                        .clone()
-                        .iter()
-                        .map(|f| f.repr())
-                        .find(|f| self.eval_zoom(navigator, f).0 <= bound),
+                let facets = navigator.current_facets.clone();
+                for f in facets.iter().map(|f| f.exclusive_repr()) {
+                    if self.eval_zoom(navigator, &f)?.0 <= bound {
+                        return Ok(Some(f));
+                    }
                 }
+                for f in facets.iter().map(|f| f.repr()) {
+                    if self.eval_zoom(navigator, &f)?.0 <= bound {
+                        return Ok(Some(f));
+                    }
+                }
+
+                Ok(None)
             }
         }
     }
 }
 
 pub trait GoalOrientedNavigation: Send + Sync {
-    fn eval_w(&self, navigator: &mut Navigator, facet: &str) -> (usize, Option<usize>);
-    fn show_w(&self, navigator: &mut Navigator, facet: &str);
-    fn show_a_w(&self, navigator: &mut Navigator);
-    fn eval_z(&self, navigator: &mut Navigator, facet: &str) -> (f32, Option<f32>);
-    fn show_z(&self, navigator: &mut Navigator, facet: &str);
-    fn show_a_z(&self, navigator: &mut Navigator);
-    fn find_with_zh(&self, navigator: &mut Navigator, bound: f32) -> Option<String>;
-    fn find_with_zl(&self, navigator: &mut Navigator, bound: f32) -> Option<String>;
-    fn filter(&self, navigator: &mut Navigator, current_facets: &[Symbol]) -> Vec<String>;
+    fn eval_w(&self, navigator: &mut Navigator, facet: &str) -> Result<(usize, Option<usize>)>;
+    fn w(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>>;
+    fn a_w(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>>;
+    fn eval_z(&self, navigator: &mut Navigator, facet: &str) -> Result<(f32, Option<f32>)>;
+    fn z(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>>;
+    fn a_z(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>>;
+    fn find_with_zh(&self, navigator: &mut Navigator, bound: f32) -> Result<Option<String>>;
+    fn find_with_zl(&self, navigator: &mut Navigator, bound: f32) -> Result<Option<String>>;
+    fn filter(&self, navigator: &mut Navigator, current_facets: &[Symbol]) -> Result<Vec<String>>;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Mode {
     GoalOriented(Weight),
     StrictlyGoalOriented(Weight),
     Explore(Weight),
-    Io(u8),
 }
 impl std::fmt::Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -444,125 +654,137 @@ impl std::fmt::Display for Mode {
             Self::Explore(Weight::Absolute) => write!(f, "absolute explore mode"),
             Self::Explore(Weight::FacetCounting) => write!(f, "facet-counting explore mode"),
             Self::Explore(Weight::Information) => write!(f, "U explore mode"),
-            Self::Io(_) => panic!(),
+            Self::GoalOriented(Weight::Entropy) => write!(f, "entropy goal-oriented mode"),
+            Self::StrictlyGoalOriented(Weight::Entropy) => {
+                write!(f, "entropy strictly-goal-oriented mode")
+            }
+            Self::Explore(Weight::Entropy) => write!(f, "entropy explore mode"),
+            Self::GoalOriented(Weight::InformationGain) => {
+                write!(f, "information-gain goal-oriented mode")
+            }
+            Self::StrictlyGoalOriented(Weight::InformationGain) => {
+                write!(f, "information-gain strictly-goal-oriented mode")
+            }
+            Self::Explore(Weight::InformationGain) => write!(f, "information-gain explore mode"),
+            Self::GoalOriented(Weight::Declared(name)) => write!(f, "{} goal-oriented mode", name),
+            Self::StrictlyGoalOriented(Weight::Declared(name)) => {
+                write!(f, "{} strictly-goal-oriented mode", name)
+            }
+            Self::Explore(Weight::Declared(name)) => write!(f, "{} explore mode", name),
         }
     }
 }
 impl GoalOrientedNavigation for Mode {
-    fn eval_w(&self, navigator: &mut Navigator, facet: &str) -> (usize, Option<usize>) {
+    fn eval_w(&self, navigator: &mut Navigator, facet: &str) -> Result<(usize, Option<usize>)> {
         match self {
             Self::GoalOriented(t) => eval_weight(t, navigator, facet),
             Self::StrictlyGoalOriented(t) => eval_weight(t, navigator, facet),
             Self::Explore(t) => eval_weight(t, navigator, facet),
-            Self::Io(_) => panic!(),
         }
     }
-    fn show_w(&self, navigator: &mut Navigator, facet: &str) {
+    fn w(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>> {
         match self {
-            Self::GoalOriented(t) => show_weight(t, navigator, facet),
-            Self::StrictlyGoalOriented(t) => show_weight(t, navigator, facet),
-            Self::Explore(t) => show_weight(t, navigator, facet),
-            Self::Io(_) => panic!(),
+            Self::GoalOriented(t) => t.weight(navigator, facet),
+            Self::StrictlyGoalOriented(t) => t.weight(navigator, facet),
+            Self::Explore(t) => t.weight(navigator, facet),
         }
     }
-    fn show_a_w(&self, navigator: &mut Navigator) {
+    fn a_w(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>> {
         match self {
-            Self::GoalOriented(t) => show_all_weights(t, navigator),
-            Self::StrictlyGoalOriented(t) => show_all_weights(t, navigator),
-            Self::Explore(t) => show_all_weights(t, navigator),
-            Self::Io(_) => panic!(),
+            Self::GoalOriented(t) => t.all_weights(navigator),
+            Self::StrictlyGoalOriented(t) => t.all_weights(navigator),
+            Self::Explore(t) => t.all_weights(navigator),
         }
     }
-    fn eval_z(&self, navigator: &mut Navigator, facet: &str) -> (f32, Option<f32>) {
+    fn eval_z(&self, navigator: &mut Navigator, facet: &str) -> Result<(f32, Option<f32>)> {
         match self {
             Self::GoalOriented(t) => eval_zoom(t, navigator, facet),
             Self::StrictlyGoalOriented(t) => eval_zoom(t, navigator, facet),
             Self::Explore(t) => eval_zoom(t, navigator, facet),
-            Self::Io(_) => panic!(),
         }
     }
-    fn show_z(&self, navigator: &mut Navigator, facet: &str) {
+    fn z(&self, navigator: &mut Navigator, facet: &str) -> Result<Vec<(String, f64)>> {
         match self {
-            Self::GoalOriented(t) => show_zoom(t, navigator, facet),
-            Self::StrictlyGoalOriented(t) => show_zoom(t, navigator, facet),
-            Self::Explore(t) => show_zoom(t, navigator, facet),
-            Self::Io(_) => panic!(),
+            Self::GoalOriented(t) => t.zoom(navigator, facet),
+            Self::StrictlyGoalOriented(t) => t.zoom(navigator, facet),
+            Self::Explore(t) => t.zoom(navigator, facet),
         }
     }
-    fn show_a_z(&self, navigator: &mut Navigator) {
+    fn a_z(&self, navigator: &mut Navigator) -> Result<Vec<(String, f64)>> {
         match self {
-            Self::GoalOriented(t) => show_all_zooms(t, navigator),
-            Self::StrictlyGoalOriented(t) => show_all_zooms(t, navigator),
-            Self::Explore(t) => show_all_zooms(t, navigator),
-            Self::Io(_) => panic!(),
+            Self::GoalOriented(t) => t.all_zooms(navigator),
+            Self::StrictlyGoalOriented(t) => t.all_zooms(navigator),
+            Self::Explore(t) => t.all_zooms(navigator),
         }
     }
-    fn find_with_zh(&self, navigator: &mut Navigator, bound: f32) -> Option<String> {
+    fn find_with_zh(&self, navigator: &mut Navigator, bound: f32) -> Result<Option<String>> {
         match self {
             Self::GoalOriented(t) => find_facet_with_zoom_higher_than(t, navigator, bound),
             Self::StrictlyGoalOriented(t) => find_facet_with_zoom_higher_than(t, navigator, bound),
             Self::Explore(t) => find_facet_with_zoom_higher_than(t, navigator, bound),
-            Self::Io(_) => panic!(),
         }
     }
-    fn find_with_zl(&self, navigator: &mut Navigator, bound: f32) -> Option<String> {
+    fn find_with_zl(&self, navigator: &mut Navigator, bound: f32) -> Result<Option<String>> {
         match self {
             Self::GoalOriented(t) => find_facet_with_zoom_lower_than(t, navigator, bound),
             Self::StrictlyGoalOriented(t) => find_facet_with_zoom_lower_than(t, navigator, bound),
             Self::Explore(t) => find_facet_with_zoom_lower_than(t, navigator, bound),
-            Self::Io(_) => panic!(),
         }
     }
-    fn filter(&self, navigator: &mut Navigator, current_facets: &[Symbol]) -> Vec<String> {
-        let mut cache = CACHE.lock().expect("cache lock is poisoned.");
+    fn filter(&self, navigator: &mut Navigator, current_facets: &[Symbol]) -> Result<Vec<String>> {
+        // the mode distinguishes weights sharing a filter, e.g., facet-counting and U
+        let key = Key::new(self.to_string(), &navigator.active_facets);
 
         match self {
             // TODO:
             Self::StrictlyGoalOriented(Weight::FacetCounting)
             | Self::StrictlyGoalOriented(Weight::Information) => {
-                let cr_s = navigator.route.iter().cloned().collect::<String>();
                 let count = current_facets.len();
 
-                if let Some(v) = cache.max_fc_facets.get(&cr_s) {
-                    println!("navigation mode : {}", self);
-                    println!("filtered        : {:?}/{:?}", v.len(), count * 2);
-                    println!("elapsed         : cached result\n");
-
-                    v.to_vec()
+                if let Some(v) = navigator.cache.filtered.get(&key).cloned() {
+                    Ok(v)
                 } else {
                     let mut data = vec![];
 
                     let pbs = ProgressStyle::default_bar()
                         .template("solving [{elapsed_precise}] {bar:30} {msg}")
                         .progress_chars("##-");
-                    let pb = ProgressBar::new(count as u64);
+                    let pb = progress_bar(count as u64);
                     pb.set_style(pbs);
                     pb.enable_steady_tick(10);
 
-                    current_facets
-                        .iter()
-                        .progress_with(pb.clone())
-                        .for_each(|f| {
-                            let repr = f.repr();
-                            let neg_repr = format!("~{}", repr);
-
-                            let r0 = navigator.route.peek_step(repr.clone()).0;
-                            let a0 = navigator
-                                .parse_input_to_literals(&r0)
-                                .collect::<Vec<Literal>>();
-                            let w0 = count - navigator.inclusive_facets(&a0).len();
+                    let reprs = current_facets.iter().map(|f| f.repr()).collect::<Vec<_>>();
+                    let results = navigator.par_map(
+                        reprs.clone(),
+                        move |navigator, repr| {
+                            let w0 = count - navigator.facets_under_step(repr)?.len();
+                            let w1 =
+                                count - navigator.facets_under_step(&format!("~{}", repr))?.len();
+                            Ok((w0, w1))
+                        },
+                        &pb,
+                    )?;
+                    pb.finish_using_style();
 
-                            let r1 = navigator.route.peek_step(neg_repr.clone()).0;
-                            let a1 = navigator
-                                .parse_input_to_literals(&r1)
-                                .collect::<Vec<Literal>>();
-                            let w1 = count - navigator.inclusive_facets(&a1).len();
+                    for (repr, result) in reprs.into_iter().zip(results) {
+                        let (w0, w1) = match interrupt::or_interrupted(result)? {
+                            Some(ws) => ws,
+                            _ => break,
+                        };
 
-                            data.push((repr, w0));
-                            data.push((neg_repr, w1));
-                        });
+                        let neg_repr = format!("~{}", repr);
+                        data.push((repr, w0));
+                        data.push((neg_repr, w1));
+                    }
 
-                    let max = data.iter().map(|(_, w)| w).max().expect("unknown error.");
+                    if data.is_empty() && interrupt::interrupted() {
+                        return Err(NavigatorError::Interrupted);
+                    }
+                    let max = data
+                        .iter()
+                        .map(|(_, w)| w)
+                        .max()
+                        .ok_or(NavigatorError::None)?;
 
                     let fs = data
                         .iter()
@@ -571,59 +793,61 @@ impl GoalOrientedNavigation for Mode {
                         .map(|(f_s, _)| f_s)
                         .collect::<Vec<String>>();
 
-                    assert!(cache.max_fc_facets.put(cr_s, fs.clone()).is_none());
-
-                    println!("navigation mode : {}", self);
-                    println!("filtered        : {:?}/{:?}", fs.len(), count * 2);
-                    println!("elapsed         : {:?}\n", pb.elapsed());
+                    // partial results are not cached
+                    if !interrupt::interrupted() {
+                        navigator.cache.filtered.put(key, fs.clone());
+                    }
 
-                    fs
+                    Ok(fs)
                 }
             }
             Self::Explore(Weight::FacetCounting) | Self::Explore(Weight::Information) => {
-                let cr_s = navigator.route.clone().iter().cloned().collect::<String>();
                 let count = current_facets.len();
 
-                if let Some(v) = cache.min_fc_facets.get(&cr_s) {
-                    println!("navigation mode : {}", self);
-                    println!("filtered        : {:?}/{:?}", v.len(), count * 2);
-                    println!("elapsed         : cached result\n");
-
-                    v.to_vec()
+                if let Some(v) = navigator.cache.filtered.get(&key).cloned() {
+                    Ok(v)
                 } else {
                     let mut data = vec![];
 
                     let pbs = ProgressStyle::default_bar()
                         .template("solving [{elapsed_precise}] {bar:30} {msg}")
                         .progress_chars("##-");
-                    let pb = ProgressBar::new(count as u64);
+                    let pb = progress_bar(count as u64);
                     pb.set_style(pbs);
                     pb.enable_steady_tick(10);
 
-                    current_facets
-                        .iter()
-                        .progress_with(pb.clone())
-                        .for_each(|f| {
-                            let repr = f.repr();
-                            let neg_repr = format!("~{}", repr);
-
-                            let r0 = navigator.route.peek_step(repr.clone()).0;
-                            let a0 = navigator
-                                .parse_input_to_literals(&r0)
-                                .collect::<Vec<Literal>>();
-                            let w0 = count - navigator.inclusive_facets(&a0).len();
+                    let reprs = current_facets.iter().map(|f| f.repr()).collect::<Vec<_>>();
+                    let results = navigator.par_map(
+                        reprs.clone(),
+                        move |navigator, repr| {
+                            let w0 = count - navigator.facets_under_step(repr)?.len();
+                            let w1 =
+                                count - navigator.facets_under_step(&format!("~{}", repr))?.len();
+                            Ok((w0, w1))
+                        },
+                        &pb,
+                    )?;
+                    pb.finish_using_style();
 
-                            let r1 = navigator.route.peek_step(neg_repr.clone()).0;
-                            let a1 = navigator
-                                .parse_input_to_literals(&r1)
-                                .collect::<Vec<Literal>>();
-                            let w1 = count - navigator.inclusive_facets(&a1).len();
+                    for (repr, result) in reprs.into_iter().zip(results) {
+                        let (w0, w1) = match interrupt::or_interrupted(result)? {
+                            Some(ws) => ws,
+                            _ => break,
+                        };
 
-                            data.push((repr, w0));
-                            data.push((neg_repr, w1));
-                        });
+                        let neg_repr = format!("~{}", repr);
+                        data.push((repr, w0));
+                        data.push((neg_repr, w1));
+                    }
 
-                    let min = data.iter().map(|(_, w)| w).min().expect("unknown error.");
+                    if data.is_empty() && interrupt::interrupted() {
+                        return Err(NavigatorError::Interrupted);
+                    }
+                    let min = data
+                        .iter()
+                        .map(|(_, w)| w)
+                        .min()
+                        .ok_or(NavigatorError::None)?;
 
                     let fs = data
                         .iter()
@@ -632,53 +856,54 @@ impl GoalOrientedNavigation for Mode {
                         .map(|(f_s, _)| f_s)
                         .collect::<Vec<String>>();
 
-                    assert!(cache.min_fc_facets.put(cr_s, fs.clone()).is_none());
-
-                    println!("navigation mode : {}", self);
-                    println!("filtered        : {:?}/{:?}", fs.len(), count * 2);
-                    println!("elapsed         : {:?}\n", pb.elapsed());
+                    // partial results are not cached
+                    if !interrupt::interrupted() {
+                        navigator.cache.filtered.put(key, fs.clone());
+                    }
 
-                    fs
+                    Ok(fs)
                 }
             }
             Self::StrictlyGoalOriented(Weight::Absolute) => {
-                let cr_s = navigator.route.iter().cloned().collect::<String>();
-
-                if let Some(v) = cache.max_as_facets.get(&cr_s) {
-                    println!("navigation mode : {}", self);
-                    println!(
-                        "filtered        : {:?}/{:?}",
-                        v.len(),
-                        current_facets.len() * 2
-                    );
-                    println!("elapsed         : cached result\n");
-
-                    v.to_vec()
+                if let Some(v) = navigator.cache.filtered.get(&key).cloned() {
+                    Ok(v)
                 } else {
-                    drop(cache);
-
                     let mut data = vec![];
 
                     let pbs = ProgressStyle::default_bar()
                         .template("solving [{elapsed_precise}] {bar:30} {msg}")
                         .progress_chars("##-");
-                    let pb = ProgressBar::new((current_facets.len() / 2) as u64);
+                    let pb = progress_bar((current_facets.len() / 2) as u64);
                     pb.set_style(pbs);
                     pb.enable_steady_tick(10);
 
-                    current_facets.iter().for_each(|f| {
-                        let repr = f.repr();
-                        let neg_repr = format!("~{}", repr);
+                    let reprs = current_facets.iter().map(|f| f.repr()).collect::<Vec<_>>();
+                    let results = navigator.par_map(
+                        reprs.clone(),
+                        |navigator, repr| eval_weight(&Weight::Absolute, navigator, repr),
+                        &pb,
+                    )?;
+                    pb.finish_using_style();
 
-                        let (w0, w1) = eval_weight(&Weight::Absolute, navigator, &repr);
-                        pb.inc(1);
+                    for (repr, result) in reprs.into_iter().zip(results) {
+                        let (w0, w1) = match interrupt::or_interrupted(result)? {
+                            Some(ws) => ws,
+                            _ => break,
+                        };
 
+                        let neg_repr = format!("~{}", repr);
                         data.push((repr, w0));
-                        data.push((neg_repr, w1.expect("unknown error.")));
-                    });
-                    pb.finish_using_style();
+                        data.push((neg_repr, w1.ok_or(NavigatorError::None)?));
+                    }
 
-                    let max = data.iter().map(|(_, w)| w).max().expect("unknown error.");
+                    if data.is_empty() && interrupt::interrupted() {
+                        return Err(NavigatorError::Interrupted);
+                    }
+                    let max = data
+                        .iter()
+                        .map(|(_, w)| w)
+                        .max()
+                        .ok_or(NavigatorError::None)?;
 
                     let fs = data
                         .iter()
@@ -687,58 +912,54 @@ impl GoalOrientedNavigation for Mode {
                         .map(|(f_s, _)| f_s)
                         .collect::<Vec<String>>();
 
-                    let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                    assert!(cache.max_as_facets.put(cr_s, fs.clone()).is_none());
-
-                    println!("navigation mode : {}", self);
-                    println!(
-                        "filtered        : {:?}/{:?}",
-                        fs.len(),
-                        current_facets.len() * 2
-                    );
-                    println!("elapsed         : {:?}\n", pb.elapsed());
+                    // partial results are not cached
+                    if !interrupt::interrupted() {
+                        navigator.cache.filtered.put(key, fs.clone());
+                    }
 
-                    fs
+                    Ok(fs)
                 }
             }
             Self::Explore(Weight::Absolute) => {
-                let cr_s = navigator.route.iter().cloned().collect::<String>();
-
-                if let Some(v) = cache.min_as_facets.get(&cr_s) {
-                    println!("navigation mode : {}", self);
-                    println!(
-                        "filtered        : {:?}/{:?}",
-                        v.len(),
-                        current_facets.len() * 2
-                    );
-                    println!("elapsed         : cached result\n");
-
-                    v.to_vec()
+                if let Some(v) = navigator.cache.filtered.get(&key).cloned() {
+                    Ok(v)
                 } else {
-                    drop(cache);
-
                     let mut data = vec![];
 
                     let pbs = ProgressStyle::default_bar()
                         .template("solving [{elapsed_precise}] {bar:30} {msg}")
                         .progress_chars("##-");
-                    let pb = ProgressBar::new((current_facets.len() / 2) as u64);
+                    let pb = progress_bar((current_facets.len() / 2) as u64);
                     pb.set_style(pbs);
                     pb.enable_steady_tick(10);
 
-                    current_facets.iter().for_each(|f| {
-                        let repr = f.repr();
-                        let neg_repr = format!("~{}", repr);
+                    let reprs = current_facets.iter().map(|f| f.repr()).collect::<Vec<_>>();
+                    let results = navigator.par_map(
+                        reprs.clone(),
+                        |navigator, repr| eval_weight(&Weight::Absolute, navigator, repr),
+                        &pb,
+                    )?;
+                    pb.finish_using_style();
 
-                        let (w0, w1) = eval_weight(&Weight::Absolute, navigator, &repr);
-                        pb.inc(1);
+                    for (repr, result) in reprs.into_iter().zip(results) {
+                        let (w0, w1) = match interrupt::or_interrupted(result)? {
+                            Some(ws) => ws,
+                            _ => break,
+                        };
 
+                        let neg_repr = format!("~{}", repr);
                         data.push((repr, w0));
-                        data.push((neg_repr, w1.expect("unknown error.")));
-                    });
-                    pb.finish_using_style();
+                        data.push((neg_repr, w1.ok_or(NavigatorError::None)?));
+                    }
 
-                    let min = data.iter().map(|(_, w)| w).min().expect("unknown error.");
+                    if data.is_empty() && interrupt::interrupted() {
+                        return Err(NavigatorError::Interrupted);
+                    }
+                    let min = data
+                        .iter()
+                        .map(|(_, w)| w)
+                        .min()
+                        .ok_or(NavigatorError::None)?;
 
                     let fs = data
                         .iter()
@@ -747,37 +968,83 @@ impl GoalOrientedNavigation for Mode {
                         .map(|(f_s, _)| f_s)
                         .collect::<Vec<String>>();
 
-                    let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                    assert!(cache.min_as_facets.put(cr_s, fs.clone()).is_none());
-
-                    println!("navigation mode : {}", self);
-                    println!(
-                        "filtered        : {:?}/{:?}",
-                        fs.len(),
-                        current_facets.len() * 2
-                    );
-                    println!("elapsed         : {:?}\n", pb.elapsed());
+                    // partial results are not cached
+                    if !interrupt::interrupted() {
+                        navigator.cache.filtered.put(key, fs.clone());
+                    }
 
-                    fs
+                    Ok(fs)
                 }
             }
-            Self::GoalOriented(_) => {
-                println!("\nnavigation mode : {}", self);
-                println!(
-                    "filtered        : {:?}/{:?}",
-                    current_facets.len() * 2,
-                    current_facets.len() * 2
-                );
-                println!("elapsed         : cached result\n");
+            Self::StrictlyGoalOriented(
+                t @ (Weight::Entropy | Weight::InformationGain | Weight::Declared(_)),
+            ) => filter_by_weight(t, navigator, key, current_facets, true),
+            Self::Explore(
+                t @ (Weight::Entropy | Weight::InformationGain | Weight::Declared(_)),
+            ) => filter_by_weight(t, navigator, key, current_facets, false),
+            Self::GoalOriented(_) => Ok(vec![]),
+        }
+    }
+}
 
-                // NOTE: avoid .map
-                println!("{}", navigator.current_facets);
+/// Facets among `current_facets` and their inverses of maximal (or minimal) normalized `weight`.
+fn filter_by_weight(
+    weight: &Weight,
+    navigator: &mut Navigator,
+    key: Key,
+    current_facets: &[Symbol],
+    maximal: bool,
+) -> Result<Vec<String>> {
+    if let Some(v) = navigator.cache.filtered.get(&key).cloned() {
+        return Ok(v);
+    }
 
-                vec![]
-            }
-            Self::Io(_) => panic!(),
+    let mut data = vec![];
+
+    let pbs = ProgressStyle::default_bar()
+        .template("solving [{elapsed_precise}] {bar:30} {msg}")
+        .progress_chars("##-");
+    let reprs = evaluated_facets(weight, current_facets);
+    let pb = progress_bar(reprs.len() as u64);
+    pb.set_style(pbs);
+    pb.enable_steady_tick(10);
+
+    let w = weight.clone();
+    let results =
+        navigator.par_map(reprs, move |navigator, repr| w.weight(navigator, repr), &pb)?;
+    pb.finish_using_style();
+
+    for result in results {
+        match interrupt::or_interrupted(result)? {
+            Some(ws) => data.extend(ws),
+            _ => break,
         }
     }
+
+    if data.is_empty() && interrupt::interrupted() {
+        return Err(NavigatorError::Interrupted);
+    }
+    let ws = data.iter().map(|(_, w)| *w);
+    let bound = match maximal {
+        true => ws.fold(f64::NEG_INFINITY, f64::max),
+        _ => ws.fold(f64::INFINITY, f64::min),
+    };
+    if !bound.is_finite() {
+        return Err(NavigatorError::None);
+    }
+
+    let fs = data
+        .into_iter()
+        .filter(|(_, w)| *w == bound)
+        .map(|(f_s, _)| f_s)
+        .collect::<Vec<String>>();
+
+    // partial results are not cached
+    if !interrupt::interrupted() {
+        navigator.cache.filtered.put(key, fs.clone());
+    }
+
+    Ok(fs)
 }
 
 #[derive(Debug)]
@@ -794,43 +1061,496 @@ impl From<EnumMode> for &str {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Navigator {
-    pub(crate) logic_program: String,
-    pub(crate) control: Arc<Control>,
-    pub(crate) literals: Literals,
-    pub(crate) n: usize,
-    pub current_facets: Facets,
-    pub(crate) initial_facets: Facets,
-    pub(crate) active_facets: Vec<Literal>,
-    pub(crate) route: Route,
-    pace: f32,
+/// Atoms navigation is restricted to; answer sets are counted modulo the projection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Projection {
+    /// atoms selected by `#show`
+    Show,
+    /// atoms of the given predicate signatures, e.g., `p/2`
+    Signatures(Vec<(String, usize)>),
+}
+impl Projection {
+    fn clingo_args(&self) -> Vec<String> {
+        match self {
+            Self::Show => vec!["--project=show".to_owned()],
+            Self::Signatures(_) => vec!["--project=project".to_owned()],
+        }
+    }
+    fn directives(&self) -> String {
+        match self {
+            Self::Show => String::new(),
+            Self::Signatures(signatures) => signatures
+                .iter()
+                .map(|(name, arity)| format!("#project {}/{}.", name, arity))
+                .join("\n"),
+        }
+    }
+    /// Whether `symbol` is projected; shown atoms pass by default, since consequences are
+    /// computed over shown atoms.
+    fn covers(&self, symbol: &Symbol) -> bool {
+        match self {
+            Self::Show => true,
+            Self::Signatures(signatures) => match (symbol.name(), symbol.arguments()) {
+                (Ok(name), Ok(arguments)) => signatures
+                    .iter()
+                    .any(|(n, arity)| n == name && *arity == arguments.len()),
+                _ => false,
+            },
+        }
+    }
+}
+impl std::str::FromStr for Projection {
+    type Err = NavigatorError;
+
+    /// Parses `show` or a comma separated list of signatures, e.g., `p/2,q/1`.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "" | "show" => Ok(Self::Show),
+            _ => s
+                .split(',')
+                .map(signature)
+                .collect::<Result<Vec<_>>>()
+                .map(Self::Signatures),
+        }
+    }
 }
-impl Navigator {
-    pub fn new(source: impl Into<String>, n: usize) -> Result<Self> {
-        let mut ctl = Control::new(vec!["0".to_owned()])?;
 
-        let pbs = ProgressStyle::default_bar()
-            .template("startup [{elapsed_precise}] {msg}")
-            .progress_chars("#-");
-        let pb = ProgressBar::new(30);
-        pb.set_style(pbs);
-        pb.enable_steady_tick(10);
+/// Parses a predicate signature, e.g., `p/2`.
+fn signature(s: &str) -> Result<(String, usize)> {
+    s.rsplit_once('/')
+        .and_then(|(name, arity)| {
+            arity
+                .parse::<usize>()
+                .ok()
+                .filter(|_| !name.is_empty())
+                .map(|arity| (name.to_owned(), arity))
+        })
+        .ok_or_else(|| NavigatorError::Parse(s.to_owned()))
+}
 
-        let logic_program = source.into();
-        pb.set_message("| process: 1/6 adding program");
-        ctl.add("base", &[], &logic_program)?;
-        pb.set_message("| process: 2/6 grounding");
-        ctl.ground(&[Part::new("base", &[])?])?;
-        pb.inc(1);
+/// Answer sets navigation is restricted to in presence of optimization statements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Optimization {
+    /// optimal answer sets only
+    Optimal,
+    /// answer sets whose cost vector does not exceed the bound
+    Bounded(Vec<i64>),
+}
+impl std::str::FromStr for Optimization {
+    type Err = NavigatorError;
+
+    /// Parses `optimal` or a comma separated cost bound, e.g., `3,2`.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "optimal" => Ok(Self::Optimal),
+            _ => s
+                .split(',')
+                .map(|c| {
+                    c.parse::<i64>()
+                        .map_err(|_| NavigatorError::Parse(c.to_owned()))
+                })
+                .collect::<Result<Vec<_>>>()
+                .map(Self::Bounded),
+        }
+    }
+}
 
-        let n_cpus = num_cpus::get().to_string();
-        ctl.configuration_mut() // activates parallel competition based search
-            .map(|c| {
-                c.root()
-                    .and_then(|rk| c.map_at(rk, "solve.parallel_mode"))
-                    .and_then(|sk| c.value_set(sk, &n_cpus))
-            })??;
+/// How answer sets are counted under [`Weight::Absolute`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Counting {
+    /// enumerates every answer set
+    Exact,
+    /// hashing-based approximation by random parity constraints; the estimate is within a
+    /// factor of `1 + tolerance` of the exact count with probability at least `confidence`
+    Approximate { tolerance: f64, confidence: f64 },
+}
+impl std::str::FromStr for Counting {
+    type Err = NavigatorError;
+
+    /// Parses `exact`, `approx` or `approx,<tolerance>,<confidence>`, e.g., `approx,0.8,0.9`.
+    fn from_str(s: &str) -> Result<Self> {
+        let parse = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| NavigatorError::Parse(v.to_owned()))
+        };
+
+        match s.split(',').collect::<Vec<_>>().as_slice() {
+            ["exact"] => Ok(Self::Exact),
+            ["approx"] => Ok(Self::Approximate {
+                tolerance: 0.8,
+                confidence: 0.8,
+            }),
+            ["approx", tolerance, confidence] => {
+                let (tolerance, confidence) = (parse(*tolerance)?, parse(*confidence)?);
+                match tolerance > 0f64 && confidence > 0f64 && confidence < 1f64 {
+                    true => Ok(Self::Approximate {
+                        tolerance,
+                        confidence,
+                    }),
+                    _ => Err(NavigatorError::InvalidInput(
+                        "tolerance must be positive and confidence within (0,1).".to_owned(),
+                    )),
+                }
+            }
+            _ => Err(NavigatorError::Parse(s.to_owned())),
+        }
+    }
+}
+
+/// Number of answer sets; the exact number lies within `lower..=upper` with probability at
+/// least `confidence`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Estimate {
+    pub count: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64,
+}
+impl Estimate {
+    fn exact(count: usize) -> Self {
+        let count = count as f64;
+
+        Self {
+            count,
+            lower: count,
+            upper: count,
+            confidence: 1f64,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.is_exact() {
+            true => write!(f, "{}", self.count),
+            _ => write!(
+                f,
+                "~{:.0} [{:.0}, {:.0}] with {:.0}% confidence",
+                self.count,
+                self.lower,
+                self.upper,
+                self.confidence * 100f64
+            ),
+        }
+    }
+}
+
+/// Frequencies of atoms, see [`Navigator::atom_entropy`] and [`Navigator::abbundance`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Frequencies {
+    pub entropy: f64,
+    pub perplexity: f64,
+    pub frequencies: BTreeMap<String, usize>,
+    pub n: usize,
+}
+impl Frequencies {
+    fn new(freq_table: HM<Symbol, usize>, n: usize) -> Self {
+        let entropy = -freq_table
+            .values()
+            .map(|f| (*f as f64 / n as f64))
+            .map(|p| p * p.log2())
+            .sum::<f64>();
+
+        Self {
+            entropy,
+            perplexity: 2f64.powf(entropy),
+            frequencies: freq_table
+                .iter()
+                .map(|(atom, freq)| (atom.repr(), *freq))
+                .collect(),
+            n,
+        }
+    }
+}
+
+/// Program a navigator was started from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Origin {
+    Program(String),
+    /// files loaded by clingo; `-` denotes the program read from stdin
+    Files {
+        sources: Vec<String>,
+        stdin: Option<String>,
+    },
+}
+impl Origin {
+    fn text(&self) -> Result<String> {
+        match self {
+            Self::Program(program) => Ok(program.clone()),
+            Self::Files { sources, stdin } => {
+                let mut text = String::new();
+                for source in sources {
+                    match source.as_str() {
+                        "-" => text.push_str(stdin.as_deref().unwrap_or_default()),
+                        path => text.push_str(&read_to_string(path)?),
+                    }
+                    text.push('\n');
+                }
+
+                Ok(text)
+            }
+        }
+    }
+
+    fn add(&self, ctl: &mut Control) -> Result<()> {
+        match self {
+            Self::Program(program) => ctl.add("base", &[], program)?,
+            Self::Files { sources, stdin } => {
+                for source in sources {
+                    match source.as_str() {
+                        "-" => ctl.add("base", &[], stdin.as_deref().unwrap_or_default())?,
+                        path => ctl.load(path)?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Texts of the program along with the directories their `#include` directives are resolved
+    /// in; unreadable files are given as empty.
+    fn texts(&self) -> Vec<(PathBuf, String)> {
+        match self {
+            Self::Program(program) => vec![(PathBuf::new(), program.clone())],
+            Self::Files { sources, stdin } => sources
+                .iter()
+                .map(|source| match source.as_str() {
+                    "-" => (PathBuf::new(), stdin.clone().unwrap_or_default()),
+                    path => (
+                        directory(Path::new(path)),
+                        read_to_string(path).unwrap_or_default(),
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn directory(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Files named by the `#include "file".` directives of `text`, relative to `dir`. Includes of
+/// clingo's library, i.e., `#include <lib>.`, are not resolved.
+fn includes(text: &str, dir: &Path) -> Vec<PathBuf> {
+    text.lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .filter_map(|line| {
+            let (_, rest) = line.split_once("#include")?;
+            let file = rest.trim_start().strip_prefix('"')?.split('"').next()?;
+            let path = dir.join(file);
+            Some(path.canonicalize().unwrap_or(path))
+        })
+        .collect()
+}
+
+/// Files included by `texts`, given along with their directories as by [`Origin::texts`], and,
+/// transitively, by the included files, along with their contents.
+fn included_files(mut texts: Vec<(PathBuf, String)>) -> Vec<(PathBuf, String)> {
+    let mut included: Vec<(PathBuf, String)> = vec![];
+    while let Some((dir, text)) = texts.pop() {
+        for path in includes(&text, &dir) {
+            if included.iter().any(|(p, _)| *p == path) {
+                continue;
+            }
+            let text = read_to_string(&path).unwrap_or_default();
+            texts.push((directory(&path), text.clone()));
+            included.push((path, text));
+        }
+    }
+
+    included
+}
+
+/// Whether `text` has `#minimize` or `#maximize` statements or weak constraints.
+fn has_optimization_statements(text: &str) -> bool {
+    text.lines()
+        .map(|line| line.split('%').next().unwrap_or_default())
+        .any(|line| {
+            line.contains(":~")
+                || ["#minimize", "#minimise", "#maximize", "#maximise"]
+                    .iter()
+                    .any(|directive| line.contains(directive))
+        })
+}
+
+/// Change of the ground program after startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Step {
+    /// program added by [`Navigator::extend`]
+    Extend(String),
+    /// program part grounded by [`Navigator::ground`]
+    Ground(String),
+}
+
+/// Everything needed to ground a replica of a navigator, possibly in another thread or session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Recipe {
+    origin: Origin,
+    arguments: Vec<String>,
+    projection: Option<Projection>,
+    steps: Vec<Step>,
+    optimum: Option<Vec<i64>>,
+    counting: Counting,
+}
+impl Recipe {
+    /// Grounds a single-threaded replica with empty route.
+    pub(crate) fn build(self) -> Result<Navigator> {
+        self.startup(0, true)
+    }
+
+    /// Grounds a navigator with empty route, see [`Navigator::startup`].
+    pub(crate) fn startup(self, n: usize, replica: bool) -> Result<Navigator> {
+        let mut navigator =
+            Navigator::startup(self.origin, self.arguments, self.projection, n, replica)?;
+        for step in &self.steps {
+            navigator.apply(step)?;
+        }
+        if let Some(bound) = self.optimum {
+            navigator.set_optimum(bound)?;
+        }
+        navigator.counting = self.counting;
+        if !self.steps.is_empty() || navigator.optimum.is_some() {
+            navigator.refresh(&Mode::GoalOriented(Weight::FacetCounting))?;
+        }
+
+        Ok(navigator)
+    }
+
+    /// Refers to program files by absolute paths, such that the recipe does not depend on the
+    /// working directory.
+    pub(crate) fn with_absolute_paths(mut self) -> Result<Self> {
+        if let Origin::Files { sources, .. } = &mut self.origin {
+            for source in sources.iter_mut().filter(|s| s.as_str() != "-") {
+                *source = std::fs::canonicalize(source.as_str())?
+                    .to_string_lossy()
+                    .into_owned();
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// Route of a navigator, taken over by the replicas of its worker pool.
+#[derive(Debug, Clone)]
+pub(crate) struct State {
+    route: Route,
+    current_facets: Facets,
+    pace: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Navigator {
+    pub(crate) logic_program: String,
+    pub(crate) arguments: Vec<String>,
+    pub(crate) control: Arc<Control>,
+    pub(crate) literals: Literals,
+    pub(crate) n: usize,
+    pub current_facets: Facets,
+    pub(crate) initial_facets: Facets,
+    pub(crate) active_facets: Vec<Literal>,
+    pub(crate) route: Route,
+    pub(crate) projection: Option<Projection>,
+    pub(crate) optimum: Option<Vec<i64>>,
+    pub(crate) counting: Counting,
+    pub(crate) cache: Cache,
+    /// guards of the parity constraints grounded so far, by hash function and level
+    parity_guards: Vec<Vec<Literal>>,
+    pace: f32,
+    step: usize,
+    origin: Origin,
+    /// changes of the program since startup, replayed by replicas
+    steps: Vec<Step>,
+    /// solver threads of the control
+    threads: usize,
+    workers: usize,
+    /// replicas evaluating facets concurrently, started on demand for more than one worker
+    pool: Option<Arc<Pool>>,
+    /// signatures of the weights declared in the program, see [`Navigator::declared_weights`]
+    declared_weights: Vec<(String, usize)>,
+}
+impl Navigator {
+    pub fn new(source: impl Into<String>, n: usize) -> Result<Self> {
+        Self::startup(Origin::Program(source.into()), vec![], None, n, false)
+    }
+
+    /// Reads the program from `sources` (`-` denotes stdin) and passes `arguments` to clingo.
+    ///
+    /// Files are loaded by clingo itself, so that `#include` directives are resolved relative to
+    /// the file they occur in. With a `projection`, facets, route literals and counts are
+    /// restricted to the projected atoms.
+    pub fn load(
+        sources: &[String],
+        arguments: Vec<String>,
+        projection: Option<Projection>,
+        n: usize,
+    ) -> Result<Self> {
+        let mut from_stdin = None;
+        if sources.iter().any(|source| source == "-") {
+            let mut program = String::new();
+            stdin().read_to_string(&mut program)?;
+            from_stdin = Some(program);
+        }
+        let origin = Origin::Files {
+            sources: sources.to_vec(),
+            stdin: from_stdin,
+        };
+
+        Self::startup(origin, arguments, projection, n, false)
+    }
+
+    /// With `replica`, solving is single-threaded and no progress is shown.
+    fn startup(
+        origin: Origin,
+        arguments: Vec<String>,
+        projection: Option<Projection>,
+        n: usize,
+        replica: bool,
+    ) -> Result<Self> {
+        let logic_program = origin.text()?;
+        let threads = match replica {
+            true => 1,
+            _ => requested_threads(&arguments).unwrap_or_else(num_cpus::get),
+        };
+        let mut ctl = Control::new(
+            std::iter::once("0".to_owned())
+                .chain(arguments.clone())
+                .chain(projection.iter().flat_map(|p| p.clingo_args()))
+                .collect::<Vec<String>>(),
+        )?;
+
+        let pbs = ProgressStyle::default_bar()
+            .template("startup [{elapsed_precise}] {msg}")
+            .progress_chars("#-");
+        let pb = match replica {
+            true => ProgressBar::hidden(),
+            _ => progress_bar(30),
+        };
+        pb.set_style(pbs);
+        pb.enable_steady_tick(10);
+
+        pb.set_message("| process: 1/6 adding program");
+        origin.add(&mut ctl)?;
+        if let Some(p) = &projection {
+            ctl.add("base", &[], &p.directives())?;
+        }
+        pb.set_message("| process: 2/6 grounding");
+        ctl.ground(&[Part::new("base", &[])?])?;
+        pb.inc(1);
+
+        // competing threads may report the same model twice, whereas splitting the search space
+        // among threads keeps enumeration, counting and consequences duplicate-free
+        let parallel_mode = format!("{},split", threads);
+        ctl.configuration_mut().map(|c| {
+            c.root()
+                .and_then(|rk| c.map_at(rk, "solve.parallel_mode"))
+                .and_then(|sk| c.value_set(sk, &parallel_mode))
+        })??;
         let mut literals: Literals = HashMap::new();
 
         pb.set_message("| process: 3/6 collecting literals");
@@ -845,6 +1565,7 @@ impl Navigator {
         solve_handle.close()?;
         pb.inc(3);
 
+        let mut cache = Cache::default();
         let initial_facets = match sat {
             true => {
                 pb.set_message("| process: 5/6 computing brave consequences");
@@ -853,11 +1574,15 @@ impl Navigator {
                         .and_then(|rk| c.map_at(rk, "solve.enum_mode"))
                         .and_then(|sk| c.value_set(sk, "brave"))
                 })??;
-                let bc = ctl
+                let mut bc = ctl
                     .all_models()?
                     .last()
                     .map(|model| model.symbols)
                     .ok_or(NavigatorError::None)?;
+                if let Some(p) = &projection {
+                    bc.retain(|s| p.covers(s));
+                    project(&mut literals, &bc);
+                }
                 pb.inc(4);
 
                 pb.set_message("| process: 6/6 computing cautious consequences");
@@ -878,13 +1603,11 @@ impl Navigator {
                     _ => Facets(bc.difference(&cc)),
                 };
 
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                cache.inclusive_facets.put(vec![], fs.clone());
-                drop(cache);
+                cache.inclusive_facets.put(Key::new("", &[]), fs.clone());
 
                 fs
             }
-            _ => panic!("program is unsatisfiable."),
+            _ => return Err(NavigatorError::Unsatisfiable),
         };
         ctl.configuration_mut().map(|c| {
             c.root()
@@ -894,8 +1617,9 @@ impl Navigator {
 
         let control = Arc::new(ctl);
 
-        Ok(Self {
+        let mut navigator = Self {
             logic_program,
+            arguments,
             control,
             literals,
             n,
@@ -903,66 +1627,354 @@ impl Navigator {
             initial_facets,
             active_facets: vec![],
             route: Route(vec![]),
+            projection,
+            optimum: None,
+            counting: Counting::Exact,
+            cache,
+            parity_guards: vec![],
             pace: 0f32,
-        })
+            step: 0,
+            origin,
+            steps: vec![],
+            threads,
+            workers: 1,
+            pool: None,
+            declared_weights: vec![],
+        };
+        navigator.declared_weights = navigator.parse_declared_weights()?;
+
+        Ok(navigator)
+    }
+
+    /// Identifies the program, including the files it includes, together with the options that
+    /// determine query results.
+    pub fn fingerprint(&self) -> u64 {
+        let options = format!("{:?}{:?}{:?}", self.projection, self.optimum, self.counting);
+        let included = self
+            .included_files()
+            .into_iter()
+            .flat_map(|(path, text)| [path.display().to_string(), text])
+            .collect::<Vec<_>>();
+        cache::fingerprint(
+            std::iter::once(self.logic_program.as_str())
+                .chain(included.iter().map(|s| s.as_str()))
+                .chain(self.arguments.iter().map(|s| s.as_str()))
+                .chain(std::iter::once(options.as_str())),
+        )
+    }
+
+    /// Files included by the program, i.e., by `#include "file".` directives of the loaded
+    /// program, of programs added since and of the included files, along with their contents.
+    fn included_files(&self) -> Vec<(PathBuf, String)> {
+        let mut texts = self.origin.texts();
+        texts.extend(self.steps.iter().filter_map(|step| match step {
+            Step::Extend(program) => Some((PathBuf::new(), program.clone())),
+            _ => None,
+        }));
+
+        included_files(texts)
+    }
+
+    /// Restarts on `program` in place of the program as loaded, e.g., after editing it; programs
+    /// added and parts grounded since startup are applied again.
+    ///
+    /// The route is replayed on the new program; returns the facets dropped from it, see
+    /// [`Navigator::extend`].
+    pub fn reload(&mut self, program: &str, mode: &Mode) -> Result<Vec<String>> {
+        let recipe = Recipe {
+            origin: Origin::Program(program.to_owned()),
+            ..self.recipe()
+        };
+        let mut navigator = recipe.startup(self.n, false)?;
+        navigator.set_cache_capacity(self.cache_capacity());
+        navigator.workers = self.workers;
+        navigator.route = self.route.clone();
+
+        let dropped = navigator.refresh(mode)?;
+        *self = navigator;
+
+        Ok(dropped)
+    }
+
+    /// Independent navigator on a newly grounded copy of the program, on the same route.
+    pub fn fork(&self, mode: &Mode) -> Result<Navigator> {
+        let mut navigator = self.recipe().startup(self.n, false)?;
+        navigator.set_cache_capacity(self.cache_capacity());
+        navigator.workers = self.workers;
+        navigator.set_route(self.route.clone(), mode)?;
+
+        Ok(navigator)
+    }
+
+    /// Program as loaded, i.e., without the programs added since startup.
+    pub fn loaded_program(&self) -> Result<String> {
+        self.origin.text()
+    }
+
+    /// Adds `program` as a new program part and grounds it on the existing control (multi-shot).
+    ///
+    /// Returns the facets dropped from the route since they became invalid or unsatisfiable.
+    pub fn extend(&mut self, program: &str, mode: &Mode) -> Result<Vec<String>> {
+        self.apply(&Step::Extend(program.to_owned()))?;
+
+        self.refresh(mode)
+    }
+
+    /// Grounds program part `part`, e.g., `step(3)`, declared via `#program` in the loaded program;
+    /// parts are grounded at most once.
+    ///
+    /// Returns the facets dropped from the route, see [`Navigator::extend`].
+    pub fn ground(&mut self, part: &str, mode: &Mode) -> Result<Vec<String>> {
+        self.apply(&Step::Ground(part.to_owned()))?;
+
+        self.refresh(mode)
+    }
+
+    /// Adds or grounds a program part without recollecting literals and facets.
+    fn apply(&mut self, step: &Step) -> Result<()> {
+        match step {
+            Step::Extend(program) => {
+                let name = format!("fasb_step_{}", self.step);
+
+                let ctl = control_mut(&mut self.control)?;
+                ctl.add(&name, &[], program)?;
+                ctl.ground(&[Part::new(&name, &[])?])?;
+
+                self.step += 1;
+                self.logic_program
+                    .push_str(&format!("\n#program {}.\n{}\n", name, program));
+            }
+            Step::Ground(part) => {
+                let symbol = Atom(part)
+                    .parse(&[])
+                    .ok_or_else(|| NavigatorError::Parse(part.to_owned()))?;
+                let arguments = symbol.arguments()?;
+
+                // grounding a part again would redefine its atoms
+                let grounded = (symbol.name()? == "base" && arguments.is_empty())
+                    || self.steps.iter().any(|step| {
+                        matches!(step, Step::Ground(p) if Atom(p).parse(&[]) == Some(symbol))
+                    });
+                if grounded {
+                    return Err(NavigatorError::InvalidInput(format!(
+                        "program part {:?} is already grounded.",
+                        part
+                    )));
+                }
+
+                let ctl = control_mut(&mut self.control)?;
+                ctl.ground(&[Part::new(symbol.name()?, &arguments)?])?;
+
+                self.logic_program
+                    .push_str(&format!("\n% grounded {}\n", part));
+            }
+        }
+        self.steps.push(step.clone());
+
+        Ok(())
+    }
+
+    /// Restricts navigation to optimal or cost-bounded answer sets.
+    ///
+    /// The optimum is computed once over the whole program, so that activating facets never
+    /// admits answer sets that are only optimal under the route. Returns the facets dropped from
+    /// the route, see [`Navigator::extend`].
+    pub fn optimize(&mut self, optimization: Optimization, mode: &Mode) -> Result<Vec<String>> {
+        let bound = match optimization {
+            Optimization::Optimal => self.find_optimum()?,
+            Optimization::Bounded(bound) => bound,
+        };
+        if bound.is_empty() {
+            return Err(NavigatorError::Unsupported(
+                "program has no optimization statements.".to_owned(),
+            ));
+        }
+
+        self.set_optimum(bound)?;
+
+        self.refresh(mode)
+    }
+
+    /// Restricts solving to answer sets of cost at most `bound`.
+    fn set_optimum(&mut self, bound: Vec<i64>) -> Result<()> {
+        let opt_mode = format!("enum,{}", bound.iter().join(","));
+        control_mut(&mut self.control)?
+            .configuration_mut()
+            .map(|c| {
+                c.root()
+                    .and_then(|rk| c.map_at(rk, "solve.opt_mode"))
+                    .and_then(|sk| c.value_set(sk, &opt_mode))
+            })??;
+        self.optimum = Some(bound);
+
+        Ok(())
+    }
+
+    /// Cost of an optimal answer set; empty if the program has no optimization statements.
+    fn find_optimum(&mut self) -> Result<Vec<i64>> {
+        if !self.has_optimization_statements() {
+            return Ok(vec![]);
+        }
+
+        let ctl = control_mut(&mut self.control)?;
+
+        let opt_mode = ctl.configuration_mut().map(|c| {
+            let key = c.root().and_then(|rk| c.map_at(rk, "solve.opt_mode"))?;
+            let opt_mode = c.value_get(key)?;
+            c.value_set(key, "opt")?;
+            Ok::<_, ClingoError>(opt_mode)
+        })??;
+
+        // models are reported with decreasing cost, the last one is optimal
+        let mut optimum = vec![];
+        let solved = for_each_model(ctl, &[], |model| {
+            optimum = model.cost()?;
+            Ok(true)
+        });
+
+        // keeps the bound of set_optimum, if any
+        ctl.configuration_mut().map(|c| {
+            c.root()
+                .and_then(|rk| c.map_at(rk, "solve.opt_mode"))
+                .and_then(|sk| c.value_set(sk, &opt_mode))
+        })??;
+        solved?;
+
+        Ok(optimum)
+    }
+
+    /// Whether the program, including programs added since startup and included files, has
+    /// `#minimize` or `#maximize` statements or weak constraints.
+    fn has_optimization_statements(&self) -> bool {
+        has_optimization_statements(&self.logic_program)
+            || self
+                .included_files()
+                .iter()
+                .any(|(_, text)| has_optimization_statements(text))
+    }
+
+    /// Recollects literals and initial facets after the ground program changed and replays the
+    /// current route facet by facet, dropping facets that are unknown or make the route replayed
+    /// so far unsatisfiable.
+    fn refresh(&mut self, mode: &Mode) -> Result<Vec<String>> {
+        // added programs may declare weights as well
+        self.declared_weights = self.parse_declared_weights()?;
+
+        let ctl = control_mut(&mut self.control)?;
+
+        let mut literals: Literals = HashMap::new();
+        for atom in ctl.symbolic_atoms()?.iter()? {
+            let symbol = atom.symbol()?;
+            if symbol.name()? != PARITY_GUARD {
+                literals.insert(symbol, atom.literal()?);
+            }
+        }
+        if self.projection.is_some() {
+            project(&mut literals, &self.consequences(EnumMode::Brave, &[])?);
+        }
+        self.literals = literals;
+
+        // every cached entry and replica refers to the previous ground program
+        self.cache.clear();
+        self.pool = None;
+
+        self.initial_facets = self.inclusive_facets(&[])?;
+        // parity constraints range over the previous initial facets
+        self.parity_guards.clear();
+
+        let (mut route, mut active_facets, mut dropped) = (Route(vec![]), vec![], vec![]);
+        for facet in self.route.clone().iter() {
+            match self.literal(facet) {
+                Ok(literal) => {
+                    active_facets.push(literal);
+                    match self.satisfiable(&active_facets)? {
+                        true => route.activate(facet),
+                        _ => {
+                            active_facets.pop();
+                            dropped.push(facet.clone());
+                        }
+                    }
+                }
+                _ => dropped.push(facet.clone()),
+            }
+        }
+
+        self.route = route;
+        self.active_facets = active_facets;
+        self.update(mode)?;
+
+        Ok(dropped)
     }
 
     #[cfg(not(tarpaulin_include))]
-    pub(crate) fn assume(&mut self, assumptions: &[Literal]) {
-        Arc::get_mut(&mut self.control)
-            .expect("control error.")
+    pub(crate) fn assume(&mut self, assumptions: &[Literal]) -> Result<()> {
+        control_mut(&mut self.control)?
             .backend()
             .and_then(|mut b| b.assume(assumptions))
-            .expect("backend assumption failed.")
+            .map_err(NavigatorError::from)
     }
 
     #[cfg(not(tarpaulin_include))]
-    fn reset_enum_mode(&mut self) {
-        Arc::get_mut(&mut self.control)
-            .expect("control error")
+    fn reset_enum_mode(&mut self) -> Result<()> {
+        control_mut(&mut self.control)?
             .configuration_mut()
             .map(|c| {
                 c.root()
                     .and_then(|rk| c.map_at(rk, "solve.enum_mode"))
                     .and_then(|sk| c.value_set(sk, "auto"))
-            })
-            .expect("resetting solve.enum-mode failed.")
-            .expect("resetting solve.enum-mode failed.");
+            })??;
+
+        Ok(())
+    }
+
+    pub fn satisfiable(&mut self, assumptions: &[Literal]) -> Result<bool> {
+        let ctl = control_mut(&mut self.control)?;
+
+        let mut sat = false;
+        for_each_model(ctl, assumptions, |_| {
+            sat = true;
+            Ok(false)
+        })?;
+
+        Ok(sat)
     }
 
-    pub fn satisfiable(&mut self, assumptions: &[Literal]) -> bool {
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+    /// Unsat core of `assumptions` as found by the solver, i.e., an unsatisfiable, though not
+    /// necessarily minimal, subset of them; `None` if `assumptions` are satisfiable.
+    pub(crate) fn core(&mut self, assumptions: &[Literal]) -> Result<Option<Vec<Literal>>> {
+        let ctl = control_mut(&mut self.control)?;
+        let mut handle = ctl.solve(SolveMode::ASYNC | SolveMode::YIELD, assumptions)?;
+
+        let mut solve = || {
+            while !handle.wait(POLL) {
+                if interrupt::interrupted() {
+                    handle.cancel()?;
+                    return Err(NavigatorError::Interrupted);
+                }
+            }
 
-        let mut solve_handle = ctl
-            .solve(SolveMode::YIELD, assumptions)
-            .expect("getting solve handle failed.");
-        let sat = solve_handle
-            .get()
-            .map(|sr| sr == SolveResult::SATISFIABLE)
-            .expect("getting solve result failed.");
+            match handle.get()?.contains(SolveResult::UNSATISFIABLE) {
+                true => Ok(Some(handle.core()?)),
+                _ => Ok(None),
+            }
+        };
+        let core = solve();
 
-        solve_handle.close().expect("closing solve handle failed.");
+        handle.close()?;
 
-        sat
+        core
     }
 
-    pub fn find_one(&mut self, assumptions: &[Literal]) -> Option<Vec<Symbol>> {
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+    pub fn find_one(&mut self, assumptions: &[Literal]) -> Result<Option<Vec<Symbol>>> {
+        let ctl = control_mut(&mut self.control)?;
 
-        let mut solve_handle = ctl
-            .solve(SolveMode::YIELD, assumptions)
-            .expect("getting solve handle failed.");
+        let mut ret = None;
+        for_each_model(ctl, assumptions, |model| {
+            ret = Some(model.symbols(ShowType::SHOWN)?);
+            Ok(false)
+        })?;
 
-        let ret = match solve_handle.model() {
-            Ok(Some(model)) => Some(
-                model
-                    .symbols(ShowType::SHOWN)
-                    .expect("getting Symbols failed."),
-            ),
-            _ => None,
-        };
-        solve_handle.close().expect("closing solve handle failed.");
-        ret
+        Ok(ret)
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -970,29 +1982,45 @@ impl Navigator {
         &mut self,
         enum_mode: EnumMode,
         assumptions: &[Literal],
-    ) -> Option<Vec<Symbol>> {
-        if !self.satisfiable(assumptions) {
-            return Some(vec![]);
+    ) -> Result<Vec<Symbol>> {
+        let symbols = self.shown_consequences(enum_mode, assumptions)?;
+
+        Ok(match &self.projection {
+            Some(p) => symbols.into_iter().filter(|s| p.covers(s)).collect(),
+            _ => symbols,
+        })
+    }
+
+    /// Like [`Navigator::consequences`], but over all shown atoms regardless of the projection.
+    fn shown_consequences(
+        &mut self,
+        enum_mode: EnumMode,
+        assumptions: &[Literal],
+    ) -> Result<Vec<Symbol>> {
+        if !self.satisfiable(assumptions)? {
+            return Ok(vec![]);
         }
 
-        self.assume(assumptions);
+        self.assume(assumptions)?;
 
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+        let ctl = control_mut(&mut self.control)?;
 
-        ctl.configuration_mut()
-            .map(|c| {
-                c.root()
-                    .and_then(|rk| c.map_at(rk, "solve.enum_mode"))
-                    .map(|sk| c.value_set(sk, enum_mode.into()))
-                    .ok()
-            })
-            .ok()?;
+        ctl.configuration_mut().map(|c| {
+            c.root()
+                .and_then(|rk| c.map_at(rk, "solve.enum_mode"))
+                .and_then(|sk| c.value_set(sk, enum_mode.into()))
+        })??;
 
-        let consequences = ctl.all_models().ok()?.last().map(|model| model.symbols);
+        let mut last = None;
+        let solved = for_each_model(ctl, &[], |model| {
+            last = Some(model.symbols(ShowType::SHOWN)?);
+            Ok(true)
+        });
 
-        self.reset_enum_mode();
+        self.reset_enum_mode()?;
+        solved?;
 
-        consequences
+        last.ok_or_else(|| NavigatorError::Solver("no consequences computed.".to_owned()))
     }
 
     pub(crate) fn literal(&self, str: impl AsRef<str> + Debug) -> Result<Literal> {
@@ -1001,103 +2029,509 @@ impl Navigator {
 
         match negative_prefixes.iter().any(|p| s.starts_with(*p)) {
             true => match Atom(&s[1..]).parse(negative_prefixes) {
-                Some(s) => self.literals.get(&s).map(|l| l.negate()).ok_or_else(|| {
-                    NavigatorError::InvalidInput(format!("unknown literal: {:?}", str))
-                }),
-                _ => {
-                    println!("\n[INFO] cannot parse input");
-                    Err(NavigatorError::InvalidInput(
-                        "parsing literal failed.".to_owned(),
-                    ))
-                }
+                Some(sym) => self
+                    .literals
+                    .get(&sym)
+                    .map(|l| l.negate())
+                    .ok_or_else(|| NavigatorError::UnknownAtom(s.to_owned())),
+                _ => Err(NavigatorError::Parse(s.to_owned())),
             },
             _ => match Atom(s).parse(negative_prefixes) {
-                Some(s) => self.literals.get(&s).cloned().ok_or_else(|| {
-                    NavigatorError::InvalidInput(format!("unknown literal: {:?}", str))
-                }),
-                _ => {
-                    println!("\n[INFO] cannot parse input");
-                    Err(NavigatorError::InvalidInput(
-                        "parsing literal failed.".to_owned(),
-                    ))
-                }
+                Some(sym) => self
+                    .literals
+                    .get(&sym)
+                    .cloned()
+                    .ok_or_else(|| NavigatorError::UnknownAtom(s.to_owned())),
+                _ => Err(NavigatorError::Parse(s.to_owned())),
             },
         }
     }
 
-    pub fn inclusive_facets(&mut self, assumptions: &[Literal]) -> Facets {
-        let bc = self
-            .consequences(EnumMode::Brave, assumptions)
-            .expect("BC computation failed.");
-        let cc = self
-            .consequences(EnumMode::Cautious, assumptions)
-            .expect("CC computation failed.");
+    pub fn inclusive_facets(&mut self, assumptions: &[Literal]) -> Result<Facets> {
+        let bc = self.consequences(EnumMode::Brave, assumptions)?;
+        let cc = self.consequences(EnumMode::Cautious, assumptions)?;
 
-        match cc.is_empty() {
+        Ok(match cc.is_empty() {
             true => Facets(bc),
             _ => Facets(bc.difference(&cc)),
+        })
+    }
+
+    pub(crate) fn count(&mut self, assumptions: &[Literal]) -> Result<usize> {
+        self.assume(assumptions)?;
+
+        let ctl = control_mut(&mut self.control)?;
+
+        let mut count = 0;
+        for_each_model(ctl, &[], |_| {
+            count += 1;
+            Ok(true)
+        })?;
+
+        Ok(count)
+    }
+
+    /// Number of answer sets under `assumptions`, counting stops at `bound`.
+    fn count_up_to(&mut self, assumptions: &[Literal], bound: usize) -> Result<usize> {
+        self.assume(assumptions)?;
+
+        let ctl = control_mut(&mut self.control)?;
+
+        let mut count = 0;
+        for_each_model(ctl, &[], |_| {
+            count += 1;
+            Ok(count < bound)
+        })?;
+
+        Ok(count)
+    }
+
+    /// Number of answer sets under `assumptions` w.r.t. the counting mode.
+    pub fn estimate(&mut self, assumptions: &[Literal]) -> Result<Estimate> {
+        match self.counting {
+            Counting::Exact => Ok(Estimate::exact(self.count(assumptions)?)),
+            Counting::Approximate {
+                tolerance,
+                confidence,
+            } => self.approximate_count(assumptions, tolerance, confidence),
         }
     }
 
-    fn count(&mut self, assumptions: &[Literal]) -> usize {
-        self.assume(assumptions);
+    /// Rounded [`Navigator::estimate`].
+    pub(crate) fn estimated_count(&mut self, assumptions: &[Literal]) -> Result<usize> {
+        Ok(self.estimate(assumptions)?.count.round() as usize)
+    }
+
+    /// Switches the counting mode; cached counts are dropped.
+    pub fn set_counting(&mut self, counting: Counting, mode: &Mode) -> Result<()> {
+        self.counting = counting;
+        self.cache.clear();
+        self.pool = None;
+
+        self.update(mode)
+    }
+
+    /// ApproxMC: answer sets are split into cells by random parity constraints over the initial
+    /// facets, until a cell is small enough to be counted exactly. The median of the scaled cell
+    /// sizes over independent hash functions is the estimate.
+    fn approximate_count(
+        &mut self,
+        assumptions: &[Literal],
+        tolerance: f64,
+        confidence: f64,
+    ) -> Result<Estimate> {
+        let threshold = (1f64
+            + 9.84 * (1f64 + tolerance / (1f64 + tolerance)) * (1f64 + 1f64 / tolerance).powi(2))
+        .ceil() as usize;
+
+        let count = self.count_up_to(assumptions, threshold)?;
+        if count < threshold {
+            return Ok(Estimate::exact(count));
+        }
+
+        let iterations = (17f64 * (3f64 / (1f64 - confidence)).log2()).ceil() as usize;
+        let mut estimates = vec![];
+        for hash in 0..iterations {
+            let mut cell = assumptions.to_vec();
+            for level in 0..self.initial_facets.len() {
+                cell.push(self.parity_guard(hash, level)?);
+
+                let count = self.count_up_to(&cell, threshold)?;
+                if count < threshold {
+                    estimates.push(count as f64 * 2f64.powi(level as i32 + 1));
+                    break;
+                }
+            }
+        }
+        estimates.sort_by(|a, b| a.total_cmp(b));
+
+        let count = *estimates
+            .get(estimates.len() / 2)
+            .ok_or_else(|| NavigatorError::Solver("no cell counted.".to_owned()))?;
+
+        Ok(Estimate {
+            count,
+            lower: count / (1f64 + tolerance),
+            upper: count * (1f64 + tolerance),
+            confidence,
+        })
+    }
+
+    /// Literal of the external guarding the `level`-th parity constraint of the `hash`-th hash
+    /// function. Constraints are grounded on first use and stay inactive unless assumed.
+    fn parity_guard(&mut self, hash: usize, level: usize) -> Result<Literal> {
+        if let Some(guard) = self.parity_guards.get(hash).and_then(|g| g.get(level)) {
+            return Ok(*guard);
+        }
+        if self.parity_guards.len() <= hash {
+            self.parity_guards.resize(hash + 1, vec![]);
+        }
 
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+        let mut rng = rand::thread_rng();
+        let atoms = self
+            .initial_facets
+            .iter()
+            .filter(|_| rng.gen_bool(0.5))
+            .map(|s| s.to_string())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let parity = rng.gen_bool(0.5) as usize;
+
+        let id = self.parity_guards.iter().map(|g| g.len()).sum::<usize>();
+        let guard = format!("{}({})", PARITY_GUARD, id);
+        let constraint = match atoms.is_empty() {
+            true => format!(":- {}, {} != 0.", guard, parity),
+            _ => format!(
+                ":- {}, N = #count{{ {} }}, N \\ 2 != {}.",
+                guard,
+                atoms
+                    .iter()
+                    .enumerate()
+                    .map(|(i, atom)| format!("{}: {}", i, atom))
+                    .join("; "),
+                parity
+            ),
+        };
+
+        let name = format!("{}_{}", PARITY_GUARD, id);
+        let ctl = control_mut(&mut self.control)?;
+        ctl.add(&name, &[], &format!("#external {}.\n{}", guard, constraint))?;
+        ctl.ground(&[Part::new(&name, &[])?])?;
+
+        let symbol = Atom(&guard)
+            .parse(&[])
+            .ok_or_else(|| NavigatorError::Parse(guard.clone()))?;
+        let mut literal = None;
+        for atom in ctl.symbolic_atoms()?.iter()? {
+            if atom.symbol()? == symbol {
+                literal = Some(atom.literal()?);
+                break;
+            }
+        }
+        let literal = literal.ok_or_else(|| NavigatorError::UnknownAtom(guard))?;
 
-        let count = ctl
-            .all_models()
-            .map(|models| models.count())
-            .expect("counting solutions failed.");
+        self.parity_guards[hash].push(literal);
 
-        count
+        Ok(literal)
     }
 
-    pub(crate) fn current_route_is_maximal_safe(&mut self) -> bool {
+    pub(crate) fn current_route_is_maximal_safe(&mut self) -> Result<bool> {
         let route = self
             .parse_input_to_literals(&self.route.0)
             .collect::<Vec<Literal>>();
-        match self.satisfiable(&route) {
-            false => false,
+
+        if !self.satisfiable(&route)? {
+            return Ok(false);
+        }
+
+        let facets = self.inclusive_facets(&route)?; // avoid that by using Lemma: does solve handle find second solution?
+        for s in facets.to_strings() {
+            // NOTE: closed world assumption for any, all
+            let assumptions = self
+                .parse_input_to_literals(&self.route.peek_step(&s).0)
+                .collect::<Vec<Literal>>();
+            if self.satisfiable(&assumptions)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Explains why `facet` is no current facet: returns `facet` or its exclusion, whichever the
+    /// current route entails, along with a minimal subset of the route entailing it; `None` if
+    /// the route entails neither.
+    ///
+    /// The route along with the opposite of the entailed literal is unsatisfiable, and the
+    /// solver's unsat core of it is shrunk to a minimal one by dropping one facet after another
+    /// as long as the remaining ones stay unsatisfiable, each time continuing on the new core.
+    pub fn explain(&mut self, facet: &str) -> Result<Option<(String, Route)>> {
+        let atom = facet.strip_prefix('~').unwrap_or(facet);
+        let literal = self.literal(atom)?;
+        let route = self.active_facets.clone();
+
+        let (entailed, opposite, core) =
+            match self.core(&[route.clone(), vec![literal.negate()]].concat())? {
+                Some(core) => (atom.to_owned(), literal.negate(), core),
+                _ => match self.core(&[route, vec![literal]].concat())? {
+                    Some(core) => (format!("~{}", atom), literal, core),
+                    _ => return Ok(None),
+                },
+            };
+
+        let mut core = self
+            .route
+            .0
+            .iter()
+            .cloned()
+            .zip(self.active_facets.iter().copied())
+            .filter(|(_, l)| core.contains(l))
+            .unique_by(|(_, l)| *l)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while i < core.len() {
+            let assumptions = core
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, (_, l))| *l)
+                .chain(std::iter::once(opposite))
+                .collect::<Vec<_>>();
+            match self.core(&assumptions)? {
+                // facets before `i` are needed, so they are part of the smaller core as well
+                Some(smaller) => {
+                    core.remove(i);
+                    core.retain(|(_, l)| smaller.contains(l));
+                }
+                _ => i += 1,
+            }
+        }
+
+        Ok(Some((
+            entailed,
+            Route(core.into_iter().map(|(f, _)| f).collect()),
+        )))
+    }
+
+    /// Memoized inclusive facets under `assumptions`.
+    fn cached_inclusive_facets(&mut self, assumptions: &[Literal]) -> Result<Facets> {
+        let key = Key::new("", assumptions);
+        match self.cache.inclusive_facets.get(&key) {
+            Some(fs) => Ok(fs.clone()),
             _ => {
-                let facets = self.inclusive_facets(&route); // avoid that by using Lemma: does solve handle find second solution?
-                facets.is_empty() // NOTE: closed world assumption for any, all
-                    || facets.to_strings().all(|s| {
-                        !self.satisfiable(
-                            &self
-                                .parse_input_to_literals(&self.route.peek_step(&s).0)
-                                .collect::<Vec<Literal>>(),
-                        )
-                    })
+                let fs = self.inclusive_facets(assumptions)?;
+                self.cache.inclusive_facets.put(key, fs.clone());
+                Ok(fs)
             }
         }
     }
 
+    /// Memoized number of answer sets under `assumptions` w.r.t. the counting mode.
+    pub(crate) fn cached_count(&mut self, assumptions: &[Literal]) -> Result<usize> {
+        let key = Key::new("", assumptions);
+        match self.cache.counts.get(&key) {
+            Some(c) => Ok(*c),
+            _ => {
+                let c = self.estimated_count(assumptions)?;
+                self.cache.counts.put(key, c);
+                Ok(c)
+            }
+        }
+    }
+
+    /// Memoized brave or cautious consequences under `assumptions`.
+    pub(crate) fn cached_consequences(
+        &mut self,
+        enum_mode: EnumMode,
+        assumptions: &[Literal],
+    ) -> Result<Vec<Symbol>> {
+        let key = Key::new(format!("{:?}", enum_mode), assumptions);
+        match self.cache.consequences.get(&key) {
+            Some(cs) => Ok(cs.clone()),
+            _ => {
+                let cs = self.consequences(enum_mode, assumptions)?;
+                self.cache.consequences.put(key, cs.clone());
+                Ok(cs)
+            }
+        }
+    }
+
+    /// Signatures of the weights declared in the program or in the files it includes, each by a
+    /// line `%@fasb weight p/n`.
+    pub fn declared_weights(&self) -> &[(String, usize)] {
+        &self.declared_weights
+    }
+
+    /// Reads the weight declarations, see [`Navigator::declared_weights`], from the program and
+    /// the files it includes.
+    fn parse_declared_weights(&self) -> Result<Vec<(String, usize)>> {
+        let included = self.included_files();
+        self.logic_program
+            .lines()
+            .chain(included.iter().flat_map(|(_, text)| text.lines()))
+            .filter_map(|line| line.trim().strip_prefix("%@fasb"))
+            .filter_map(|annotation| {
+                let mut words = annotation.split_whitespace();
+                match words.next() {
+                    Some("weight") => Some(words.next().unwrap_or_default()),
+                    _ => None,
+                }
+            })
+            .map(|s| match signature(s) {
+                Ok((_, 0)) => Err(NavigatorError::InvalidInput(format!(
+                    "weight {:?} has no argument to take values from.",
+                    s
+                ))),
+                parsed => parsed,
+            })
+            .collect()
+    }
+
+    /// Value of the declared weight `name` (`p` or `p/n`) under `assumptions`, i.e., the sum of
+    /// the last arguments of the brave consequences over its signature.
+    pub(crate) fn declared_value(&mut self, name: &str, assumptions: &[Literal]) -> Result<usize> {
+        let (predicate, arity) = self
+            .declared_weights
+            .iter()
+            .find(|(p, n)| name == p || *name == format!("{}/{}", p, n))
+            .cloned()
+            .ok_or_else(|| {
+                NavigatorError::InvalidInput(format!(
+                    "no weight {:?} declared, use `%@fasb weight {}/n`.",
+                    name, name
+                ))
+            })?;
+
+        // values share the table of counts, distinguished by the signature
+        let key = Key::new(format!("weight {}/{}", predicate, arity), assumptions);
+        if let Some(value) = self.cache.counts.get(&key) {
+            return Ok(*value);
+        }
+
+        let mut value = 0;
+        for symbol in self.shown_consequences(EnumMode::Brave, assumptions)? {
+            let arguments = match (symbol.name(), symbol.arguments()) {
+                (Ok(p), Ok(arguments)) if p == predicate && arguments.len() == arity => arguments,
+                _ => continue,
+            };
+
+            let v = arguments
+                .last()
+                .and_then(|a| a.number().ok())
+                .and_then(|v| usize::try_from(v).ok())
+                .ok_or_else(|| {
+                    NavigatorError::InvalidInput(format!(
+                        "{} takes no non-negative integer value.",
+                        symbol.repr()
+                    ))
+                })?;
+            value += v;
+        }
+        self.cache.counts.put(key, value);
+
+        Ok(value)
+    }
+
+    pub fn cache_capacity(&self) -> CacheCapacity {
+        self.cache.capacity()
+    }
+
+    /// Resizes the cache tables, keeping their most recently used entries.
+    pub fn set_cache_capacity(&mut self, capacity: CacheCapacity) {
+        self.cache.set_capacity(capacity)
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Evaluates weights, zooms, filters and components on `workers` replicas of the program
+    /// concurrently; a single worker evaluates them one after another on this navigator.
+    pub fn set_workers(&mut self, workers: usize) -> Result<()> {
+        if workers == 0 {
+            return Err(NavigatorError::InvalidInput(
+                "number of workers must be positive.".to_owned(),
+            ));
+        }
+        if workers != self.workers {
+            self.workers = workers;
+            self.pool = None;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn recipe(&self) -> Recipe {
+        Recipe {
+            origin: self.origin.clone(),
+            arguments: self.arguments.clone(),
+            projection: self.projection.clone(),
+            steps: self.steps.clone(),
+            optimum: self.optimum.clone(),
+            counting: self.counting.clone(),
+        }
+    }
+
+    fn state(&self) -> State {
+        State {
+            route: self.route.clone(),
+            current_facets: self.current_facets.clone(),
+            pace: self.pace,
+        }
+    }
+
+    /// Takes over the route of the navigator `state` was taken from.
+    pub(crate) fn adopt(&mut self, state: &State) -> Result<()> {
+        self.active_facets = self.literals_of(&state.route.0)?;
+        self.route = state.route.clone();
+        self.current_facets = state.current_facets.clone();
+        self.pace = state.pace;
+
+        Ok(())
+    }
+
+    /// Evaluates `f` on every facet and returns the results in the order of `facets`, as long as
+    /// no evaluation fails; `pb` advances with every result.
+    ///
+    /// With more than one worker, the facets are distributed over the replicas of the worker
+    /// pool, which is started on first use.
+    pub(crate) fn par_map<T, F>(
+        &mut self,
+        facets: Vec<String>,
+        f: F,
+        pb: &ProgressBar,
+    ) -> Result<Vec<Result<T>>>
+    where
+        T: Send + 'static,
+        F: Fn(&mut Navigator, &str) -> Result<T> + Send + Sync + 'static,
+    {
+        if self.workers <= 1 {
+            let mut results = vec![];
+            for facet in facets {
+                let result = f(self, &facet);
+                let failed = result.is_err();
+                results.push(result);
+                pb.inc(1);
+                if failed {
+                    break;
+                }
+            }
+
+            return Ok(results);
+        }
+
+        let pool = match &self.pool {
+            Some(pool) => pool.clone(),
+            _ => {
+                let pool = Arc::new(Pool::new(&self.recipe(), self.workers)?);
+                self.pool = Some(pool.clone());
+                pool
+            }
+        };
+
+        pool.map(self.state(), facets, f, || pb.inc(1))
+    }
+
+    /// Facets under the current route extended by `facet`.
+    pub(crate) fn facets_under_step(&mut self, facet: &str) -> Result<Facets> {
+        let route = self.route.peek_step(facet).0;
+        let assumptions = self
+            .parse_input_to_literals(&route)
+            .collect::<Vec<Literal>>();
+
+        self.inclusive_facets(&assumptions)
+    }
+
     #[cfg(not(tarpaulin_include))]
-    pub(crate) fn update(&mut self, mode: &Mode) {
+    pub(crate) fn update(&mut self, mode: &Mode) -> Result<()> {
+        let assumptions = self.active_facets.clone();
+
         match mode {
             Mode::GoalOriented(Weight::FacetCounting)
             | Mode::StrictlyGoalOriented(Weight::FacetCounting)
             | Mode::Explore(Weight::FacetCounting) => {
                 let initial_count = (self.initial_facets.len() * 2) as f32;
 
-                let assumptions = self.active_facets.clone();
-
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                let lits = assumptions
-                    .iter()
-                    .map(|l| l.get_integer())
-                    .collect::<Vec<i32>>();
-                let new_facets = cache
-                    .inclusive_facets
-                    .get(&lits)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        let fs = self.inclusive_facets(&assumptions);
-                        cache.inclusive_facets.put(lits, fs.clone());
-                        fs
-                    });
-                drop(cache);
-
+                let new_facets = self.cached_inclusive_facets(&assumptions)?;
                 let new_count = (new_facets.len() * 2) as f32;
 
                 self.current_facets = new_facets;
@@ -1108,187 +2542,109 @@ impl Navigator {
             | Mode::Explore(Weight::Information) => {
                 let initial_count = self.initial_facets.len() as f32;
 
-                let assumptions = self.active_facets.clone();
-
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-                let lits = assumptions
-                    .iter()
-                    .map(|l| l.get_integer())
-                    .collect::<Vec<i32>>();
-                let new_facets = cache
-                    .inclusive_facets
-                    .get(&lits)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        let fs = self.inclusive_facets(&assumptions);
-                        cache.inclusive_facets.put(lits, fs.clone());
-                        fs
-                    });
-                drop(cache);
-
+                let new_facets = self.cached_inclusive_facets(&assumptions)?;
                 let new_count = new_facets.len() as f32;
 
                 self.current_facets = new_facets;
                 self.pace = new_count / initial_count;
             }
-            _ => {
-                let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-
-                let initial_count =
-                    cache
-                        .as_counts
-                        .get(&"".to_owned())
-                        .cloned()
-                        .unwrap_or_else(|| {
-                            let c = self.count(&[]);
-                            cache.as_counts.put("".to_owned(), c);
-                            c
-                        }) as f32;
-                let assumptions = self.active_facets.clone();
-                let curr_route_str = self.route.iter().cloned().collect::<String>();
-                let new_count = cache
-                    .as_counts
-                    .get(&curr_route_str)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        let c = self.count(&assumptions);
-                        cache.as_counts.put(curr_route_str, c);
-                        c
-                    }) as f32;
+            // the information gained along the route is the entropy it removed
+            Mode::GoalOriented(Weight::Entropy | Weight::InformationGain)
+            | Mode::StrictlyGoalOriented(Weight::Entropy | Weight::InformationGain)
+            | Mode::Explore(Weight::Entropy | Weight::InformationGain) => {
+                let initial_count = self.cached_count(&[])?;
+                let new_count = self.cached_count(&assumptions)?;
 
-                let lits = assumptions
-                    .iter()
-                    .map(|l| l.get_integer())
-                    .collect::<Vec<i32>>();
-                let new_facets = cache
-                    .inclusive_facets
-                    .get(&lits)
-                    .cloned()
-                    .unwrap_or_else(|| {
-                        let fs = self.inclusive_facets(&assumptions);
-                        cache.inclusive_facets.put(lits, fs.clone());
-                        fs
-                    });
-                drop(cache);
+                let new_facets = self.cached_inclusive_facets(&assumptions)?;
 
                 self.current_facets = new_facets;
-                self.pace = (initial_count - new_count) / initial_count;
+                self.pace = entropy_reduction(initial_count, new_count) as f32;
             }
-        }
-    }
+            Mode::GoalOriented(Weight::Declared(name))
+            | Mode::StrictlyGoalOriented(Weight::Declared(name))
+            | Mode::Explore(Weight::Declared(name)) => {
+                let initial_value = self.declared_value(name, &[])?;
+                let new_value = self.declared_value(name, &assumptions)?;
 
-    #[cfg(not(tarpaulin_include))]
-    pub fn navigate(&mut self) {
-        self.assume(&self.active_facets.clone());
-
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
-
-        let mut iter = ctl
-            .all_models()
-            .expect("solving failed.")
-            .map(|model| model.symbols);
-
-        println!();
-
-        match iter.next() {
-            Some(first_model) => {
-                println!("Answer 1: ");
-                for atom in first_model.clone() {
-                    // quickfix
-                    print!(
-                        "{} ",
-                        atom.to_string()
-                            .expect("Symbol to String conversion failed.")
-                    );
-                }
-                println!();
-
-                for (i, model) in iter.enumerate() {
-                    if model != first_model {
-                        // quickfix
-                        println!("Answer {:?}: ", i + 2);
-                        for atom in model {
-                            print!(
-                                "{} ",
-                                atom.to_string()
-                                    .expect("Symbol to String conversion failed.")
-                            );
-                        }
-                        println!();
-                    }
-                }
-                println!("SATISFIABLE\n");
-            }
-            _ => println!("UNSATISFIABLE\n"),
-        }
-    }
+                let new_facets = self.cached_inclusive_facets(&assumptions)?;
 
-    #[cfg(not(tarpaulin_include))]
-    pub fn navigate_n(&mut self, n: Option<usize>) {
-        match n == Some(0) {
-            true => self.navigate(),
+                self.current_facets = new_facets;
+                self.pace = match initial_value {
+                    0 => 0f32,
+                    _ => initial_value.saturating_sub(new_value) as f32 / initial_value as f32,
+                };
+            }
             _ => {
-                let route = self.active_facets.clone();
-                self.assume(&route);
+                let initial_count = self.cached_count(&[])? as f32;
+                let new_count = self.cached_count(&assumptions)? as f32;
 
-                let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+                let new_facets = self.cached_inclusive_facets(&assumptions)?;
 
-                let mut handle = ctl
-                    .solve(SolveMode::YIELD, &route)
-                    .expect("solving failed.");
+                self.current_facets = new_facets;
+                self.pace = (initial_count - new_count) / initial_count;
+            }
+        }
 
-                println!();
+        Ok(())
+    }
 
-                let mut i = 1;
+    /// Up to `n` answer sets under the current route; `n == 0` enumerates all of them.
+    pub fn answer_sets(&mut self, n: usize) -> Result<Vec<Vec<Symbol>>> {
+        Ok(self
+            .costed_answer_sets(n)?
+            .into_iter()
+            .map(|(answer_set, _)| answer_set)
+            .collect())
+    }
 
-                match handle.get().expect("getting first solve result failed.")
-                    != SolveResult::SATISFIABLE
-                {
-                    true => println!("UNSATISFIABLE\n"),
-                    _ => {
-                        let mut prev = vec![];
-                        while let Some(model) = handle.model().expect("getting model failed.") {
-                            let curr = model
-                                .symbols(ShowType::SHOWN)
-                                .expect("getting Symbols failed.");
-
-                            match !prev.is_empty() && prev == curr.clone() {
-                                true => handle.resume().expect("solve handle failed resuming."),
-                                _ => {
-                                    println!("Answer {:?}: ", i);
-                                    for atom in curr.iter() {
-                                        print!(
-                                            "{} ",
-                                            atom.to_string()
-                                                .expect("Symbol to String conversion failed.")
-                                        );
-                                    }
-                                    println!();
-
-                                    prev = curr.clone();
-
-                                    if i == n.unwrap_or(self.n) as u64 {
-                                        println!("SATISFIABLE\n");
-
-                                        handle.close().expect("closing solve handle failed.");
-
-                                        return;
-                                    }
-
-                                    i += 1;
-
-                                    handle.resume().expect("solve handle failed resuming.");
-                                }
-                            }
-                        }
+    /// Like [`Navigator::answer_sets`], paired with the cost vector of each answer set.
+    pub fn costed_answer_sets(&mut self, n: usize) -> Result<Vec<(Vec<Symbol>, Vec<i64>)>> {
+        let mut answer_sets = vec![];
+        self.for_each_answer_set(n, |answer_set, cost| {
+            answer_sets.push((answer_set.to_vec(), cost.to_vec()))
+        })?;
 
-                        println!("SATISFIABLE\n");
+        Ok(answer_sets)
+    }
 
-                        handle.close().expect("closing solve handle failed.");
-                    }
+    /// Passes each of up to `n` answer sets under the current route, along with its cost vector,
+    /// to `on_answer_set` as soon as it is found; `n == 0` enumerates all of them.
+    ///
+    /// Returns the number of answer sets passed; on interrupt, the answer sets passed so far are
+    /// kept.
+    pub fn for_each_answer_set(
+        &mut self,
+        n: usize,
+        mut on_answer_set: impl FnMut(&[Symbol], &[i64]),
+    ) -> Result<usize> {
+        let route = self.active_facets.clone();
+        self.assume(&route)?;
+
+        let parallel = self.threads > 1;
+        let ctl = control_mut(&mut self.control)?;
+
+        let mut count = 0;
+        let mut seen = std::collections::HashSet::new();
+        let solved = for_each_model(ctl, &route, |model| {
+            let curr = model.symbols(ShowType::SHOWN)?;
+
+            // threads may report models differing in hidden atoms only, which are shown once
+            if parallel {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                curr.hash(&mut hasher);
+                if !seen.insert(hasher.finish()) {
+                    return Ok(true);
                 }
             }
-        }
+            on_answer_set(&curr, &model.cost()?);
+            count += 1;
+
+            Ok(count != n)
+        });
+        // keep the answer sets found before an interrupt
+        interrupt::or_interrupted(solved)?;
+
+        Ok(count)
     }
 
     #[cfg(not(tarpaulin_include))]
@@ -1305,37 +2661,31 @@ impl Navigator {
             .filter_map(Result::ok)
     }
 
-    pub fn activate(&mut self, facets: &[String], mode: &Mode) {
-        #[cfg(feature = "with_stats")]
-        let start = Instant::now();
+    /// Like [`Navigator::parse_input_to_literals`], but fails on the first invalid facet.
+    pub fn literals_of<S>(&self, input: &[S]) -> Result<Vec<Literal>>
+    where
+        S: AsRef<str> + Debug,
+    {
+        input.iter().map(|s| self.literal(s)).collect()
+    }
 
-        for s in facets {
-            let lit = self.literal(s);
-            if lit.is_err() {
-                println!("\n[ERROR] {:?}\n", lit);
-                break;
-            };
+    pub fn activate(&mut self, facets: &[String], mode: &Mode) -> Result<()> {
+        let literals = self.literals_of(facets)?;
+        let (route, active_facets) = (self.route.clone(), self.active_facets.clone());
 
-            self.route.activate(s);
-            self.active_facets.push(unsafe { lit.unwrap_unchecked() });
-        }
+        facets.iter().for_each(|s| self.route.activate(s));
+        self.active_facets.extend(literals);
 
-        self.update(mode);
+        self.update_or_restore(mode, route, active_facets)?;
 
-        #[cfg(feature = "with_stats")]
-        {
-            let elapsed = start.elapsed();
-            println!("call    : --activate");
-            println!("elapsed : {:?}\n", elapsed);
-        }
+        Ok(())
     }
 
-    pub fn deactivate_any<S>(&mut self, facets: &[S], mode: &Mode)
+    pub fn deactivate_any<S>(&mut self, facets: &[S], mode: &Mode) -> Result<()>
     where
         S: Repr + Eq + Hash,
     {
-        println!("\nsolving...");
-        let start = Instant::now();
+        let (route, active_facets) = (self.route.clone(), self.active_facets.clone());
 
         facets.iter().unique().for_each(|f| {
             self.route.deactivate_any(f.repr()).iter().for_each(|pos| {
@@ -1343,60 +2693,92 @@ impl Navigator {
             });
         });
 
-        self.update(mode);
+        self.update_or_restore(mode, route, active_facets)
+    }
+
+    /// Drops all activated facets.
+    pub fn clear_route(&mut self, mode: &Mode) -> Result<()> {
+        let route = std::mem::replace(&mut self.route, Route(vec![]));
+        let active_facets = std::mem::take(&mut self.active_facets);
+
+        self.update_or_restore(mode, route, active_facets)
+    }
+
+    /// Replaces the route by `route`, e.g., to return to a route navigated before; facets
+    /// and counts along known routes are taken from the cache.
+    pub fn set_route(&mut self, route: Route, mode: &Mode) -> Result<()> {
+        let active_facets = self.literals_of(&route.0)?;
+        let route = std::mem::replace(&mut self.route, route);
+        let active_facets = std::mem::replace(&mut self.active_facets, active_facets);
+
+        self.update_or_restore(mode, route, active_facets)
+    }
+
+    /// Updates the current facets, falling back to `route` if that fails, e.g., on interrupt.
+    fn update_or_restore(
+        &mut self,
+        mode: &Mode,
+        route: Route,
+        active_facets: Vec<Literal>,
+    ) -> Result<()> {
+        let updated = self.update(mode);
+        if updated.is_err() {
+            self.route = route;
+            self.active_facets = active_facets;
+        }
+
+        updated
+    }
 
-        let elapsed = start.elapsed();
+    pub fn route(&self) -> &Route {
+        &self.route
+    }
 
-        println!("call    : --deactivate");
-        println!("elapsed : {:?}\n", elapsed);
+    /// Fraction of the search space already navigated, relative to the mode's weight.
+    pub fn pace(&self) -> f32 {
+        self.pace
     }
 
-    pub fn atom_entropy(&mut self) {
-        self.assume(&self.active_facets.clone());
+    /// Frequencies of the shown atoms over all answer sets under the route.
+    pub fn atom_entropy(&mut self) -> Result<Frequencies> {
+        self.assume(&self.active_facets.clone())?;
 
-        let ctl = Arc::get_mut(&mut self.control).expect("control error.");
+        let ctl = control_mut(&mut self.control)?;
         let mut freq_table: HM<Symbol, usize> = HM::new();
 
-        println!("solving...");
         let mut n = 0;
-        ctl.all_models()
-            .expect("solving failed.")
-            .for_each(|model| {
-                model.symbols.iter().for_each(|atom| {
-                    if let Some(freq) = freq_table.get_mut(atom) {
-                        *freq += 1;
-                    } else {
-                        freq_table.insert(*atom, 1);
-                    }
-                    n += 1;
-                })
+        for_each_model(ctl, &[], |model| {
+            model.symbols(ShowType::SHOWN)?.iter().for_each(|atom| {
+                if let Some(freq) = freq_table.get_mut(atom) {
+                    *freq += 1;
+                } else {
+                    freq_table.insert(*atom, 1);
+                }
+                n += 1;
             });
-        let entropy = -freq_table
-            .values()
-            .map(|f| (*f as f64 / n as f64))
-            .map(|p| p * p.log2())
-            .sum::<f64>();
-        let perplexitiy = 2f64.powf(entropy);
-        println!("{:.2} {:.2}", entropy, perplexitiy);
-        println!("{:?}", freq_table);
-        println!("{:?}", n);
+            Ok(true)
+        })?;
+
+        Ok(Frequencies::new(freq_table, n))
     }
 
-    pub fn abbundance(&mut self) {
-        self.assume(&self.active_facets.clone());
+    /// Frequencies of the facets entailed by activating each current facet under the route.
+    pub fn abbundance(&mut self) -> Result<Frequencies> {
+        self.assume(&self.active_facets.clone())?;
         let mut freq_table: HM<Symbol, usize> = HM::new();
 
-        println!("solving...");
         let mut n = 0;
-        self.current_facets.clone().iter().for_each(|a| {
-            self.inclusive_facets(
-                &[*self.literals.get(a).unwrap()]
-                    .into_iter()
-                    .chain(self.active_facets.clone().into_iter())
-                    .collect::<Vec<_>>(),
-            )
-            .iter()
-            .for_each(|b| {
+        for a in self.current_facets.clone().iter() {
+            let literal = *self
+                .literals
+                .get(a)
+                .ok_or_else(|| NavigatorError::UnknownAtom(a.repr()))?;
+            let assumptions = [literal]
+                .into_iter()
+                .chain(self.active_facets.clone().into_iter())
+                .collect::<Vec<_>>();
+
+            self.inclusive_facets(&assumptions)?.iter().for_each(|b| {
                 if let Some(freq) = freq_table.get_mut(b) {
                     *freq += 1;
                 } else {
@@ -1404,130 +2786,132 @@ impl Navigator {
                 }
                 n += 1;
             })
-        });
-        let e = -freq_table
-            .values()
-            .map(|f| (*f as f64 / n as f64))
-            .map(|p| p * p.log2())
-            .sum::<f64>();
-        let pp = 2f64.powf(e);
-        println!("{:.2} {:.2}", e, pp);
-        for (a, b) in freq_table {
-            println!("{:?} {:?}", a.to_string().unwrap(), b);
         }
-        println!("{:?}", n);
+
+        Ok(Frequencies::new(freq_table, n))
     }
 
-    pub fn uncertainty_true(&mut self, by: &[String], target: &[String]) -> f64 {
-        match target.is_empty() {
-            true => {
-                self.inclusive_facets(&self.parse_input_to_literals(by).collect::<Vec<_>>())
-                    .iter()
-                    .count() as f64
-                    / self.current_facets.len() as f64
-            }
+    pub fn uncertainty_true(&mut self, by: &[String], target: &[String]) -> Result<f64> {
+        let fs = self.inclusive_facets(&self.literals_of(by)?)?;
+
+        Ok(match target.is_empty() {
+            true => fs.len() as f64 / self.current_facets.len() as f64,
             _ => {
-                self.inclusive_facets(&self.parse_input_to_literals(by).collect::<Vec<_>>())
-                    .iter()
-                    .filter(|f| target.contains(&f.repr()))
-                    .count() as f64
+                fs.iter().filter(|f| target.contains(&f.repr())).count() as f64
                     / target.len() as f64
             }
-        }
+        })
     }
 
-    pub fn gini_child_true(&mut self, by: &[String], target: &[String]) -> (usize, f64) {
+    pub fn gini_child_true(&mut self, by: &[String], target: &[String]) -> Result<(usize, f64)> {
         if target.is_empty() {
-            panic!()
+            return Err(NavigatorError::InvalidInput(
+                "no target provided.".to_owned(),
+            ));
         }
-        let fs = self.inclusive_facets(&self.parse_input_to_literals(by).collect::<Vec<_>>());
+        let fs = self.inclusive_facets(&self.literals_of(by)?)?;
         let n = fs.len();
         let t = fs.iter().filter(|f| target.contains(&f.repr())).count() as f64 / n as f64;
         let f = 1f64 - t;
 
-        (n, 1.0 - t.powf(2.0) - f.powf(2.0))
+        Ok((n, 1.0 - t.powf(2.0) - f.powf(2.0)))
     }
 
-    pub fn uncertainty_false(&mut self, by: &[String], target: &[String]) -> f64 {
-        match target.is_empty() {
-            true => {
-                self.inclusive_facets(
-                    &self
-                        .parse_input_to_literals(by)
-                        .map(|l| l.negate())
-                        .collect::<Vec<_>>(),
-                )
-                .iter()
-                .count() as f64
-                    / self.current_facets.len() as f64
-            }
+    pub fn uncertainty_false(&mut self, by: &[String], target: &[String]) -> Result<f64> {
+        let assumptions = self
+            .literals_of(by)?
+            .into_iter()
+            .map(|l| l.negate())
+            .collect::<Vec<_>>();
+        let fs = self.inclusive_facets(&assumptions)?;
+
+        Ok(match target.is_empty() {
+            true => fs.len() as f64 / self.current_facets.len() as f64,
             _ => {
-                self.inclusive_facets(
-                    &self
-                        .parse_input_to_literals(by)
-                        .map(|l| l.negate())
-                        .collect::<Vec<_>>(),
-                )
-                .iter()
-                .filter(|f| target.contains(&f.repr()))
-                .count() as f64
+                fs.iter().filter(|f| target.contains(&f.repr())).count() as f64
                     / target.len() as f64
             }
-        }
+        })
     }
 
-    pub fn gini_child_false(&mut self, by: &[String], target: &[String]) -> (usize, f64) {
+    pub fn gini_child_false(&mut self, by: &[String], target: &[String]) -> Result<(usize, f64)> {
         if target.is_empty() {
-            panic!()
+            return Err(NavigatorError::InvalidInput(
+                "no target provided.".to_owned(),
+            ));
         }
-        let fs = self.inclusive_facets(
-            &self
-                .parse_input_to_literals(by)
-                .map(|l| l.negate())
-                .collect::<Vec<_>>(),
-        );
+        let assumptions = self
+            .literals_of(by)?
+            .into_iter()
+            .map(|l| l.negate())
+            .collect::<Vec<_>>();
+        let fs = self.inclusive_facets(&assumptions)?;
         let n = fs.len();
         let t = fs.iter().filter(|f| target.contains(&f.repr())).count() as f64 / n as f64;
         let f = 1f64 - t;
 
-        (n, 1.0 - t.powf(2.0) - f.powf(2.0))
+        Ok((n, 1.0 - t.powf(2.0) - f.powf(2.0)))
     }
 
-    pub fn gini(&mut self, by: &[String], target: Option<&[String]>) -> f64 {
-        let target_atoms = target.unwrap_or_else(|| &[]);
+    pub fn gini(&mut self, by: &[String], target: Option<&[String]>) -> Result<f64> {
+        let target_atoms = target.unwrap_or(&[]);
         let ((n, gt), (m, gf)) = (
-            self.gini_child_true(by, target_atoms),
-            self.gini_child_false(by, target_atoms),
+            self.gini_child_true(by, target_atoms)?,
+            self.gini_child_false(by, target_atoms)?,
         );
         let g = (m + n) as f64;
         //dbg!(g,n,gt,m,gf);
 
-        ((n as f64 / g) * gt) + ((m as f64 / g) * gf)
+        Ok(((n as f64 / g) * gt) + ((m as f64 / g) * gf))
     }
 
     #[cfg(not(tarpaulin_include))]
-    pub fn user_input(&self) -> String {
+    pub fn user_input(&self) -> Result<String> {
         let mut user_input = String::new();
-        stdout()
-            .flush()
-            .and_then(|_| stdin().read_line(&mut user_input))
-            .expect("IO operation failed.");
+        stdout().flush()?;
+
+        Ok(match stdin().read_line(&mut user_input)? {
+            0 => ":q".to_owned(), // EOF, e.g., program was read from stdin
+            _ => user_input.trim().to_owned(),
+        })
+    }
 
-        user_input.trim().to_owned()
+    /// Identifies the program by its sources, or by its text if given as such; unlike
+    /// [`Navigator::fingerprint`] it does not change as the files are edited.
+    pub fn program_id(&self) -> u64 {
+        match &self.origin {
+            Origin::Program(program) => cache::fingerprint([program.as_str()]),
+            Origin::Files { sources, .. } => {
+                let paths = sources
+                    .iter()
+                    .map(|source| match source.as_str() {
+                        "-" => source.clone(),
+                        path => std::fs::canonicalize(path)
+                            .map(|p| p.to_string_lossy().into_owned())
+                            .unwrap_or_else(|_| path.to_owned()),
+                    })
+                    .collect::<Vec<_>>();
+                cache::fingerprint(paths.iter().map(|p| p.as_str()))
+            }
+        }
     }
 
-    #[cfg(not(tarpaulin_include))]
-    pub fn info(&mut self) {
-        match self.route.len() > 3 {
-            true => print!("{}", self.route),
-            _ => print!("{:?}", self.route),
+    /// Prompt showing the route, the pace and the optimum, if any.
+    pub fn prompt(&mut self) -> Result<String> {
+        let mut prompt = match self.route.len() > 3 {
+            true => format!("{}", self.route),
+            _ => format!("{:?}", self.route),
+        };
+        match self.satisfiable(&self.active_facets.clone())? {
+            true => prompt.push_str(&format!(" [{:.2}]", self.pace)),
+            _ => prompt.push_str(" [UNSAT]"),
         }
-        //print!("{}", self.route);
-        match self.satisfiable(&self.active_facets.clone()) {
-            //true => print!(" [ {:?}% ] ~> ", (self.pace * 100f32).round() as usize),
-            true => print!(" [{:.2}] ~> ", self.pace),
-            _ => print!(" [UNSAT] ~> "),
+        if let Some(optimum) = &self.optimum {
+            prompt.push_str(&format!(" [opt {}]", optimum.iter().join(" ")));
         }
+        prompt.push_str(" ~> ");
+
+        Ok(prompt)
     }
 }
 
@@ -1660,10 +3044,232 @@ mod test {
         Ok(())
     }
 
-    #[should_panic(expected = "program is unsatisfiable.")]
     #[test]
-    fn new_panic() {
-        let _ = Navigator::new(UNSAT, 0).unwrap();
+    fn extend_and_ground() -> Result<()> {
+        let mode = Mode::GoalOriented(Weight::FacetCounting);
+        let mut nav = Navigator::new(format!("{}\n#program step(t).\ns(t).", PI_1), 0)?;
+        nav.activate(&["b".to_owned(), "c".to_owned(), "e".to_owned()], &mode)?;
+
+        // only the facet that became unsatisfiable is dropped, later ones are kept
+        assert_eq!(nav.extend(":- c.", &mode)?, vec!["c".to_owned()]);
+        assert_eq!(nav.route().0, vec!["b".to_owned(), "e".to_owned()]);
+        assert_eq!(nav.active_facets, nav.literals_of(&["b", "e"])?);
+
+        assert!(nav.ground("step(1)", &mode)?.is_empty());
+        assert!(nav.literal("s(1)").is_ok());
+        assert!(matches!(
+            nav.ground("step(1)", &mode),
+            Err(NavigatorError::InvalidInput(_))
+        ));
+        assert!(nav.ground("base", &mode).is_err());
+        assert_eq!(nav.logic_program.matches("% grounded").count(), 1);
+        assert!(nav.ground("step(2)", &mode)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn workers() -> Result<()> {
+        let mut single = Navigator::new(PI_1, 0)?;
+        let mut pooled = Navigator::new(PI_1, 0)?;
+        pooled.set_workers(3)?;
+        assert!(pooled.set_workers(0).is_err());
+
+        // replicas replay program changes and take over the route
+        for nav in [&mut single, &mut pooled] {
+            nav.extend("f :- c.", &Mode::GoalOriented(Weight::FacetCounting))?;
+            nav.activate(&["b".to_owned()], &Mode::GoalOriented(Weight::Absolute))?;
+        }
+
+        for weight in [
+            Weight::Absolute,
+            Weight::FacetCounting,
+            Weight::Information,
+            Weight::Entropy,
+            Weight::InformationGain,
+        ] {
+            assert_eq!(
+                weight.all_weights(&mut pooled)?,
+                weight.all_weights(&mut single)?
+            );
+            assert_eq!(
+                weight.all_zooms(&mut pooled)?,
+                weight.all_zooms(&mut single)?
+            );
+        }
+        for mode in [
+            Mode::StrictlyGoalOriented(Weight::Absolute),
+            Mode::StrictlyGoalOriented(Weight::FacetCounting),
+            Mode::Explore(Weight::Absolute),
+            Mode::Explore(Weight::FacetCounting),
+            Mode::StrictlyGoalOriented(Weight::InformationGain),
+            Mode::Explore(Weight::Entropy),
+        ] {
+            let fs = single.current_facets.0.clone();
+            assert_eq!(
+                filter(&mode, &mut pooled, &fs)?,
+                filter(&mode, &mut single, &fs)?
+            );
+        }
+        assert!(pooled.pool.is_some());
+
+        let facets = pooled.current_facets.iter().map(|f| f.repr()).collect();
+        let results = pooled.par_map(
+            facets,
+            |nav, facet| Ok(nav.facets_under_step(facet)?.len()),
+            &ProgressBar::hidden(),
+        )?;
+        assert_eq!(results.len(), pooled.current_facets.len());
+
+        // a changed program restarts the replicas on demand
+        pooled.extend("g.", &Mode::GoalOriented(Weight::FacetCounting))?;
+        assert!(pooled.pool.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn threads() -> Result<()> {
+        let program = "a;b. c;d :- b. {e;f}.";
+        let parallel = |mode: &str| {
+            Navigator::startup(
+                Origin::Program(program.to_owned()),
+                vec!["--parallel-mode".to_owned(), mode.to_owned()],
+                None,
+                0,
+                false,
+            )
+        };
+
+        let mut single = parallel("1")?;
+        for mode in ["4", "4,compete", "8,split"] {
+            let mut nav = parallel(mode)?;
+
+            assert_eq!(nav.count(&[])?, single.count(&[])?);
+            assert_eq!(nav.count(&[])?, 12);
+            let assumptions = [nav.literal("b")?, nav.literal("~e")?];
+            assert_eq!(nav.count(&assumptions)?, 4);
+
+            let answer_sets = nav.answer_sets(0)?;
+            assert_eq!(answer_sets.len(), 12);
+            assert_eq!(answer_sets.to_hashset().len(), 12);
+            assert_eq!(nav.initial_facets.len(), single.initial_facets.len());
+        }
+
+        assert_eq!(
+            requested_threads(&["-t".to_owned(), "4".to_owned()]),
+            Some(4)
+        );
+        assert_eq!(requested_threads(&["-t2,compete".to_owned()]), Some(2));
+        assert_eq!(
+            requested_threads(&["--parallel-mode=3".to_owned(), "-c".to_owned()]),
+            Some(3)
+        );
+        assert_eq!(requested_threads(&["--const".to_owned()]), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn projection() -> Result<()> {
+        let program = "a;b. c;d :- b. {aux(1..2)} = 1.";
+        let projected = |projection| {
+            Navigator::startup(program.to_owned(), vec![], Some(projection), 0, |ctl| {
+                ctl.add("base", &[], program).map_err(NavigatorError::from)
+            })
+        };
+
+        let mut nav = Navigator::new(program, 0)?;
+        assert_eq!(nav.initial_facets.len(), 6);
+        assert_eq!(nav.count(&[])?, 6);
+
+        let mut nav = projected(Projection::Signatures(vec![
+            ("a".to_owned(), 0),
+            ("b".to_owned(), 0),
+            ("c".to_owned(), 0),
+            ("d".to_owned(), 0),
+        ]))?;
+        assert_eq!(
+            nav.initial_facets
+                .to_strings()
+                .collect::<Vec<String>>()
+                .to_hashset(),
+            vec!["a", "b", "c", "d"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>()
+                .to_hashset()
+        );
+        assert_eq!(nav.count(&[])?, 3);
+        assert!(matches!(
+            nav.literal("aux(1)"),
+            Err(NavigatorError::UnknownAtom(_))
+        ));
+
+        let mut nav = projected(Projection::Show)?;
+        assert_eq!(nav.count(&[])?, 6);
+
+        assert_eq!("show".parse::<Projection>()?, Projection::Show);
+        assert!("p/x".parse::<Projection>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn optimize() -> Result<()> {
+        let program = "a;b;c. #minimize{1,a:a; 1,b:b; 2,c:c}.";
+        let mode = Mode::GoalOriented(Weight::Absolute);
+
+        let mut nav = Navigator::new(program, 0)?;
+        nav.optimize(Optimization::Optimal, &mode)?;
+        assert_eq!(nav.optimum, Some(vec![1]));
+        assert_eq!(nav.count(&[])?, 2);
+        assert_eq!(
+            nav.initial_facets
+                .to_strings()
+                .collect::<Vec<String>>()
+                .to_hashset(),
+            vec!["a".to_owned(), "b".to_owned()].to_hashset()
+        );
+        assert!(nav
+            .costed_answer_sets(0)?
+            .iter()
+            .all(|(_, cost)| cost == &vec![1]));
+
+        let mut nav = Navigator::new(program, 0)?;
+        nav.optimize(Optimization::Bounded(vec![2]), &mode)?;
+        assert_eq!(nav.count(&[])?, 3);
+        assert_eq!(nav.initial_facets.len(), 3);
+
+        // the optimum is found without restricting later solving to improving models
+        let mut nav = Navigator::new(program, 0)?;
+        assert_eq!(nav.find_optimum()?, vec![1]);
+        assert_eq!(nav.count(&[])?, 3);
+
+        let mut nav = Navigator::new("a;b. :~ a. [1]", 0)?;
+        assert_eq!(nav.find_optimum()?, vec![0]);
+
+        let mut nav = Navigator::new(PI_1, 0)?;
+        assert!(nav.find_optimum()?.is_empty());
+        assert!(matches!(
+            nav.optimize(Optimization::Optimal, &mode),
+            Err(NavigatorError::Unsupported(_))
+        ));
+
+        assert_eq!(
+            "3,2".parse::<Optimization>()?,
+            Optimization::Bounded(vec![3, 2])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn new_unsat() {
+        assert!(matches!(
+            Navigator::new(UNSAT, 0),
+            Err(NavigatorError::Unsatisfiable)
+        ));
     }
 
     #[test]
@@ -1709,30 +3315,30 @@ mod test {
         );
 
         assert_eq!(
-            nav.inclusive_facets(&[nav.literal("a")?, nav.literal("~a")?]),
+            nav.inclusive_facets(&[nav.literal("a")?, nav.literal("~a")?])?,
             Facets(vec![])
         );
-        assert_eq!(nav.inclusive_facets(&[nav.literal("a")?]), Facets(vec![]));
-        assert_eq!(nav.inclusive_facets(&[nav.literal("~b")?]), Facets(vec![]));
-        assert_eq!(nav.inclusive_facets(&[nav.literal("c")?]), Facets(vec![]));
-        assert_eq!(nav.inclusive_facets(&[nav.literal("d")?]), Facets(vec![]));
+        assert_eq!(nav.inclusive_facets(&[nav.literal("a")?])?, Facets(vec![]));
+        assert_eq!(nav.inclusive_facets(&[nav.literal("~b")?])?, Facets(vec![]));
+        assert_eq!(nav.inclusive_facets(&[nav.literal("c")?])?, Facets(vec![]));
+        assert_eq!(nav.inclusive_facets(&[nav.literal("d")?])?, Facets(vec![]));
 
         assert_eq!(
-            nav.inclusive_facets(&[nav.literal("~a")?])
+            nav.inclusive_facets(&[nav.literal("~a")?])?
                 .to_strings()
                 .collect::<Vec<String>>()
                 .to_hashset(),
             vec!["c".to_owned(), "d".to_owned()].to_hashset()
         );
         assert_eq!(
-            nav.inclusive_facets(&[nav.literal("~c")?])
+            nav.inclusive_facets(&[nav.literal("~c")?])?
                 .to_strings()
                 .collect::<Vec<String>>()
                 .to_hashset(),
             vec!["a".to_owned(), "b".to_owned(), "d".to_owned()].to_hashset()
         );
         assert_eq!(
-            nav.inclusive_facets(&[nav.literal("~d")?])
+            nav.inclusive_facets(&[nav.literal("~d")?])?
                 .to_strings()
                 .collect::<Vec<String>>()
                 .to_hashset(),
@@ -1746,27 +3352,25 @@ mod test {
     fn activate_deactivate() -> Result<()> {
         let mut rng = rand::thread_rng();
 
-        let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-        cache.inclusive_facets.clear();
-        drop(cache);
-
         let mut nav = Navigator::new(GRID, 0)?;
         let lits = nav.clone().literals;
 
-        nav.activate(
-            &["bla".to_owned()],
-            &Mode::GoalOriented(Weight::FacetCounting),
-        );
-        nav.deactivate_any(
-            &[Symbol::create_id("bla", true)?],
-            &Mode::GoalOriented(Weight::FacetCounting),
-        );
+        assert!(matches!(
+            nav.activate(
+                &["bla".to_owned()],
+                &Mode::GoalOriented(Weight::FacetCounting),
+            ),
+            Err(NavigatorError::UnknownAtom(_))
+        ));
         assert_eq!(nav.active_facets, vec![]);
+        assert_eq!(nav.route, Route(vec![]));
 
-        nav.activate(
-            &["set_obj_cell(1, 1)".to_owned()],
-            &Mode::GoalOriented(Weight::FacetCounting),
-        );
+        assert!(nav
+            .activate(
+                &["set_obj_cell(1, 1)".to_owned()],
+                &Mode::GoalOriented(Weight::FacetCounting),
+            )
+            .is_err());
         assert_eq!(nav.active_facets, vec![]);
 
         let sym0 = lits
@@ -1884,39 +3488,87 @@ mod test {
         assert_eq!(nav.pace.round(), 1.0_f32.round());
         nav.active_facets = vec![];
         nav.route = Route(vec![]);
-        nav.update(&Mode::GoalOriented(Weight::FacetCounting));
+        nav.update(&Mode::GoalOriented(Weight::FacetCounting))?;
         assert_eq!(nav.pace.round(), 0.0_f32.round());
 
         Ok(())
     }
 
+    #[test]
+    fn cache() -> Result<()> {
+        let mut nav = Navigator::new("{ab;c;a;bc}. :- a, bc, ab.", 0)?;
+        assert!(nav.cache.counts.is_empty());
+
+        // routes ab,c and a,bc are distinct, although their concatenations coincide
+        let (ab, c, a, bc) = (
+            nav.literal("ab")?,
+            nav.literal("c")?,
+            nav.literal("a")?,
+            nav.literal("bc")?,
+        );
+        assert_eq!(nav.cached_count(&[ab, c])?, 3);
+        assert_eq!(nav.cached_count(&[a, bc])?, 2);
+
+        // permutations of a route hit the same entry
+        let hits = nav.cache.stats().hits;
+        assert_eq!(nav.cached_count(&[c, ab, c])?, 3);
+        assert_eq!(nav.cache.stats().hits, hits + 1);
+
+        // modes sharing a filter are cached apart
+        let fs = nav.current_facets.clone().0;
+        filter(
+            &Mode::StrictlyGoalOriented(Weight::FacetCounting),
+            &mut nav,
+            &fs,
+        )?;
+        filter(
+            &Mode::StrictlyGoalOriented(Weight::Information),
+            &mut nav,
+            &fs,
+        )?;
+        assert_eq!(nav.cache.filtered.len(), 2);
+
+        // every navigator owns its cache
+        let other = Navigator::new(PI_1, 0)?;
+        assert!(other.cache.counts.is_empty());
+
+        nav.set_cache_capacity(CacheCapacity {
+            counts: 1,
+            ..CacheCapacity::default()
+        });
+        assert_eq!(nav.cache.counts.len(), 1);
+        assert_eq!(nav.cache_capacity().counts, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn count() -> Result<()> {
         let mut nav = Navigator::new(PI_1, 0)?;
 
-        assert_eq!(nav.count(&[]), 3);
+        assert_eq!(nav.count(&[])?, 3);
 
         let msrs = [
-            nav.count(&[nav.literal("a")?]),
-            nav.count(&[nav.literal("~b")?]),
-            nav.count(&[nav.literal("c")?]),
-            nav.count(&[nav.literal("d")?]),
-            nav.count(&[nav.literal("b")?, nav.literal("c")?]),
-            nav.count(&[nav.literal("b")?, nav.literal("d")?]),
-            nav.count(&[nav.literal("~c")?, nav.literal("~d")?]),
+            nav.count(&[nav.literal("a")?])?,
+            nav.count(&[nav.literal("~b")?])?,
+            nav.count(&[nav.literal("c")?])?,
+            nav.count(&[nav.literal("d")?])?,
+            nav.count(&[nav.literal("b")?, nav.literal("c")?])?,
+            nav.count(&[nav.literal("b")?, nav.literal("d")?])?,
+            nav.count(&[nav.literal("~c")?, nav.literal("~d")?])?,
         ];
         assert!(msrs.iter().all(|c| *c == 1));
 
         let other = [
-            nav.count(&[nav.literal("b")?]),
-            nav.count(&[nav.literal("~a")?]),
-            nav.count(&[nav.literal("~c")?]),
-            nav.count(&[nav.literal("~d")?]),
+            nav.count(&[nav.literal("b")?])?,
+            nav.count(&[nav.literal("~a")?])?,
+            nav.count(&[nav.literal("~c")?])?,
+            nav.count(&[nav.literal("~d")?])?,
         ];
         assert!(other.iter().all(|c| *c == 2));
 
-        assert_eq!(nav.count(&[nav.literal("~e")?]), 0);
-        assert_eq!(nav.count(&[nav.literal("e")?]), 3);
+        assert_eq!(nav.count(&[nav.literal("~e")?])?, 0);
+        assert_eq!(nav.count(&[nav.literal("e")?])?, 3);
 
         Ok(())
     }
@@ -1925,35 +3577,84 @@ mod test {
     fn current_route_is_maximal_safe() -> Result<()> {
         let mut nav = Navigator::new(PI_1, 0)?;
 
-        assert!(nav.satisfiable(&[]));
+        assert!(nav.satisfiable(&[])?);
 
-        [
+        for v in [
             ["a", "~b", "~c", "~d"],
             ["b", "~a", "c", "~d"],
             ["b", "~a", "~c", "d"],
-        ]
-        .iter()
-        .for_each(|v| {
+        ] {
             nav.activate(
                 &v.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
                 &Mode::GoalOriented(Weight::FacetCounting),
-            );
-            assert!(nav.current_route_is_maximal_safe());
+            )?;
+            assert!(nav.current_route_is_maximal_safe()?);
             nav.route = Route(vec![]);
             nav.active_facets = vec![];
-        });
+        }
 
-        [["~c", "e"], ["~d", "e"], ["b", "~a"]]
-            .iter()
-            .for_each(|v| {
-                nav.activate(
-                    &v.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
-                    &Mode::GoalOriented(Weight::FacetCounting),
-                );
-                assert!(!nav.current_route_is_maximal_safe());
-                nav.route = Route(vec![]);
-                nav.active_facets = vec![];
-            });
+        for v in [["~c", "e"], ["~d", "e"], ["b", "~a"]] {
+            nav.activate(
+                &v.iter().map(|s| s.to_string()).collect::<Vec<String>>(),
+                &Mode::GoalOriented(Weight::FacetCounting),
+            )?;
+            assert!(!nav.current_route_is_maximal_safe()?);
+            nav.route = Route(vec![]);
+            nav.active_facets = vec![];
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn explain() -> Result<()> {
+        let mut nav = Navigator::new(PI_1, 0)?;
+        nav.activate(
+            &["e", "b", "~c"].map(|s| s.to_owned()),
+            &Mode::GoalOriented(Weight::FacetCounting),
+        )?;
+
+        let mut explain = |facet: &str| -> Result<Option<(String, Vec<String>)>> {
+            Ok(nav
+                .explain(facet)?
+                .map(|(entailed, core)| (entailed, core.0)))
+        };
+        let some = |entailed: &str, core: &[&str]| {
+            Some((
+                entailed.to_owned(),
+                core.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            ))
+        };
+        assert_eq!(explain("d")?, some("d", &["b", "~c"]));
+        assert_eq!(explain("~a")?, some("~a", &["b"]));
+        assert_eq!(explain("c")?, some("~c", &["~c"]));
+        // facts need no route at all
+        assert_eq!(explain("e")?, some("e", &[]));
+        assert!(explain("x").is_err());
+
+        let assumptions = nav.literals_of(&["e", "b", "~b"])?;
+        let core = nav.core(&assumptions)?.expect("no core.");
+        assert!(core.contains(&assumptions[1]) && core.contains(&assumptions[2]));
+        assert!(nav.core(&assumptions[..2])?.is_none());
+
+        nav.route = Route(vec![]);
+        nav.active_facets = vec![];
+        assert!(nav.explain("a")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn atom_entropy() -> Result<()> {
+        let mut nav = Navigator::new("a;b.", 0)?;
+        let frequencies = nav.atom_entropy()?;
+        assert_eq!(frequencies.n, 2);
+        assert_eq!(frequencies.entropy, 1f64);
+        assert_eq!(frequencies.perplexity, 2f64);
+        assert_eq!(
+            frequencies.frequencies,
+            BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 1)])
+        );
 
         Ok(())
     }
@@ -1962,27 +3663,27 @@ mod test {
     fn satisfiable() -> Result<()> {
         let mut nav = Navigator::new(PI_1, 0)?;
 
-        assert_eq!(nav.count(&[]), 3);
+        assert_eq!(nav.count(&[])?, 3);
 
         let sat = [
-            nav.satisfiable(&[nav.literal("a")?]),
-            nav.satisfiable(&[nav.literal("~b")?]),
-            nav.satisfiable(&[nav.literal("c")?]),
-            nav.satisfiable(&[nav.literal("d")?]),
-            nav.satisfiable(&[nav.literal("b")?, nav.literal("c")?]),
-            nav.satisfiable(&[nav.literal("b")?, nav.literal("d")?]),
-            nav.satisfiable(&[nav.literal("b")?]),
-            nav.satisfiable(&[nav.literal("~a")?]),
-            nav.satisfiable(&[nav.literal("~c")?]),
-            nav.satisfiable(&[nav.literal("~d")?]),
+            nav.satisfiable(&[nav.literal("a")?])?,
+            nav.satisfiable(&[nav.literal("~b")?])?,
+            nav.satisfiable(&[nav.literal("c")?])?,
+            nav.satisfiable(&[nav.literal("d")?])?,
+            nav.satisfiable(&[nav.literal("b")?, nav.literal("c")?])?,
+            nav.satisfiable(&[nav.literal("b")?, nav.literal("d")?])?,
+            nav.satisfiable(&[nav.literal("b")?])?,
+            nav.satisfiable(&[nav.literal("~a")?])?,
+            nav.satisfiable(&[nav.literal("~c")?])?,
+            nav.satisfiable(&[nav.literal("~d")?])?,
         ];
         assert!(sat.iter().all(|b| *b));
 
         let unsat = [
-            nav.satisfiable(&[nav.literal("a")?, nav.literal("~a")?]),
-            nav.satisfiable(&[nav.literal("a")?, nav.literal("b")?]),
-            nav.satisfiable(&[nav.literal("c")?, nav.literal("d")?]),
-            nav.satisfiable(&[nav.literal("~e")?]),
+            nav.satisfiable(&[nav.literal("a")?, nav.literal("~a")?])?,
+            nav.satisfiable(&[nav.literal("a")?, nav.literal("b")?])?,
+            nav.satisfiable(&[nav.literal("c")?, nav.literal("d")?])?,
+            nav.satisfiable(&[nav.literal("~e")?])?,
         ];
         assert!(!unsat.iter().any(|b| *b));
 
@@ -1997,8 +3698,8 @@ mod test {
             .current_facets
             .clone()
             .iter()
-            .map(|f| eval_weight(&Weight::FacetCounting, &mut nav, &f.repr()).0)
-            .collect::<Vec<usize>>();
+            .map(|f| eval_weight(&Weight::FacetCounting, &mut nav, &f.repr()).map(|(w, _)| w))
+            .collect::<Result<Vec<usize>>>()?;
         assert_eq!(ifs.len(), nav.current_facets.len());
         assert!(ifs.iter().all(|w| *w == 34));
 
@@ -2006,49 +3707,61 @@ mod test {
             .current_facets
             .clone()
             .iter()
-            .map(|f| eval_weight(&Weight::FacetCounting, &mut nav, &format!("~{}", f.repr())).0)
-            .collect::<Vec<usize>>();
+            .map(|f| {
+                eval_weight(&Weight::FacetCounting, &mut nav, &format!("~{}", f.repr()))
+                    .map(|(w, _)| w)
+            })
+            .collect::<Result<Vec<usize>>>()?;
         assert_eq!(efs.len(), nav.current_facets.len());
         assert!(efs.iter().all(|w| *w == 2));
 
         let mut nav = Navigator::new(PI_1, 0)?;
 
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "a"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "a")?,
             (nav.current_facets.len() * 2, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "~b"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "~b")?,
             (nav.current_facets.len() * 2, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "c"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "c")?,
             (nav.current_facets.len() * 2, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "d"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "d")?,
             (nav.current_facets.len() * 2, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "b"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "b")?,
             (4, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "~c"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "~c")?,
             (2, None)
         );
         assert_eq!(
-            eval_weight(&Weight::FacetCounting, &mut nav, "~d"),
+            eval_weight(&Weight::FacetCounting, &mut nav, "~d")?,
             (2, None)
         );
 
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "a"), (2, Some(1)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "~b"), (2, Some(1)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "c"), (2, Some(1)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "d"), (2, Some(1)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "b"), (1, Some(2)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "~c"), (1, Some(2)));
-        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "~d"), (1, Some(2)));
+        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "a")?, (2, Some(1)));
+        assert_eq!(
+            eval_weight(&Weight::Absolute, &mut nav, "~b")?,
+            (2, Some(1))
+        );
+        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "c")?, (2, Some(1)));
+        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "d")?, (2, Some(1)));
+        assert_eq!(eval_weight(&Weight::Absolute, &mut nav, "b")?, (1, Some(2)));
+        assert_eq!(
+            eval_weight(&Weight::Absolute, &mut nav, "~c")?,
+            (1, Some(2))
+        );
+        assert_eq!(
+            eval_weight(&Weight::Absolute, &mut nav, "~d")?,
+            (1, Some(2))
+        );
 
         Ok(())
     }
@@ -2061,8 +3774,8 @@ mod test {
             .current_facets
             .clone()
             .iter()
-            .map(|f| eval_zoom(&Weight::FacetCounting, &mut nav, &f.repr()).0)
-            .collect::<Vec<f32>>();
+            .map(|f| eval_zoom(&Weight::FacetCounting, &mut nav, &f.repr()).map(|(w, _)| w))
+            .collect::<Result<Vec<f32>>>()?;
         assert_eq!(ifs.len(), nav.current_facets.len());
         assert!(ifs.iter().all(|z| *z as usize == 0.2987_f32 as usize));
 
@@ -2070,68 +3783,71 @@ mod test {
             .current_facets
             .clone()
             .iter()
-            .map(|f| eval_zoom(&Weight::FacetCounting, &mut nav, &format!("~{}", f.repr())).0)
-            .collect::<Vec<f32>>();
+            .map(|f| {
+                eval_zoom(&Weight::FacetCounting, &mut nav, &format!("~{}", f.repr()))
+                    .map(|(w, _)| w)
+            })
+            .collect::<Result<Vec<f32>>>()?;
         assert_eq!(efs.len(), nav.current_facets.len());
         assert!(efs.iter().all(|z| *z as usize == 0.012346_f32 as usize));
 
         let mut nav = Navigator::new(PI_1, 0)?;
 
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "a"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "a")?,
             (1.0_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "~b"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "~b")?,
             (1.0_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "c"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "c")?,
             (1.0_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "d"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "d")?,
             (1.0_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "b"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "b")?,
             (0.5_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "~c"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "~c")?,
             (0.25_f32, None)
         );
         assert_eq!(
-            eval_zoom(&Weight::FacetCounting, &mut nav, "~d"),
+            eval_zoom(&Weight::FacetCounting, &mut nav, "~d")?,
             (0.25_f32, None)
         );
 
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "a"),
+            eval_zoom(&Weight::Absolute, &mut nav, "a")?,
             (2_f32 / 3_f32, Some(1_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "~b"),
+            eval_zoom(&Weight::Absolute, &mut nav, "~b")?,
             (2_f32 / 3_f32, Some(1_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "c"),
+            eval_zoom(&Weight::Absolute, &mut nav, "c")?,
             (2_f32 / 3_f32, Some(1_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "d"),
+            eval_zoom(&Weight::Absolute, &mut nav, "d")?,
             (2_f32 / 3_f32, Some(1_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "b"),
+            eval_zoom(&Weight::Absolute, &mut nav, "b")?,
             (1_f32 / 3_f32, Some(2_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "~c"),
+            eval_zoom(&Weight::Absolute, &mut nav, "~c")?,
             (1_f32 / 3_f32, Some(2_f32 / 3_f32))
         );
         assert_eq!(
-            eval_zoom(&Weight::Absolute, &mut nav, "~d"),
+            eval_zoom(&Weight::Absolute, &mut nav, "~d")?,
             (1_f32 / 3_f32, Some(2_f32 / 3_f32))
         );
 
@@ -2143,14 +3859,14 @@ mod test {
         let mut nav = Navigator::new(GRID, 0)?;
         let fs = nav.current_facets.clone().0;
 
-        let filtered = filter(&Mode::GoalOriented(Weight::FacetCounting), &mut nav, &fs);
+        let filtered = filter(&Mode::GoalOriented(Weight::FacetCounting), &mut nav, &fs)?;
         assert_eq!(filtered.len(), 0);
 
         let filtered = filter(
             &Mode::StrictlyGoalOriented(Weight::FacetCounting),
             &mut nav,
             &fs,
-        );
+        )?;
         assert_eq!(filtered.len(), nav.current_facets.len());
         assert_eq!(
             filtered.to_hashset(),
@@ -2159,7 +3875,7 @@ mod test {
                 .collect::<Vec<String>>()
                 .to_hashset()
         );
-        let filtered = filter(&Mode::Explore(Weight::FacetCounting), &mut nav, &fs);
+        let filtered = filter(&Mode::Explore(Weight::FacetCounting), &mut nav, &fs)?;
         assert_eq!(filtered.len(), nav.current_facets.len());
         assert_eq!(
             filtered.to_hashset(),
@@ -2170,22 +3886,17 @@ mod test {
                 .to_hashset()
         );
 
-        let mut cache = CACHE.lock().expect("cache lock is poisoned.");
-        cache.max_fc_facets.clear();
-        cache.min_fc_facets.clear();
-        drop(cache);
-
         let mut nav0 = Navigator::new(PI_1, 0)?;
         let fs0 = nav0.current_facets.clone().0;
 
-        let filtered0 = filter(&Mode::GoalOriented(Weight::FacetCounting), &mut nav0, &fs0);
+        let filtered0 = filter(&Mode::GoalOriented(Weight::FacetCounting), &mut nav0, &fs0)?;
         assert_eq!(filtered0.len(), 0);
 
         let filtered0 = filter(
             &Mode::StrictlyGoalOriented(Weight::FacetCounting),
             &mut nav0,
             &fs0,
-        );
+        )?;
         assert_eq!(
             filtered0.to_hashset(),
             vec![
@@ -2196,20 +3907,20 @@ mod test {
             ]
             .to_hashset()
         );
-        let filtered = filter(&Mode::Explore(Weight::FacetCounting), &mut nav0, &fs0);
+        let filtered = filter(&Mode::Explore(Weight::FacetCounting), &mut nav0, &fs0)?;
         assert_eq!(
             filtered.to_hashset(),
             vec!["~c".to_owned(), "~d".to_owned()].to_hashset()
         );
 
-        let filtered = filter(&Mode::GoalOriented(Weight::Absolute), &mut nav0, &fs0);
+        let filtered = filter(&Mode::GoalOriented(Weight::Absolute), &mut nav0, &fs0)?;
         assert_eq!(filtered.len(), 0);
 
         let filtered = filter(
             &Mode::StrictlyGoalOriented(Weight::Absolute),
             &mut nav0,
             &fs0,
-        );
+        )?;
         assert_eq!(
             filtered.to_hashset(),
             vec![
@@ -2221,7 +3932,7 @@ mod test {
             .to_hashset()
         );
 
-        let filtered = filter(&Mode::Explore(Weight::Absolute), &mut nav0, &fs0);
+        let filtered = filter(&Mode::Explore(Weight::Absolute), &mut nav0, &fs0)?;
         assert_eq!(
             filtered.to_hashset(),
             vec![
@@ -2235,4 +3946,154 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn entropy_t() -> Result<()> {
+        let mut nav = Navigator::new(PI_1, 0)?;
+        let fs = nav.current_facets.clone().0;
+        let third = -(1f64 / 3f64) * (1f64 / 3f64).log2() - (2f64 / 3f64) * (2f64 / 3f64).log2();
+
+        let weights = Weight::Entropy.weight(&mut nav, "a")?;
+        assert_eq!(weights[0], ("a".to_owned(), 1f64));
+        assert_eq!(weights[1].0, "~a");
+        assert!((weights[1].1 - (1f64 - 1f64 / 3f64.log2())).abs() < 1e-9);
+
+        let gains = Weight::InformationGain.all_weights(&mut nav)?;
+        assert_eq!(gains.len(), 2 * fs.len());
+        assert!(gains.iter().all(|(_, w)| (w - third).abs() < 1e-9));
+
+        let filtered = filter(&Mode::StrictlyGoalOriented(Weight::Entropy), &mut nav, &fs)?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["a", "~b", "c", "d"]
+                .into_iter()
+                .map(|s| s.to_owned())
+                .collect::<Vec<_>>()
+                .to_hashset()
+        );
+        let filtered = filter(&Mode::Explore(Weight::Entropy), &mut nav, &fs)?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["b", "~a", "~c", "~d"]
+                .into_iter()
+                .map(|s| s.to_owned())
+                .collect::<Vec<_>>()
+                .to_hashset()
+        );
+        let filtered = filter(
+            &Mode::StrictlyGoalOriented(Weight::InformationGain),
+            &mut nav,
+            &fs,
+        )?;
+        assert_eq!(filtered.len(), 2 * fs.len());
+
+        let mode = Mode::StrictlyGoalOriented(Weight::Entropy);
+        nav.activate(&["b".to_owned()], &mode)?;
+        assert!((nav.pace() as f64 - (1f64 - 1f64 / 3f64.log2())).abs() < 1e-6);
+        nav.activate(&["c".to_owned()], &mode)?;
+        assert_eq!(nav.pace(), 1f32);
+
+        Ok(())
+    }
+
+    #[test]
+    fn information_gain_t() -> Result<()> {
+        // e splits the answer sets evenly, whereas a, b, c and d leave one each
+        let mut nav = Navigator::new("a;b;c;d. e :- a. e :- b.", 0)?;
+        let fs = nav.current_facets.clone().0;
+        let quarter = -0.25f64 * 0.25f64.log2() - 0.75f64 * 0.75f64.log2();
+
+        let gains = Weight::InformationGain.weight(&mut nav, "e")?;
+        assert_eq!(gains, vec![("e".to_owned(), 1f64), ("~e".to_owned(), 1f64)]);
+        let gains = Weight::InformationGain.weight(&mut nav, "a")?;
+        assert!(gains.iter().all(|(_, w)| (w - quarter).abs() < 1e-9));
+        let weights = Weight::Entropy.weight(&mut nav, "a")?;
+        assert_eq!(weights[0], ("a".to_owned(), 1f64));
+        assert_eq!(
+            Weight::Entropy.weight(&mut nav, "e")?[0],
+            ("e".to_owned(), 0.5)
+        );
+
+        // entropy prefers the facets removing the most, information gain the even split
+        let filtered = filter(&Mode::StrictlyGoalOriented(Weight::Entropy), &mut nav, &fs)?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["a", "b", "c", "d"]
+                .into_iter()
+                .map(|s| s.to_owned())
+                .collect::<Vec<_>>()
+                .to_hashset()
+        );
+        let filtered = filter(
+            &Mode::StrictlyGoalOriented(Weight::InformationGain),
+            &mut nav,
+            &fs,
+        )?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["e".to_owned(), "~e".to_owned()].to_hashset()
+        );
+
+        let (z0, z1) = eval_zoom(&Weight::InformationGain, &mut nav, "a")?;
+        assert!((z0 as f64 - quarter / 2f64).abs() < 1e-6);
+        assert_eq!(z0, z1);
+        assert_eq!(eval_zoom(&Weight::Entropy, &mut nav, "a")?.0, 1f32);
+
+        Ok(())
+    }
+
+    #[test]
+    fn declared_weight() -> Result<()> {
+        let mut nav = Navigator::new(
+            "a;b. c;d :- b. cost(a,3) :- a. cost(c,1) :- c. cost(d,2) :- d.\n\
+             %@fasb weight cost/2",
+            0,
+        )?;
+        let fs = nav.current_facets.clone().0;
+        assert_eq!(nav.declared_weights(), [("cost".to_owned(), 2)]);
+
+        let weight = Weight::Declared("cost".to_owned());
+        assert_eq!(eval_weight(&weight, &mut nav, "c")?, (5, None));
+        assert_eq!(eval_weight(&weight, &mut nav, "~c")?, (1, None));
+        assert_eq!(weight.weight(&mut nav, "a")?, vec![("a".to_owned(), 0.5)]);
+        assert!(Weight::Declared("price".to_owned())
+            .weight(&mut nav, "a")
+            .is_err());
+
+        let filtered = filter(&Mode::StrictlyGoalOriented(weight.clone()), &mut nav, &fs)?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["c".to_owned(), "cost(c,1)".to_owned()].to_hashset()
+        );
+        let filtered = filter(&Mode::Explore(weight.clone()), &mut nav, &fs)?;
+        assert_eq!(
+            filtered.to_hashset(),
+            vec!["~c".to_owned(), "~cost(c,1)".to_owned()].to_hashset()
+        );
+
+        nav.activate(&["b".to_owned()], &Mode::GoalOriented(weight))?;
+        assert_eq!(nav.pace(), 0.5);
+
+        assert!(Navigator::new("a.\n%@fasb weight a/0", 0).is_err());
+
+        // declarations in included files count as well
+        let dir = std::env::temp_dir().join(format!("fasb-weights-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let (program, included) = (dir.join("program.lp"), dir.join("costs.lp"));
+        std::fs::write(&program, "#include \"costs.lp\".\na;b.")?;
+        std::fs::write(&included, "cost(a,3) :- a.\n%@fasb weight cost/2")?;
+        let nav = Navigator::load(&[program.to_string_lossy().into_owned()], vec![], None, 0)?;
+        assert_eq!(nav.declared_weights(), [("cost".to_owned(), 2)]);
+        std::fs::remove_dir_all(dir)?;
+
+        let mut nav = Navigator::new("a;b.", 0)?;
+        assert!(nav.declared_weights().is_empty());
+        nav.extend(
+            "cost(a,1) :- a.\n%@fasb weight cost/2",
+            &Mode::GoalOriented(Weight::Absolute),
+        )?;
+        assert_eq!(nav.declared_weights(), [("cost".to_owned(), 2)]);
+
+        Ok(())
+    }
 }
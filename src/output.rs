@@ -0,0 +1,179 @@
+//! Output of commands and queries.
+//!
+//! Text output is printed while a command runs. JSON output is collected instead: a command
+//! records typed fields by [`field`], which [`emit`] prints as a single JSON object per command,
+//! together with the command and its error, if any.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::navigator::NavigatorError;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static FIELDS: Mutex<Vec<(String, Value)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+impl std::str::FromStr for Format {
+    type Err = NavigatorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(NavigatorError::InvalidInput(format!(
+                "unknown format {:?}, expected one of `text`, `json`.",
+                s
+            ))),
+        }
+    }
+}
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+pub fn set_format(format: Format) {
+    JSON.store(format == Format::Json, Ordering::SeqCst);
+    fields().clear();
+}
+
+pub fn format() -> Format {
+    match JSON.load(Ordering::SeqCst) {
+        true => Format::Json,
+        _ => Format::Text,
+    }
+}
+
+/// Whether output is printed as text, see [`text`] and [`textln`].
+pub fn is_text() -> bool {
+    format() == Format::Text
+}
+
+fn fields() -> std::sync::MutexGuard<'static, Vec<(String, Value)>> {
+    FIELDS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Records `key` with `value` for the JSON object of the running command; no op for text output.
+pub fn field(key: &str, value: impl Serialize) {
+    if is_text() {
+        return;
+    }
+
+    let value = serde_json::to_value(value).unwrap_or(Value::Null);
+    record(&mut fields(), key, value);
+}
+
+/// Sets `key` to `value` among `fields`, replacing the value recorded before, if any.
+fn record(fields: &mut Vec<(String, Value)>, key: &str, value: Value) {
+    match fields.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value,
+        _ => fields.push((key.to_owned(), value)),
+    }
+}
+
+/// Records the time a command took in seconds.
+pub fn elapsed(elapsed: Duration) {
+    field("elapsed", elapsed.as_secs_f64())
+}
+
+/// JSON object of `command` with the fields recorded since the last call.
+pub fn object(command: &str, result: &Result<bool>, interrupted: bool) -> Value {
+    let recorded = fields().drain(..).collect();
+    assemble(command, recorded, result, interrupted)
+}
+
+fn assemble(
+    command: &str,
+    fields: Vec<(String, Value)>,
+    result: &Result<bool>,
+    interrupted: bool,
+) -> Value {
+    let mut object = Map::new();
+    object.insert("command".to_owned(), Value::from(command.trim()));
+    object.insert("ok".to_owned(), Value::from(result.is_ok()));
+    object.extend(fields);
+    if let Err(e) = result {
+        object.insert("error".to_owned(), Value::from(e.to_string()));
+    }
+    if interrupted {
+        object.insert("interrupted".to_owned(), Value::from(true));
+    }
+
+    Value::Object(object)
+}
+
+/// Prints the JSON object of `command`; no op for text output.
+pub fn emit(command: &str, result: &Result<bool>, interrupted: bool) {
+    if !is_text() {
+        println!("{}", object(command, result, interrupted));
+    }
+}
+
+/// [`print!`] for text output only.
+macro_rules! text {
+    ($($arg:tt)*) => {
+        if $crate::output::is_text() {
+            print!($($arg)*);
+        }
+    };
+}
+
+/// [`println!`] for text output only.
+macro_rules! textln {
+    ($($arg:tt)*) => {
+        if $crate::output::is_text() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use {text, textln};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_object() {
+        assert!("xml".parse::<Format>().is_err());
+
+        // fields are recorded locally, as the format and recorded fields are shared by all tests
+        let mut fields = vec![];
+        record(&mut fields, "facets", json!(["a", "~b"]));
+        record(&mut fields, "count", json!(2));
+        record(&mut fields, "count", json!(3));
+        record(&mut fields, "elapsed", json!(1.5));
+        let count = assemble("?c ", fields, &Ok(false), false);
+
+        assert_eq!(count["command"], "?c");
+        assert_eq!(count["ok"], true);
+        assert_eq!(count["facets"], json!(["a", "~b"]));
+        assert_eq!(count["count"], 3);
+        assert_eq!(count["elapsed"], 1.5);
+        assert!(count.get("error").is_none());
+
+        let failed = assemble(":a x", vec![], &Err(NavigatorError::Unsatisfiable), true);
+        assert_eq!(failed["ok"], false);
+        assert_eq!(failed["error"], "program is unsatisfiable.");
+        assert_eq!(failed["interrupted"], true);
+
+        // text output, the default, records nothing
+        field("count", 1);
+        assert!(object("?c", &Ok(false), false).get("count").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+//! Pool of worker threads, each owning a replica of a navigator, i.e., an independently grounded
+//! control, such that per-facet computations run concurrently.
+//!
+//! Replicas are built within their worker thread from a [`Recipe`] and take the route of the
+//! navigator along with every task, so that results coincide with the sequential ones.
+
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::interrupt;
+use crate::navigator::{Navigator, NavigatorError, Recipe, State};
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+type Task = Box<dyn FnOnce(&mut Navigator) + Send>;
+
+fn worker_failed() -> NavigatorError {
+    NavigatorError::Solver("worker failed.".to_owned())
+}
+
+#[derive(Debug)]
+pub(crate) struct Pool {
+    tasks: Option<Sender<Task>>,
+    workers: Vec<JoinHandle<()>>,
+}
+impl Pool {
+    /// Starts `workers` threads, each grounding its replica from `recipe`; fails if some replica
+    /// cannot be built.
+    pub(crate) fn new(recipe: &Recipe, workers: usize) -> Result<Self> {
+        let (tasks, queue) = channel::<Task>();
+        let queue = Arc::new(Mutex::new(queue));
+        let (ready, started) = channel();
+
+        let workers = (0..workers)
+            .map(|_| {
+                let (recipe, queue, ready) = (recipe.clone(), queue.clone(), ready.clone());
+                std::thread::spawn(move || {
+                    let mut replica = match recipe.build() {
+                        Ok(replica) => replica,
+                        Err(e) => {
+                            let _ = ready.send(Err(e));
+                            return;
+                        }
+                    };
+                    let _ = ready.send(Ok(()));
+
+                    // the queue is unlocked while the task runs; closing it stops the worker
+                    loop {
+                        let task = queue.lock().expect("task queue lock is poisoned.").recv();
+                        match task {
+                            Ok(task) => task(&mut replica),
+                            _ => break,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(ready);
+
+        let pool = Self {
+            tasks: Some(tasks),
+            workers,
+        };
+        for _ in 0..pool.workers.len() {
+            started.recv().map_err(|_| worker_failed())??;
+        }
+
+        Ok(pool)
+    }
+
+    /// Evaluates `f` on every facet under `state` and returns the results in the order of
+    /// `facets`; `on_result` is called whenever some result arrives.
+    pub(crate) fn map<T, F>(
+        &self,
+        state: State,
+        facets: Vec<String>,
+        f: F,
+        mut on_result: impl FnMut(),
+    ) -> Result<Vec<Result<T>>>
+    where
+        T: Send + 'static,
+        F: Fn(&mut Navigator, &str) -> Result<T> + Send + Sync + 'static,
+    {
+        let tasks = self.tasks.as_ref().ok_or_else(worker_failed)?;
+        let (state, f) = (Arc::new(state), Arc::new(f));
+        let (results, received) = channel();
+
+        let n = facets.len();
+        for (i, facet) in facets.into_iter().enumerate() {
+            let (state, f, results) = (state.clone(), f.clone(), results.clone());
+            tasks
+                .send(Box::new(move |replica: &mut Navigator| {
+                    // pending tasks are skipped after an interrupt
+                    let result = match interrupt::interrupted() {
+                        true => Err(NavigatorError::Interrupted),
+                        _ => replica.adopt(&state).and_then(|_| (*f)(replica, &facet)),
+                    };
+                    let _ = results.send((i, result));
+                }))
+                .map_err(|_| worker_failed())?;
+        }
+        drop(results);
+
+        let mut slots = (0..n).map(|_| None).collect::<Vec<Option<Result<T>>>>();
+        for _ in 0..n {
+            let (i, result) = received.recv().map_err(|_| worker_failed())?;
+            slots[i] = Some(result);
+            on_result();
+        }
+
+        Ok(slots.into_iter().flatten().collect())
+    }
+}
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.tasks.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
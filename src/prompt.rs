@@ -0,0 +1,157 @@
+//! Line editing at the prompt.
+//!
+//! Lines are kept in a history persisted per program, commands and queries are completed from
+//! the manual and their arguments from the current facets, including their `~`-prefixed
+//! counterparts.
+
+use std::path::PathBuf;
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::config;
+use crate::navigator::NavigatorError;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+fn readline_error(e: ReadlineError) -> NavigatorError {
+    match e {
+        ReadlineError::Io(e) => NavigatorError::IO(e),
+        e => NavigatorError::IO(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            e.to_string(),
+        )),
+    }
+}
+
+/// Completes the first word of a line by commands and queries, any other word by facets.
+struct Completion {
+    commands: Vec<&'static str>,
+    facets: Vec<String>,
+}
+impl Completion {
+    fn candidates(&self, word: &str, first: bool) -> Vec<String> {
+        match first {
+            true => self
+                .commands
+                .iter()
+                .filter(|c| c.starts_with(word))
+                .map(|c| c.to_string())
+                .collect(),
+            _ => self
+                .facets
+                .iter()
+                .flat_map(|f| [f.to_owned(), format!("~{}", f)])
+                .filter(|f| f.starts_with(word))
+                .collect(),
+        }
+    }
+}
+impl Completer for Completion {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+
+        Ok((start, self.candidates(&line[start..pos], start == 0)))
+    }
+}
+impl Hinter for Completion {
+    type Hint = String;
+}
+impl Highlighter for Completion {}
+impl Validator for Completion {}
+impl Helper for Completion {}
+
+pub struct Prompt {
+    editor: Editor<Completion, DefaultHistory>,
+    history: Option<PathBuf>,
+}
+impl Prompt {
+    /// Prompt with the history of the program identified by `program`, see
+    /// [`crate::navigator::Navigator::program_id`]; the history is kept in memory only if there
+    /// is no home directory.
+    pub fn new(program: u64) -> Result<Self> {
+        let mut editor = Editor::new().map_err(readline_error)?;
+        editor.set_helper(Some(Completion {
+            commands: config::commands().collect(),
+            facets: vec![],
+        }));
+
+        let history = std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join(".fasb")
+                .join("history")
+                .join(format!("{:016x}", program))
+        });
+        if let Some(path) = &history {
+            // no history yet
+            let _ = editor.load_history(path);
+        }
+
+        Ok(Self { editor, history })
+    }
+
+    /// Facets completed at the prompt.
+    pub fn set_facets(&mut self, facets: Vec<String>) {
+        if let Some(completion) = self.editor.helper_mut() {
+            completion.facets = facets;
+        }
+    }
+
+    /// Reads a line after printing `prompt`; end of input reads as `:q`, `Ctrl-C` as empty line.
+    pub fn read(&mut self, prompt: &str) -> Result<String> {
+        let line = match self.editor.readline(prompt) {
+            Ok(line) => line.trim().to_owned(),
+            Err(ReadlineError::Eof) => return Ok(":q".to_owned()),
+            Err(ReadlineError::Interrupted) => return Ok(String::new()),
+            Err(e) => return Err(readline_error(e)),
+        };
+
+        if !line.is_empty() {
+            self.editor
+                .add_history_entry(line.as_str())
+                .map_err(readline_error)?;
+            if let Some(path) = &self.history {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                self.editor.save_history(path).map_err(readline_error)?;
+            }
+        }
+
+        Ok(line)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn complete() {
+        let completion = Completion {
+            commands: vec![":a", ":aw", "?fs"],
+            facets: vec!["in(3,r,b)".to_owned(), "saw(3,b)".to_owned()],
+        };
+
+        assert_eq!(completion.candidates(":a", true), vec![":a", ":aw"]);
+        assert_eq!(completion.candidates("in", false), vec!["in(3,r,b)"]);
+        assert_eq!(completion.candidates("~s", false), vec!["~saw(3,b)"]);
+        assert_eq!(completion.candidates("", false).len(), 4);
+        assert!(completion.candidates("?c", true).is_empty());
+    }
+}
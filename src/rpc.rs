@@ -0,0 +1,342 @@
+//! JSON-RPC 2.0 over stdin and stdout, see `--rpc`.
+//!
+//! Requests are read one per line and answered one per line in order; requests without `id`
+//! are notifications and get no response. Parameters are passed by name, e.g.,
+//! `{"jsonrpc": "2.0", "id": 1, "method": "activate", "params": {"facets": ["~a"]}}`. While a
+//! request runs, the progress otherwise drawn as bar is sent as `progress` notification with
+//! `position` and `length`.
+
+use std::io::{stdin, stdout, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::commands::{answer_sets_json, parse_mode, route_is_maximal_safe, valued};
+use crate::interrupt;
+use crate::navigator::{self, NavigatorError};
+use crate::session::Session;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// errors of fasb itself, e.g., unknown atoms
+const SERVER_ERROR: i64 = -32000;
+
+/// how often progress is polled
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Guards stdout, such that no progress of a request is sent after its response.
+static OUT: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Error {
+    code: i64,
+    message: String,
+}
+impl From<NavigatorError> for Error {
+    fn from(e: NavigatorError) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: e.to_string(),
+        }
+    }
+}
+
+fn invalid_params(message: String) -> Error {
+    Error {
+        code: INVALID_PARAMS,
+        message,
+    }
+}
+
+/// Parameter `key`, if given.
+fn param<T: DeserializeOwned>(params: &Value, key: &str) -> std::result::Result<Option<T>, Error> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|e| invalid_params(format!("parameter {:?}: {}", key, e))),
+    }
+}
+
+fn required<T: DeserializeOwned>(params: &Value, key: &str) -> std::result::Result<T, Error> {
+    param(params, key)?.ok_or_else(|| invalid_params(format!("missing parameter {:?}.", key)))
+}
+
+fn call(session: &mut Session, method: &str, params: &Value) -> std::result::Result<Value, Error> {
+    Ok(match method {
+        "activate" => {
+            session.activate(&required::<Vec<String>>(params, "facets")?)?;
+            json!({ "route": session.route() })
+        }
+        "deactivate" => {
+            session.deactivate(&required::<Vec<String>>(params, "facets")?)?;
+            json!({ "route": session.route() })
+        }
+        "facets" => json!(session.facets()),
+        "weights" => {
+            let weights = match param::<String>(params, "facet")? {
+                Some(facet) => session.weight(&facet)?,
+                _ => session.weights()?,
+            };
+            json!(valued(&weights, "weight"))
+        }
+        "zoom" => {
+            let zooms = match param::<String>(params, "facet")? {
+                Some(facet) => session.zoom(&facet)?,
+                _ => session.zooms()?,
+            };
+            json!(valued(&zooms, "zoom"))
+        }
+        "filter" => match param::<String>(params, "mode")? {
+            Some(mode) => {
+                let mut flags = mode.split_whitespace();
+                let mode = parse_mode((flags.next(), flags.next()))
+                    .ok_or_else(|| invalid_params(format!("unknown mode {:?}.", mode)))?;
+                json!(session.filter_with(&mode)?)
+            }
+            _ => json!(session.filter()?),
+        },
+        "navigate_n" => {
+            let n = param::<usize>(params, "n")?.unwrap_or(session.navigator().n);
+            json!(answer_sets_json(&session.costed_answer_sets(n)?))
+        }
+        // w.r.t. the counting mode, unless `exact` is requested
+        "count" => match param::<bool>(params, "exact")? {
+            Some(true) => json!(session.count()?),
+            _ => json!(session.estimate()?),
+        },
+        "route_safe" => match param::<Vec<String>>(params, "route")? {
+            Some(route) => {
+                let navigator = session.navigator_mut();
+                let assumptions = navigator.literals_of(&route)?;
+                json!(navigator.satisfiable(&assumptions)?)
+            }
+            _ => json!(session.satisfiable(&[])?),
+        },
+        "route_maximal_safe" => match param::<Vec<String>>(params, "route")? {
+            Some(route) => {
+                let navigator = session.navigator_mut();
+                let assumptions = navigator.literals_of(&route)?;
+                json!(route_is_maximal_safe(navigator, &assumptions)?)
+            }
+            _ => json!(session.is_maximal_safe()?),
+        },
+        _ => {
+            return Err(Error {
+                code: METHOD_NOT_FOUND,
+                message: format!("unknown method {:?}.", method),
+            })
+        }
+    })
+}
+
+fn error(id: Value, e: Error) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": e.code, "message": e.message },
+    })
+}
+
+/// Runs the request on `line`; returns the response, unless the request is a notification.
+fn handle(session: &mut Session, line: &str) -> Option<Value> {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(e) => {
+            let e = Error {
+                code: PARSE_ERROR,
+                message: e.to_string(),
+            };
+            return Some(error(Value::Null, e));
+        }
+    };
+    let id = request.get("id").cloned();
+    let method = match request.get("method").and_then(Value::as_str) {
+        Some(method) => method,
+        _ => {
+            let e = Error {
+                code: INVALID_REQUEST,
+                message: "no method provided.".to_owned(),
+            };
+            return Some(error(id.unwrap_or(Value::Null), e));
+        }
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    interrupt::reset();
+    let result = call(session, method, &params);
+    let id = id?;
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error(id, e),
+    })
+}
+
+fn send(message: &Value) -> Result<()> {
+    let mut out = stdout().lock();
+    writeln!(out, "{}", message)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Sends the progress of running requests until `done`.
+fn report(done: &AtomicBool) {
+    let mut last = None;
+    while !done.load(Ordering::SeqCst) {
+        {
+            let _out = OUT.lock().unwrap_or_else(|e| e.into_inner());
+            let progress = navigator::progress();
+            if let Some((position, length)) = progress.filter(|_| progress != last) {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "progress",
+                    "params": { "position": position, "length": length },
+                });
+                if send(&notification).is_err() {
+                    return;
+                }
+            }
+            last = progress;
+        }
+        std::thread::sleep(PROGRESS_INTERVAL);
+    }
+}
+
+/// Answers requests read from stdin until end of input.
+pub(crate) fn serve(session: &mut Session) -> Result<()> {
+    navigator::report_progress(true);
+    let done = Arc::new(AtomicBool::new(false));
+    let reporter = done.clone();
+    std::thread::spawn(move || report(&reporter));
+
+    for line in stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle(session, &line);
+
+        let _out = OUT.lock().unwrap_or_else(|e| e.into_inner());
+        navigator::report_progress(true);
+        if let Some(response) = response {
+            send(&response)?;
+        }
+    }
+    done.store(true, Ordering::SeqCst);
+    navigator::report_progress(false);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::navigator::{Mode, Weight};
+
+    fn request(session: &mut Session, line: &str) -> Value {
+        handle(session, line).expect("no response.")
+    }
+
+    #[test]
+    fn handle_requests() -> Result<()> {
+        let mut session = Session::new("a;b. c;d :- b.", Mode::GoalOriented(Weight::Absolute), 0)?;
+
+        let facets = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":1,"method":"facets"}"#,
+        );
+        assert_eq!(facets["id"], 1);
+        let mut facets = serde_json::from_value::<Vec<String>>(facets["result"].clone())
+            .expect("facets expected.");
+        facets.sort();
+        assert_eq!(facets, vec!["a", "b", "c", "d"]);
+
+        let safe = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":2,"method":"route_safe","params":{"route":["a","c"]}}"#,
+        );
+        assert_eq!(safe["result"], false);
+
+        let activated = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":10,"method":"activate","params":{"facets":["a"]}}"#,
+        );
+        assert_eq!(activated["result"]["route"], json!(["a"]));
+        // routes other than the session's one are checked on their own
+        let maximal_safe = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":11,"method":"route_maximal_safe","params":{"route":["b"]}}"#,
+        );
+        assert_eq!(maximal_safe["result"], false);
+        let maximal_safe = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":12,"method":"route_maximal_safe","params":{"route":["b","c"]}}"#,
+        );
+        assert_eq!(maximal_safe["result"], true);
+        let maximal_safe = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":13,"method":"route_maximal_safe"}"#,
+        );
+        assert_eq!(maximal_safe["result"], true);
+        request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":14,"method":"deactivate","params":{"facets":["a"]}}"#,
+        );
+
+        let activated = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":"x","method":"activate","params":{"facets":["b"]}}"#,
+        );
+        assert_eq!(activated["id"], "x");
+        assert_eq!(activated["result"]["route"], json!(["b"]));
+
+        let count = request(&mut session, r#"{"jsonrpc":"2.0","id":3,"method":"count"}"#);
+        assert_eq!(count["result"]["count"], 2f64);
+        assert_eq!(count["result"]["confidence"], 1f64);
+        let count = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":3,"method":"count","params":{"exact":true}}"#,
+        );
+        assert_eq!(count["result"], 2);
+        let answer_sets = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":4,"method":"navigate_n","params":{"n":0}}"#,
+        );
+        assert_eq!(answer_sets["result"].as_array().map(|a| a.len()), Some(2));
+        let weights = request(
+            &mut session,
+            r#"{"jsonrpc":"2.0","id":5,"method":"weights","params":{"facet":"c"}}"#,
+        );
+        assert_eq!(weights["result"][0]["facet"], "c");
+
+        // notifications are not answered
+        assert!(handle(&mut session, r#"{"jsonrpc":"2.0","method":"count"}"#).is_none());
+
+        let errors = [
+            ("{", PARSE_ERROR),
+            (r#"{"id":6}"#, INVALID_REQUEST),
+            (r#"{"id":7,"method":"?c"}"#, METHOD_NOT_FOUND),
+            (r#"{"id":8,"method":"activate"}"#, INVALID_PARAMS),
+            (
+                r#"{"id":9,"method":"activate","params":{"facets":["x"]}}"#,
+                SERVER_ERROR,
+            ),
+        ];
+        for (line, code) in errors {
+            assert_eq!(request(&mut session, line)["error"]["code"], code);
+        }
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+
+        Ok(())
+    }
+}
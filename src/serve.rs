@@ -0,0 +1,313 @@
+//! Local HTTP server with a browser front end, see `fasb serve`.
+//!
+//! The page bundled at `/` navigates by a JSON API. Every browser tab opens a session of its
+//! own, which navigates on an independently grounded copy of the program:
+//!
+//! * `POST /api/sessions` opens a session and returns its state
+//! * `GET /api/sessions/{id}` returns the state, i.e., `route`, `pace`, `mode`, `facets` along
+//!   with their weights and up to ten `answer_sets`
+//! * `POST /api/sessions/{id}/activate` and `/deactivate` with body `{"facets": [...]}`, as well
+//!   as `/clear`, `/undo` and `/redo` change the route and return the new state
+//! * `POST /api/sessions/{id}/close` closes the session
+//!
+//! Errors are returned as `{"error": ...}`.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Response, Server};
+
+use crate::commands::answer_sets_json;
+use crate::interrupt;
+use crate::navigator::NavigatorError;
+use crate::output::textln;
+use crate::session::Session;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+pub const DEFAULT_PORT: u16 = 8080;
+
+const PAGE: &str = include_str!("serve.html");
+/// sessions kept open at most; beyond, the least recently used one is closed
+const MAX_SESSIONS: usize = 32;
+/// how often the server checks for `Ctrl-C` while waiting for requests
+const POLL: Duration = Duration::from_millis(200);
+/// answer sets shown at most
+const MAX_ANSWER_SETS: usize = 10;
+/// routes of a session whose facets and answer sets are kept at most
+const MAX_ROUTES: usize = 64;
+
+/// Open session along with what was computed on the routes it visited.
+struct Open {
+    /// time the session was used last
+    used: u64,
+    session: Session,
+    /// facets along with their weights and answer sets by route
+    computed: HashMap<Vec<String>, Value>,
+}
+
+struct Sessions<'a> {
+    /// session every other session is forked from
+    base: &'a Session,
+    /// open sessions by id
+    open: HashMap<String, Open>,
+    clock: u64,
+}
+impl<'a> Sessions<'a> {
+    fn new(base: &'a Session) -> Self {
+        Self {
+            base,
+            open: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn open(&mut self) -> Result<(String, &mut Open)> {
+        if self.open.len() >= MAX_SESSIONS {
+            let unused = self
+                .open
+                .iter()
+                .min_by_key(|(_, open)| open.used)
+                .map(|(id, _)| id.clone());
+            if let Some(id) = unused {
+                self.open.remove(&id);
+            }
+        }
+
+        let id = format!("{:016x}", rand::random::<u64>());
+        let session = self.base.fork()?;
+        self.clock += 1;
+        let open = self.open.entry(id.clone()).or_insert(Open {
+            used: self.clock,
+            session,
+            computed: HashMap::new(),
+        });
+
+        Ok((id, open))
+    }
+
+    fn get(&mut self, id: &str) -> Option<&mut Open> {
+        self.clock += 1;
+        self.open.get_mut(id).map(|open| {
+            open.used = self.clock;
+            open
+        })
+    }
+}
+
+/// Facets along with their weights and answer sets under the current route of `session`.
+fn compute(session: &mut Session) -> Result<Value> {
+    let weights = session.weights()?;
+    let weight = |facet: &str| {
+        weights
+            .iter()
+            .find(|(f, _)| f == facet)
+            .map(|(_, weight)| *weight)
+    };
+    let facets = session
+        .facets()
+        .into_iter()
+        .map(|facet| {
+            json!({
+                "facet": facet,
+                "weight": weight(&facet),
+                "exclusive_weight": weight(&format!("~{}", facet)),
+            })
+        })
+        .collect::<Vec<_>>();
+    let n = session.navigator().n.clamp(1, MAX_ANSWER_SETS);
+    let answer_sets = session.costed_answer_sets(n)?;
+
+    Ok(json!({
+        "facets": facets,
+        "answer_sets": answer_sets_json(&answer_sets),
+    }))
+}
+
+/// State of session `id` as shown by the page; facets and answer sets are computed once per
+/// route.
+fn state(id: &str, open: &mut Open) -> Result<Value> {
+    let route = open.session.route().0.clone();
+    if !open.computed.contains_key(&route) {
+        if open.computed.len() >= MAX_ROUTES {
+            open.computed.clear();
+        }
+        let computed = compute(&mut open.session)?;
+        open.computed.insert(route.clone(), computed);
+    }
+    let computed = &open.computed[&route];
+    let session = &open.session;
+
+    Ok(json!({
+        "session": id,
+        "route": session.route(),
+        "pace": session.pace(),
+        "mode": session.mode().to_string(),
+        "facets": computed["facets"],
+        "answer_sets": computed["answer_sets"],
+    }))
+}
+
+/// Facets of the request body `{"facets": [...]}`.
+fn facets(body: &str) -> Result<Vec<String>> {
+    serde_json::from_str::<Value>(body)
+        .and_then(|body| serde_json::from_value(body["facets"].clone()))
+        .map_err(|e| NavigatorError::InvalidInput(format!("invalid facets: {}", e)))
+}
+
+fn change(session: &mut Session, action: &str, body: &str) -> Result<()> {
+    match action {
+        "activate" => session.activate(&facets(body)?),
+        "deactivate" => session.deactivate(&facets(body)?),
+        "clear" => session.clear_route(),
+        "undo" => session.undo().map(|_| ()),
+        "redo" => session.redo().map(|_| ()),
+        _ => Err(NavigatorError::InvalidInput(format!(
+            "unknown action {:?}.",
+            action
+        ))),
+    }
+}
+
+fn not_found(message: String) -> (u16, Value) {
+    (404, json!({ "error": message }))
+}
+
+/// Answers API request `method` on `path` with `body` by status code and JSON body.
+fn respond(sessions: &mut Sessions, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let path = path.split('?').next().unwrap_or_default();
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+    let result = match (method, segments.as_slice()) {
+        ("POST", ["api", "sessions"]) => sessions.open().and_then(|(id, open)| state(&id, open)),
+        ("POST", ["api", "sessions", id, "close"]) => match sessions.open.remove(*id) {
+            Some(_) => Ok(json!({})),
+            _ => return not_found(format!("unknown session {:?}.", id)),
+        },
+        ("GET", ["api", "sessions", id])
+        | (
+            "POST",
+            ["api", "sessions", id, "activate" | "deactivate" | "clear" | "undo" | "redo"],
+        ) => {
+            let open = match sessions.get(id) {
+                Some(open) => open,
+                _ => return not_found(format!("unknown session {:?}.", id)),
+            };
+            match segments.get(3) {
+                Some(action) => {
+                    change(&mut open.session, action, body).and_then(|_| state(id, open))
+                }
+                _ => state(id, open),
+            }
+        }
+        _ => return not_found(format!("unknown path {:?}.", path)),
+    };
+
+    match result {
+        Ok(value) => (200, value),
+        Err(e) => (400, json!({ "error": e.to_string() })),
+    }
+}
+
+fn header(content_type: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).expect("invalid header.")
+}
+
+/// Serves the page and the API on `port` of localhost until `Ctrl-C`; sessions are forked from
+/// `session`.
+pub(crate) fn run(session: &Session, port: u16) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| NavigatorError::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    textln!("\nserving on http://127.0.0.1:{}, stop by Ctrl-C\n", port);
+
+    let mut sessions = Sessions::new(session);
+    interrupt::reset();
+    while !interrupt::interrupted() {
+        let mut request = match server.recv_timeout(POLL)? {
+            Some(request) => request,
+            _ => continue,
+        };
+
+        let (method, url) = (request.method().to_string(), request.url().to_owned());
+        let response = match (method.as_str(), url.as_str()) {
+            ("GET", "/" | "/index.html") => {
+                Response::from_string(PAGE).with_header(header("text/html; charset=utf-8"))
+            }
+            _ => {
+                let mut body = String::new();
+                let (status, value) = match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => respond(&mut sessions, &method, &url, &body),
+                    Err(e) => (400, json!({ "error": e.to_string() })),
+                };
+                Response::from_string(value.to_string())
+                    .with_status_code(status)
+                    .with_header(header("application/json"))
+            }
+        };
+        // the client may have gone meanwhile
+        let _ = request.respond(response);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::navigator::{Mode, Weight};
+
+    #[test]
+    fn respond_t() -> Result<()> {
+        let base = Session::new("a;b. c;d :- b.", Mode::GoalOriented(Weight::Absolute), 0)?;
+        let mut sessions = Sessions::new(&base);
+
+        let (status, opened) = respond(&mut sessions, "POST", "/api/sessions", "");
+        assert_eq!(status, 200);
+        assert_eq!(opened["facets"].as_array().map(|f| f.len()), Some(4));
+        assert_eq!(opened["answer_sets"].as_array().map(|a| a.len()), Some(1));
+        let id = opened["session"]
+            .as_str()
+            .expect("no session id.")
+            .to_owned();
+        let (_, other) = respond(&mut sessions, "POST", "/api/sessions/", "");
+        assert_ne!(other["session"], opened["session"]);
+
+        let path = format!("/api/sessions/{}/activate", id);
+        let (status, activated) = respond(&mut sessions, "POST", &path, r#"{"facets":["b"]}"#);
+        assert_eq!(status, 200);
+        assert_eq!(activated["route"], json!(["b"]));
+        let weight = activated["facets"][0]["weight"].as_f64();
+        assert_eq!(weight, Some(1f64 / 3f64));
+
+        // sessions navigate independently
+        let path = format!(
+            "/api/sessions/{}",
+            other["session"].as_str().unwrap_or_default()
+        );
+        let (_, state) = respond(&mut sessions, "GET", &path, "");
+        assert_eq!(state["route"], json!([]));
+        assert!(base.route().0.is_empty());
+
+        let path = format!("/api/sessions/{}/undo", id);
+        let (_, undone) = respond(&mut sessions, "POST", &path, "");
+        assert_eq!(undone["route"], json!([]));
+        assert_eq!(undone["facets"], opened["facets"]);
+        // facets and answer sets of visited routes are kept
+        let computed = &sessions.open[&id].computed;
+        assert_eq!(computed.len(), 2);
+        assert!(computed.contains_key(&vec!["b".to_owned()]));
+
+        let path = format!("/api/sessions/{}/activate", id);
+        assert_eq!(respond(&mut sessions, "POST", &path, "[]").0, 400);
+        assert_eq!(respond(&mut sessions, "GET", "/api/sessions/0", "").0, 404);
+        assert_eq!(respond(&mut sessions, "GET", "/api/facets", "").0, 404);
+
+        let path = format!("/api/sessions/{}/close", id);
+        assert_eq!(respond(&mut sessions, "POST", &path, "").0, 200);
+        assert_eq!(respond(&mut sessions, "POST", &path, "").0, 404);
+
+        Ok(())
+    }
+}
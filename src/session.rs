@@ -0,0 +1,816 @@
+//! Embeddable navigation session.
+//!
+//! A [`Session`] couples a [`Navigator`] with its navigation [`Mode`] and answers queries with
+//! data rather than printing to stdout, e.g.,
+//!
+//! ```no_run
+//! use fasb::{Mode, Session, Weight};
+//!
+//! let mut session = Session::new("a;b. c;d :- b.", Mode::GoalOriented(Weight::FacetCounting), 0)?;
+//! session.activate(&["b".to_owned()])?;
+//! for (facet, weight) in session.weights()? {
+//!     println!("{:.2} {}", weight, facet);
+//! }
+//! # Ok::<(), fasb::NavigatorError>(())
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use clingo::Symbol;
+use serde::{Deserialize, Serialize};
+
+use crate::asnc::AsnC;
+use crate::cache::{self, CacheCapacity, CacheStats};
+use crate::history::History;
+use crate::navigator::{
+    Counting, Estimate, GoalOrientedNavigation, Mode, Navigator, NavigatorError, Optimization,
+    Projection, Recipe,
+};
+use crate::soe::{stringify, Sampler};
+use crate::translator::Atom;
+use crate::utils::{Repr, Route, ToSymbol};
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+/// Connected component of the current facets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Component {
+    pub facets: Vec<String>,
+    pub cover: Vec<String>,
+    pub content: Vec<String>,
+}
+
+/// Session state written by [`Session::save`].
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    recipe: Recipe,
+    /// fingerprint of the program and options, see [`Navigator::fingerprint`]
+    fingerprint: u64,
+    mode: Mode,
+    n: usize,
+    route: Vec<String>,
+}
+
+/// Outcome of [`Session::open`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opened {
+    /// whether the program differs from the one the session was saved with
+    pub program_changed: bool,
+    /// facets of the saved route that no longer parse or are unknown to the program
+    pub dropped: Vec<String>,
+}
+
+/// Navigation session; sessions share no solver state, use [`Session::fork`] to copy one.
+#[derive(Debug)]
+pub struct Session {
+    navigator: Navigator,
+    mode: Mode,
+    cache_dir: Option<PathBuf>,
+    history: History,
+}
+impl Session {
+    pub fn new(source: impl Into<String>, mode: Mode, n: usize) -> Result<Self> {
+        Self::from_navigator(Navigator::new(source, n)?, mode)
+    }
+
+    /// Reads the program from `sources` (`-` for stdin) and passes `arguments` to clingo, see
+    /// [`Navigator::load`].
+    pub fn load(
+        sources: &[String],
+        arguments: Vec<String>,
+        projection: Option<Projection>,
+        mode: Mode,
+        n: usize,
+    ) -> Result<Self> {
+        Self::from_navigator(Navigator::load(sources, arguments, projection, n)?, mode)
+    }
+
+    pub fn from_navigator(mut navigator: Navigator, mode: Mode) -> Result<Self> {
+        navigator.update(&mode)?;
+
+        Ok(Self {
+            navigator,
+            mode,
+            cache_dir: None,
+            history: History::new(),
+        })
+    }
+
+    /// Writes the program, options, mode and route to `path` as json; program files are
+    /// referred to by path rather than content.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let saved = Saved {
+            recipe: self.navigator.recipe().with_absolute_paths()?,
+            fingerprint: self.navigator.fingerprint(),
+            mode: self.mode.clone(),
+            n: self.navigator.n,
+            route: self.navigator.route().0.clone(),
+        };
+
+        let json =
+            serde_json::to_string_pretty(&saved).map_err(|e| NavigatorError::IO(e.into()))?;
+        std::fs::write(path, json)?;
+
+        Ok(())
+    }
+
+    /// Restores a session written by [`Session::save`], rereading its program files; facets of
+    /// the route that are no longer known are dropped from the route.
+    pub fn open(path: impl AsRef<Path>) -> Result<(Self, Opened)> {
+        let saved: Saved = serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| NavigatorError::IO(e.into()))?;
+
+        let navigator = saved.recipe.startup(saved.n, false)?;
+        let program_changed = navigator.fingerprint() != saved.fingerprint;
+        let mut session = Self::from_navigator(navigator, saved.mode)?;
+
+        let (route, dropped): (Vec<String>, Vec<String>) = saved
+            .route
+            .into_iter()
+            .partition(|f| session.navigator.literal(f).is_ok());
+        if !route.is_empty() {
+            session.activate(&route)?;
+        }
+
+        Ok((
+            session,
+            Opened {
+                program_changed,
+                dropped,
+            },
+        ))
+    }
+
+    pub fn navigator(&self) -> &Navigator {
+        &self.navigator
+    }
+
+    pub fn navigator_mut(&mut self) -> &mut Navigator {
+        &mut self.navigator
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) -> Result<()> {
+        self.navigator.update(&mode)?;
+        self.mode = mode;
+
+        Ok(())
+    }
+
+    pub fn route(&self) -> &Route {
+        self.navigator.route()
+    }
+
+    /// See [`Navigator::pace`].
+    pub fn pace(&self) -> f32 {
+        self.navigator.pace()
+    }
+
+    /// Current facets, i.e., atoms contained in some but not all answer sets under the route.
+    pub fn facets(&self) -> Vec<String> {
+        self.navigator.current_facets.to_strings().collect()
+    }
+
+    pub fn initial_facets(&self) -> Vec<String> {
+        self.navigator.initial_facets.to_strings().collect()
+    }
+
+    pub fn activate(&mut self, facets: &[String]) -> Result<()> {
+        self.navigator.activate(facets, &self.mode)?;
+        self.record(format!("+{}", facets.join(" ")));
+
+        Ok(())
+    }
+
+    /// Drops every occurrence of `facets` from the route.
+    pub fn deactivate(&mut self, facets: &[String]) -> Result<()> {
+        let symbols = facets.iter().map(|f| f.symbol()).collect::<Vec<Symbol>>();
+
+        self.navigator.deactivate_any(&symbols, &self.mode)?;
+        self.record(format!("-{}", facets.join(" ")));
+
+        Ok(())
+    }
+
+    pub fn clear_route(&mut self) -> Result<()> {
+        self.navigator.clear_route(&self.mode)?;
+        self.record("< >");
+
+        Ok(())
+    }
+
+    /// Adds `program` and returns the facets dropped from the route, see [`Navigator::extend`].
+    pub fn extend(&mut self, program: &str) -> Result<Vec<String>> {
+        let dropped = self.navigator.extend(program, &self.mode)?;
+        self.record_dropped(&dropped);
+
+        Ok(dropped)
+    }
+
+    /// Adds the program at `path`, see [`Session::extend`].
+    pub fn load_file(&mut self, path: &str) -> Result<Vec<String>> {
+        let program = std::fs::read_to_string(path)?;
+
+        self.extend(&program)
+    }
+
+    /// Independent session on a newly grounded copy of the program, see [`Navigator::fork`]; its
+    /// history starts empty.
+    pub fn fork(&self) -> Result<Self> {
+        Ok(Self {
+            navigator: self.navigator.fork(&self.mode)?,
+            mode: self.mode.clone(),
+            cache_dir: None,
+            history: History::new(),
+        })
+    }
+
+    /// Restarts on the edited `program`, see [`Navigator::reload`].
+    pub fn reload(&mut self, program: &str) -> Result<Vec<String>> {
+        let dropped = self.navigator.reload(program, &self.mode)?;
+        self.record_dropped(&dropped);
+
+        Ok(dropped)
+    }
+
+    /// Grounds program part `part` and returns the facets dropped from the route.
+    pub fn ground(&mut self, part: &str) -> Result<Vec<String>> {
+        let dropped = self.navigator.ground(part, &self.mode)?;
+        self.record_dropped(&dropped);
+
+        Ok(dropped)
+    }
+
+    /// Restricts navigation to optimal or cost-bounded answer sets, see [`Navigator::optimize`].
+    pub fn optimize(&mut self, optimization: Optimization) -> Result<Vec<String>> {
+        let dropped = self.navigator.optimize(optimization, &self.mode)?;
+        self.record_dropped(&dropped);
+
+        Ok(dropped)
+    }
+
+    fn record(&mut self, change: impl Into<String>) {
+        self.history.record(self.navigator.route(), change)
+    }
+
+    fn record_dropped(&mut self, dropped: &[String]) {
+        if !dropped.is_empty() {
+            self.record(format!("-{} (dropped)", dropped.join(" ")));
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Returns to the route before the last change; `false` if there is none.
+    pub fn undo(&mut self) -> Result<bool> {
+        match self.history.undo_target() {
+            Some(node) => self.restore(node).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns to the route undone last; `false` if there is none.
+    pub fn redo(&mut self) -> Result<bool> {
+        match self.history.redo_target() {
+            Some(node) => self.restore(node).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Bookmarks the current route by `name`.
+    pub fn bookmark(&mut self, name: &str) {
+        self.history.bookmark(name)
+    }
+
+    /// Returns to the route bookmarked by `name`.
+    pub fn goto(&mut self, name: &str) -> Result<()> {
+        let node = self
+            .history
+            .bookmarked(name)
+            .ok_or_else(|| NavigatorError::InvalidInput(format!("unknown bookmark {:?}.", name)))?;
+
+        self.restore(node)
+    }
+
+    /// Restores the route of history node `node` along with its facets and pace; the history is
+    /// left unchanged if that fails, e.g., as the program dropped some facet of the route.
+    fn restore(&mut self, node: usize) -> Result<()> {
+        let route = self
+            .history
+            .route(node)
+            .cloned()
+            .ok_or(NavigatorError::None)?;
+
+        self.navigator.set_route(route, &self.mode)?;
+        self.history.visit(node);
+
+        Ok(())
+    }
+
+    /// Cost bound navigation is restricted to, if any.
+    pub fn optimum(&self) -> Option<&[i64]> {
+        self.navigator.optimum.as_deref()
+    }
+
+    /// Whether some answer set contains the current route extended by `facets`.
+    pub fn satisfiable(&mut self, facets: &[String]) -> Result<bool> {
+        let route = self
+            .navigator
+            .route()
+            .peek_steps(facets.iter().map(|s| s.as_str()));
+        let assumptions = self.navigator.literals_of(&route.0)?;
+
+        self.navigator.satisfiable(&assumptions)
+    }
+
+    /// Whether the current route is satisfiable and cannot be extended by any facet.
+    pub fn is_maximal_safe(&mut self) -> Result<bool> {
+        self.navigator.current_route_is_maximal_safe()
+    }
+
+    /// See [`Navigator::explain`].
+    pub fn explain(&mut self, facet: &str) -> Result<Option<(String, Route)>> {
+        self.navigator.explain(facet)
+    }
+
+    /// Number of answer sets under the current route.
+    pub fn count(&mut self) -> Result<usize> {
+        let assumptions = self.navigator.active_facets.clone();
+
+        self.navigator.count(&assumptions)
+    }
+
+    /// Number of answer sets under the current route w.r.t. the counting mode.
+    pub fn estimate(&mut self) -> Result<Estimate> {
+        let assumptions = self.navigator.active_facets.clone();
+
+        self.navigator.estimate(&assumptions)
+    }
+
+    pub fn counting(&self) -> &Counting {
+        &self.navigator.counting
+    }
+
+    /// See [`Navigator::set_counting`].
+    pub fn set_counting(&mut self, counting: Counting) -> Result<()> {
+        self.navigator.set_counting(counting, &self.mode)
+    }
+
+    /// Persists the cache into `dir` from now on and restores the entries persisted there for
+    /// the current program and options; returns the number of restored entries.
+    pub fn set_cache_dir(&mut self, dir: impl Into<PathBuf>) -> Result<usize> {
+        self.cache_dir = Some(dir.into());
+
+        match self.cache_file() {
+            Some(path) if path.exists() => self.navigator.cache.load(&path),
+            _ => Ok(0),
+        }
+    }
+
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// File the cache of the current program and options is persisted to, if any.
+    pub fn cache_file(&self) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| cache::file(dir, self.navigator.fingerprint()))
+    }
+
+    /// Writes the cache to [`Session::cache_file`]; returns the file written, if any.
+    pub fn save_cache(&self) -> Result<Option<PathBuf>> {
+        match self.cache_file() {
+            Some(path) => {
+                self.navigator.cache.save(&path)?;
+                Ok(Some(path))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.navigator.cache.stats()
+    }
+
+    pub fn workers(&self) -> usize {
+        self.navigator.workers()
+    }
+
+    /// See [`Navigator::set_workers`].
+    pub fn set_workers(&mut self, workers: usize) -> Result<()> {
+        self.navigator.set_workers(workers)
+    }
+
+    pub fn cache_capacity(&self) -> CacheCapacity {
+        self.navigator.cache_capacity()
+    }
+
+    /// See [`Navigator::set_cache_capacity`].
+    pub fn set_cache_capacity(&mut self, capacity: CacheCapacity) {
+        self.navigator.set_cache_capacity(capacity)
+    }
+
+    /// Drops all cached entries, in memory as well as the persisted ones.
+    pub fn clear_cache(&mut self) -> Result<()> {
+        self.navigator.cache.clear();
+        match self.cache_file() {
+            Some(path) if path.exists() => Ok(std::fs::remove_file(path)?),
+            _ => Ok(()),
+        }
+    }
+
+    /// Up to `n` answer sets under the current route; `n == 0` enumerates all of them.
+    pub fn answer_sets(&mut self, n: usize) -> Result<Vec<Vec<String>>> {
+        Ok(self
+            .navigator
+            .answer_sets(n)?
+            .iter()
+            .map(|answer_set| answer_set.iter().map(|s| s.repr()).collect())
+            .collect())
+    }
+
+    /// Like [`Session::answer_sets`], paired with the cost vector of each answer set.
+    pub fn costed_answer_sets(&mut self, n: usize) -> Result<Vec<(Vec<String>, Vec<i64>)>> {
+        Ok(self
+            .navigator
+            .costed_answer_sets(n)?
+            .into_iter()
+            .map(|(answer_set, cost)| (answer_set.iter().map(|s| s.repr()).collect(), cost))
+            .collect())
+    }
+
+    /// Passes each of up to `n` answer sets under the current route, along with its cost vector,
+    /// to `on_answer_set` as soon as it is found, see [`Navigator::for_each_answer_set`].
+    pub fn for_each_answer_set(
+        &mut self,
+        n: usize,
+        mut on_answer_set: impl FnMut(&[String], &[i64]),
+    ) -> Result<usize> {
+        self.navigator.for_each_answer_set(n, |answer_set, cost| {
+            on_answer_set(
+                &answer_set.iter().map(|s| s.repr()).collect::<Vec<_>>(),
+                cost,
+            )
+        })
+    }
+
+    /// Weight of `facet` (and its inverse, depending on the mode's weight).
+    pub fn weight(&mut self, facet: &str) -> Result<Vec<(String, f64)>> {
+        self.mode.w(&mut self.navigator, facet)
+    }
+
+    pub fn weights(&mut self) -> Result<Vec<(String, f64)>> {
+        self.mode.a_w(&mut self.navigator)
+    }
+
+    /// Zoom of `facet` (and its inverse, depending on the mode's weight) as a fraction.
+    pub fn zoom(&mut self, facet: &str) -> Result<Vec<(String, f64)>> {
+        self.mode.z(&mut self.navigator, facet)
+    }
+
+    pub fn zooms(&mut self) -> Result<Vec<(String, f64)>> {
+        self.mode.a_z(&mut self.navigator)
+    }
+
+    /// Facets the session's mode suggests to activate next.
+    pub fn filter(&mut self) -> Result<Vec<String>> {
+        let mode = self.mode.clone();
+
+        self.filter_with(&mode)
+    }
+
+    /// Facets `mode` suggests to activate next; goal-oriented mode suggests every facet.
+    pub fn filter_with(&mut self, mode: &Mode) -> Result<Vec<String>> {
+        let current_facets = self.navigator.current_facets.clone();
+
+        match mode {
+            Mode::GoalOriented(_) => Ok(current_facets
+                .iter()
+                .flat_map(|f| [f.repr(), f.exclusive_repr()])
+                .collect()),
+            _ => mode.filter(&mut self.navigator, current_facets.as_ref()),
+        }
+    }
+
+    /// Connected components of the current facets.
+    pub fn components(&mut self) -> Result<Vec<Component>> {
+        Ok(self
+            .navigator
+            .components()?
+            .0
+            .into_iter()
+            .map(|(cover, (facets, content))| Component {
+                facets: facets.into_iter().collect(),
+                cover: stringify(&cover),
+                content: stringify(&content.into_iter().collect::<Vec<_>>()),
+            })
+            .collect())
+    }
+
+    /// Greedy sample of up to `size` (all if `None`) answer sets under the current route that
+    /// differ in atoms other than `ignored`.
+    pub fn sample(&mut self, size: Option<usize>, ignored: &[String]) -> Result<Vec<Vec<String>>> {
+        let ignored = ignored
+            .iter()
+            .map(|s| {
+                Atom(s)
+                    .parse(&[])
+                    .ok_or_else(|| NavigatorError::Parse(s.to_owned()))
+            })
+            .collect::<Result<HashSet<Symbol>>>()?;
+
+        Ok(self
+            .navigator
+            .k_greedy_search(ignored.into_iter(), size)?
+            .iter()
+            .map(|answer_set| stringify(answer_set))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::navigator::Weight;
+    use crate::utils::ToHashSet;
+
+    const PI_1: &str = "a;b. c;d :- b. e.";
+
+    #[test]
+    fn navigate() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::Absolute), 0)?;
+        assert_eq!(session.facets().len(), 4);
+        assert_eq!(session.count()?, 3);
+        assert_eq!(session.answer_sets(0)?.len(), 3);
+        assert_eq!(session.answer_sets(2)?.len(), 2);
+
+        session.activate(&["b".to_owned()])?;
+        let mut answer_sets = vec![];
+        let count = session.for_each_answer_set(1, |answer_set, _| {
+            answer_sets.push(answer_set.to_vec());
+        })?;
+        assert_eq!(count, 1);
+        assert!(answer_sets[0].contains(&"b".to_owned()));
+        assert_eq!(session.count()?, 2);
+        assert_eq!(
+            session.facets().to_hashset(),
+            vec!["c".to_owned(), "d".to_owned()].to_hashset()
+        );
+        assert!(session.satisfiable(&["c".to_owned()])?);
+        assert!(!session.satisfiable(&["a".to_owned()])?);
+        assert!(!session.is_maximal_safe()?);
+
+        session.deactivate(&["b".to_owned()])?;
+        assert!(session.route().0.is_empty());
+        assert_eq!(session.count()?, 3);
+
+        assert!(matches!(
+            session.activate(&["f".to_owned()]),
+            Err(NavigatorError::UnknownAtom(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn history() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::FacetCounting), 0)?;
+        assert!(!session.undo()?);
+
+        session.activate(&["b".to_owned()])?;
+        session.bookmark("b");
+        session.activate(&["c".to_owned()])?;
+        assert!(session.facets().is_empty());
+        assert_eq!(session.pace(), 1f32);
+
+        assert!(session.undo()?);
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+        assert_eq!(
+            session.facets().to_hashset(),
+            vec!["c".to_owned(), "d".to_owned()].to_hashset()
+        );
+        assert!(session.redo()?);
+        assert_eq!(session.route().0, vec!["b".to_owned(), "c".to_owned()]);
+        assert!(!session.redo()?);
+
+        session.clear_route()?;
+        session.activate(&["a".to_owned()])?;
+        session.goto("b")?;
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+        assert_eq!(session.pace(), 0.5);
+        assert!(session.goto("c").is_err());
+
+        // root, b, b c, cleared route, a
+        assert_eq!(session.history().to_string().lines().count(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn weights() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::Absolute), 0)?;
+
+        let weights = session.weight("a")?;
+        assert_eq!(weights.len(), 2);
+        assert_eq!(weights[0].0, "a");
+        assert!((weights[0].1 - 2f64 / 3f64).abs() < 1e-6);
+        assert_eq!(weights[1].0, "~a");
+        assert!((weights[1].1 - 1f64 / 3f64).abs() < 1e-6);
+        assert_eq!(session.weights()?.len(), 8);
+        assert_eq!(session.zooms()?.len(), 8);
+
+        session.set_mode(Mode::GoalOriented(Weight::FacetCounting))?;
+        assert_eq!(session.weights()?.len(), 8);
+        assert_eq!(session.filter()?.len(), 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sample() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::Absolute), 0)?;
+
+        let sample = session.sample(None, &[])?;
+        assert!(!sample.is_empty());
+        assert!(sample
+            .iter()
+            .all(|answer_set| answer_set.contains(&"e".to_owned())));
+        assert_eq!(session.sample(Some(1), &[])?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn approximate_count() -> Result<()> {
+        let mut session = Session::new(
+            "{a(1..10)}.",
+            Mode::StrictlyGoalOriented(Weight::Absolute),
+            0,
+        )?;
+        assert!(session.estimate()?.is_exact());
+
+        session.set_counting("approx,0.8,0.5".parse()?)?;
+        let estimate = session.estimate()?;
+        assert!(!estimate.is_exact());
+        assert!(estimate.lower <= estimate.count && estimate.count <= estimate.upper);
+        assert!(estimate.count > 64f64 && estimate.count < 16384f64);
+
+        // small spaces are counted exactly
+        let facets = (1..=5).map(|i| format!("a({})", i)).collect::<Vec<_>>();
+        session.activate(&facets)?;
+        let estimate = session.estimate()?;
+        assert!(estimate.is_exact());
+        assert_eq!(estimate.count, 32f64);
+        assert_eq!(session.count()?, 32);
+        assert!(!session.facets().iter().any(|f| f.starts_with("__fasb")));
+
+        assert!("approx,0,0.5".parse::<Counting>().is_err());
+        assert!("approx,1".parse::<Counting>().is_err());
+        assert_eq!("exact".parse::<Counting>()?, Counting::Exact);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cache_dir() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("fasb-session-{}", std::process::id()));
+        let mut session = Session::new("x;y. z.", Mode::GoalOriented(Weight::Absolute), 0)?;
+        assert!(session.save_cache()?.is_none());
+
+        assert_eq!(session.set_cache_dir(&dir)?, 0);
+        let path = session.save_cache()?.expect("no cache file.");
+        assert!(path.starts_with(&dir) && path.exists());
+        assert_eq!(session.cache_file(), Some(path.clone()));
+
+        // the file depends on the options as well
+        session.set_counting("approx".parse()?)?;
+        assert_ne!(session.cache_file(), Some(path.clone()));
+        session.set_counting(Counting::Exact)?;
+
+        session.clear_cache()?;
+        assert!(!path.exists());
+        std::fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn cache_dir_include() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("fasb-include-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let (program, included) = (dir.join("program.lp"), dir.join("included.lp"));
+        std::fs::write(&program, "#include \"included.lp\".\nc :- a.")?;
+        std::fs::write(&included, "a;b.")?;
+        let load = || {
+            Session::load(
+                &[program.to_string_lossy().into_owned()],
+                vec![],
+                None,
+                Mode::GoalOriented(Weight::Absolute),
+                0,
+            )
+        };
+
+        let mut session = load()?;
+        session.set_cache_dir(&dir)?;
+        session.save_cache()?;
+        assert!(load()?.set_cache_dir(&dir)? > 0);
+
+        // editing the included file misses the cache
+        std::fs::write(&included, "a;b;d.")?;
+        let mut edited = load()?;
+        assert_ne!(edited.cache_file(), session.cache_file());
+        assert_eq!(edited.set_cache_dir(&dir)?, 0);
+
+        std::fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_open() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("fasb-saved-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let (program, saved) = (dir.join("program.lp"), dir.join("session.json"));
+        std::fs::write(&program, PI_1)?;
+
+        let mode = Mode::StrictlyGoalOriented(Weight::Absolute);
+        let mut session = Session::load(
+            &[program.to_string_lossy().into_owned()],
+            vec![],
+            None,
+            mode,
+            5,
+        )?;
+        session.extend("f :- c.")?;
+        session.activate(&["b".to_owned(), "c".to_owned()])?;
+        session.save(&saved)?;
+
+        let (opened, report) = Session::open(&saved)?;
+        assert!(!report.program_changed && report.dropped.is_empty());
+        assert_eq!(opened.route().0, session.route().0);
+        assert_eq!(opened.facets(), session.facets());
+        assert_eq!(opened.navigator().n, 5);
+        assert_eq!(opened.mode().to_string(), session.mode().to_string());
+        assert_eq!(
+            opened.navigator().fingerprint(),
+            session.navigator().fingerprint()
+        );
+
+        // facets unknown to the changed program are reported
+        std::fs::write(&program, "a;b. d :- b. e.")?;
+        let (opened, report) = Session::open(&saved)?;
+        assert!(report.program_changed);
+        assert_eq!(report.dropped, vec!["c".to_owned()]);
+        assert_eq!(opened.route().0, vec!["b".to_owned()]);
+
+        std::fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn reload() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::FacetCounting), 0)?;
+        session.extend("f :- c.")?;
+        session.activate(&["b".to_owned(), "c".to_owned()])?;
+
+        // the route is replayed and the added program applied again
+        let dropped = session.reload("a;b. d :- b. e.")?;
+        assert_eq!(dropped, vec!["c".to_owned()]);
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+        assert!(session.facets().is_empty());
+        assert_eq!(session.navigator().loaded_program()?, "a;b. d :- b. e.");
+        assert!(session.navigator().logic_program.contains("f :- c."));
+
+        assert!(session.reload("a;b").is_err());
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fork() -> Result<()> {
+        let mut session = Session::new(PI_1, Mode::GoalOriented(Weight::FacetCounting), 0)?;
+        session.activate(&["b".to_owned()])?;
+
+        let mut forked = session.fork()?;
+        assert_eq!(forked.route().0, vec!["b".to_owned()]);
+        assert_eq!(forked.facets(), session.facets());
+
+        forked.activate(&["c".to_owned()])?;
+        assert_eq!(session.route().0, vec!["b".to_owned()]);
+        assert_eq!(forked.count()?, 1);
+        assert_eq!(session.count()?, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,390 @@
+//! Full-screen terminal UI for browsing facets, see `:tui`.
+//!
+//! Panes show the route, the current facets grouped by predicate along with their weights and
+//! zooms under the session's mode, and answer sets under the route. The selected facet is
+//! activated by `Enter` or `+`, its exclusion by `~` or `-`, and every pane is updated.
+
+use std::collections::BTreeMap;
+use std::io::{stdin, stdout, IsTerminal};
+
+use ncurses::*;
+
+use crate::interrupt;
+use crate::navigator::NavigatorError;
+use crate::session::Session;
+
+type Result<T> = std::result::Result<T, NavigatorError>;
+
+const KEYS: &str =
+    "j/k select  enter/+ activate  ~/- exclude  u undo  r redo  c clear route  q quit";
+/// rows skipped by page up and page down
+const PAGE: isize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Select(isize),
+    Activate,
+    Exclude,
+    Undo,
+    Redo,
+    Clear,
+    Quit,
+}
+
+fn action(key: i32) -> Option<Action> {
+    match key {
+        KEY_DOWN => Some(Action::Select(1)),
+        KEY_UP => Some(Action::Select(-1)),
+        KEY_NPAGE => Some(Action::Select(PAGE)),
+        KEY_PPAGE => Some(Action::Select(-PAGE)),
+        KEY_ENTER => Some(Action::Activate),
+        _ => match u8::try_from(key).ok().map(char::from)? {
+            'j' => Some(Action::Select(1)),
+            'k' => Some(Action::Select(-1)),
+            '\n' | '\r' | '+' => Some(Action::Activate),
+            '~' | '-' => Some(Action::Exclude),
+            'u' => Some(Action::Undo),
+            'r' => Some(Action::Redo),
+            'c' => Some(Action::Clear),
+            'q' => Some(Action::Quit),
+            _ => None,
+        },
+    }
+}
+
+/// Line of the facet pane.
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    /// signature of the facets below, e.g., `in/3`
+    Predicate(String),
+    /// facet along with the weights and zooms of it and of its exclusion, if known
+    Facet {
+        facet: String,
+        weights: [Option<f64>; 2],
+        zooms: [Option<f64>; 2],
+    },
+}
+
+/// Groups `facets`, given along with their signatures, by predicate.
+fn rows(
+    facets: Vec<(String, String)>,
+    weights: &[(String, f64)],
+    zooms: &[(String, f64)],
+) -> Vec<Row> {
+    let lookup = |values: &[(String, f64)], facet: &str| {
+        let exclusive = format!("~{}", facet);
+        [facet, exclusive.as_str()]
+            .map(|f| values.iter().find(|(g, _)| g == f).map(|(_, value)| *value))
+    };
+
+    let mut predicates: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (signature, facet) in facets {
+        predicates.entry(signature).or_default().push(facet);
+    }
+
+    let mut rows = vec![];
+    for (signature, facets) in predicates {
+        rows.push(Row::Predicate(signature));
+        for facet in facets {
+            rows.push(Row::Facet {
+                weights: lookup(weights, &facet),
+                zooms: lookup(zooms, &facet),
+                facet,
+            });
+        }
+    }
+
+    rows
+}
+
+fn values(label: &str, values: &[Option<f64>; 2]) -> String {
+    match values {
+        [Some(v), Some(w)] => format!("  {} {:.2} ~{:.2}", label, v, w),
+        [Some(v), _] => format!("  {} {:.2}", label, v),
+        _ => String::new(),
+    }
+}
+
+/// Breaks `atoms` into lines of at most `width` chars, unless a single atom is longer.
+fn wrap(atoms: &[String], width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for atom in atoms {
+        let line = lines.last_mut().expect("no line.");
+        match line.is_empty() {
+            true => line.push_str(atom),
+            _ if line.chars().count() + 1 + atom.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(atom);
+            }
+            _ => lines.push(atom.clone()),
+        }
+    }
+
+    lines
+}
+
+fn put(y: usize, x: usize, s: &str, width: usize) {
+    mvaddstr(
+        y as i32,
+        x as i32,
+        &s.chars().take(width).collect::<String>(),
+    );
+}
+
+struct Tui {
+    rows: Vec<Row>,
+    samples: Vec<Vec<String>>,
+    /// index of the selected row, which is a facet unless there are none
+    selected: usize,
+    /// first row shown in the facet pane
+    top: usize,
+    message: String,
+}
+impl Tui {
+    fn new() -> Self {
+        Self {
+            rows: vec![],
+            samples: vec![],
+            selected: 0,
+            top: 0,
+            message: String::new(),
+        }
+    }
+
+    /// Recomputes every pane after the route changed.
+    fn update(&mut self, session: &mut Session) {
+        self.message = "solving...".to_owned();
+        self.draw(session);
+
+        interrupt::reset();
+        self.message = match self.load(session) {
+            Ok(()) if interrupt::interrupted() => "interrupted, results are partial".to_owned(),
+            Ok(()) => String::new(),
+            Err(e) => e.to_string(),
+        };
+        self.selected = 0;
+        self.select(0);
+
+        // progress bars may have been drawn to the terminal meanwhile
+        clear();
+    }
+
+    fn load(&mut self, session: &mut Session) -> Result<()> {
+        let facets = session
+            .navigator()
+            .current_facets
+            .0
+            .iter()
+            .zip(session.facets())
+            .map(|(symbol, facet)| -> Result<(String, String)> {
+                let signature = format!("{}/{}", symbol.name()?, symbol.arguments()?.len());
+                Ok((signature, facet))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.rows = rows(facets.clone(), &[], &[]);
+        self.samples = vec![];
+
+        self.samples = session.answer_sets(session.navigator().n.max(1))?;
+        let weights = session.weights()?;
+        let zooms = session.zooms()?;
+        self.rows = rows(facets, &weights, &zooms);
+
+        Ok(())
+    }
+
+    /// Moves the selection by `offset` facets, stopping at the first and the last facet.
+    fn select(&mut self, offset: isize) {
+        let facets = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, Row::Facet { .. }))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if facets.is_empty() {
+            self.selected = 0;
+            return;
+        }
+
+        let current = facets
+            .iter()
+            .position(|i| *i >= self.selected)
+            .unwrap_or(facets.len() - 1);
+        let next = (current as isize + offset).clamp(0, facets.len() as isize - 1);
+        self.selected = facets[next as usize];
+    }
+
+    fn facet(&self) -> Option<&str> {
+        match self.rows.get(self.selected) {
+            Some(Row::Facet { facet, .. }) => Some(facet),
+            _ => None,
+        }
+    }
+
+    fn draw(&mut self, session: &Session) {
+        let (mut height, mut width) = (0, 0);
+        getmaxyx(stdscr(), &mut height, &mut width);
+        let (height, width) = (height.max(5) as usize, width.max(20) as usize);
+        let (pane, split) = (height - 4, width * 3 / 5);
+
+        erase();
+        let route = format!(
+            "route: {}  [{:.2}]  {}",
+            session.route().0.join(" "),
+            session.pace(),
+            session.mode()
+        );
+        put(0, 0, &route, width);
+        put(1, 0, &"-".repeat(width), width);
+
+        // facets
+        if self.rows.is_empty() {
+            put(2, 0, "no current facets", split);
+        }
+        self.top = self
+            .top
+            .min(self.selected)
+            .max((self.selected + 1).saturating_sub(pane));
+        for (i, row) in self.rows.iter().enumerate().skip(self.top).take(pane) {
+            let line = match row {
+                Row::Predicate(signature) => signature.clone(),
+                Row::Facet {
+                    facet,
+                    weights,
+                    zooms,
+                } => format!(
+                    "{} {}{}{}",
+                    if i == self.selected { ">" } else { " " },
+                    facet,
+                    values("w", weights),
+                    values("z", zooms)
+                ),
+            };
+            put(i - self.top + 2, 0, &line, split - 1);
+        }
+
+        // answer sets
+        let lines = self
+            .samples
+            .iter()
+            .enumerate()
+            .flat_map(|(i, answer_set)| {
+                std::iter::once(format!("answer {}:", i + 1))
+                    .chain(wrap(answer_set, width - split - 1))
+            })
+            .take(pane);
+        for (y, line) in lines.enumerate() {
+            put(y + 2, split, &line, width - split);
+        }
+
+        put(height - 2, 0, &self.message, width);
+        put(height - 1, 0, KEYS, width);
+        refresh();
+    }
+}
+
+/// Browses the facets of `session` full-screen until `q` is pressed; errors of single actions
+/// are shown in the status line.
+pub(crate) fn run(session: &mut Session) -> Result<()> {
+    if !stdin().is_terminal() || !stdout().is_terminal() {
+        return Err(NavigatorError::Unsupported(
+            "the terminal UI requires a terminal.".to_owned(),
+        ));
+    }
+
+    setlocale(LcCategory::all, "");
+    initscr();
+    // keeps `Ctrl-C` interrupting long running updates
+    cbreak();
+    noecho();
+    keypad(stdscr(), true);
+    curs_set(CURSOR_VISIBILITY::CURSOR_INVISIBLE);
+
+    let mut tui = Tui::new();
+    tui.update(session);
+    loop {
+        tui.draw(session);
+
+        let result = match action(getch()) {
+            Some(Action::Quit) => break,
+            Some(Action::Select(offset)) => {
+                tui.select(offset);
+                continue;
+            }
+            Some(Action::Activate) => match tui.facet() {
+                Some(facet) => session.activate(&[facet.to_owned()]),
+                _ => continue,
+            },
+            Some(Action::Exclude) => match tui.facet() {
+                Some(facet) => session.activate(&[format!("~{}", facet)]),
+                _ => continue,
+            },
+            Some(Action::Undo) => session.undo().map(|_| ()),
+            Some(Action::Redo) => session.redo().map(|_| ()),
+            Some(Action::Clear) => session.clear_route(),
+            _ => continue,
+        };
+
+        match result {
+            Ok(()) => tui.update(session),
+            Err(e) => tui.message = e.to_string(),
+        }
+    }
+    endwin();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn group_facets() {
+        let facets = vec![
+            ("saw/2".to_owned(), "saw(3,b)".to_owned()),
+            ("in/3".to_owned(), "in(1,r,r)".to_owned()),
+            ("saw/2".to_owned(), "saw(1,r)".to_owned()),
+        ];
+        let weights = vec![
+            ("saw(3,b)".to_owned(), 0.5),
+            ("~saw(3,b)".to_owned(), 0.25),
+            ("in(1,r,r)".to_owned(), 1.0),
+        ];
+
+        let mut tui = Tui::new();
+        tui.rows = rows(facets, &weights, &[]);
+        assert_eq!(tui.rows.len(), 5);
+        assert_eq!(tui.rows[0], Row::Predicate("in/3".to_owned()));
+        assert_eq!(
+            tui.rows[3],
+            Row::Facet {
+                facet: "saw(3,b)".to_owned(),
+                weights: [Some(0.5), Some(0.25)],
+                zooms: [None, None],
+            }
+        );
+
+        // predicates are skipped by the selection
+        tui.select(0);
+        assert_eq!(tui.facet(), Some("in(1,r,r)"));
+        tui.select(1);
+        assert_eq!(tui.facet(), Some("saw(3,b)"));
+        tui.select(PAGE);
+        assert_eq!(tui.facet(), Some("saw(1,r)"));
+        tui.select(-PAGE);
+        assert_eq!(tui.facet(), Some("in(1,r,r)"));
+
+        assert_eq!(action('~' as i32), Some(Action::Exclude));
+        assert_eq!(action(KEY_DOWN), Some(Action::Select(1)));
+        assert_eq!(action('x' as i32), None);
+    }
+
+    #[test]
+    fn wrap_atoms() {
+        let atoms = ["a(1)", "b(2)", "c(3)"].map(|a| a.to_owned());
+        assert_eq!(wrap(&atoms, 9), vec!["a(1) b(2)", "c(3)"]);
+        assert_eq!(wrap(&atoms, 2), vec!["a(1)", "b(2)", "c(3)"]);
+        assert_eq!(wrap(&[], 9), vec![""]);
+    }
+}
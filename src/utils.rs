@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use clingo::Symbol;
+use serde::{Serialize, Serializer};
 
 pub trait ToHashSet<T> {
     fn to_hashset(&self) -> HashSet<T>;
@@ -79,6 +80,12 @@ impl AsRef<[Symbol]> for Facets {
         &self.0
     }
 }
+/// Facets serialize as the sequence of their representations, e.g., `["a", "b(1)"]`.
+impl Serialize for Facets {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.to_strings())
+    }
+}
 #[cfg(not(tarpaulin_include))]
 impl std::fmt::Display for Facets {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -94,7 +101,7 @@ impl std::fmt::Display for Facets {
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize)]
 pub struct Route(pub Vec<String>);
 impl Route {
     pub fn activate(&mut self, facet: impl Into<String>) {
@@ -319,6 +326,19 @@ mod tests {
         );
     }
     #[test]
+    fn serialize() {
+        let facets = Facets(vec!["a".symbol(), "b".symbol()]);
+        assert_eq!(
+            serde_json::to_string(&facets).expect("serializing facets failed."),
+            r#"["a","b"]"#
+        );
+        let route = Route(vec!["a".to_owned(), "~b".to_owned()]);
+        assert_eq!(
+            serde_json::to_string(&route).expect("serializing route failed."),
+            r#"["a","~b"]"#
+        );
+    }
+    #[test]
     fn is_empty_len() {
         let facets = Facets(vec![]);
         assert!(facets.is_empty());